@@ -39,6 +39,7 @@ function startClient(context: vscode.ExtensionContext) {
     const clientOptions: LanguageClientOptions = {
         documentSelector: [{ scheme: 'file', language: 'rust' }],
         synchronize: {
+            configurationSection: 'cargo-perf',
             fileEvents: vscode.workspace.createFileSystemWatcher('**/*.rs')
         }
     };
@@ -7,7 +7,7 @@
 
 use cargo_perf::engine::AnalysisContext;
 use cargo_perf::plugin::{analyze_with_plugins, PluginRegistry};
-use cargo_perf::rules::{Diagnostic, Rule, Severity};
+use cargo_perf::rules::{Confidence, Diagnostic, Explanation, Rule, Severity};
 use cargo_perf::Config;
 use std::path::Path;
 use syn::visit::Visit;
@@ -36,6 +36,26 @@ impl Rule for NoUnwrapRule {
         Severity::Warning
     }
 
+    // Optional metadata: used by `--strict`, rule groups, SARIF and the LSP.
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["panic", "error-handling"]
+    }
+
+    // Rendered by `cargo perf explain`; every field but the rationale is optional.
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale: "`.unwrap()` panics on `None`/`Err`, turning a recoverable error into a \
+                crash. Propagate the error with `?` or document the invariant with `.expect()`.",
+            bad_example: Some("let config = std::fs::read_to_string(path).unwrap();"),
+            good_example: Some("let config = std::fs::read_to_string(path)?;"),
+            ..Explanation::default()
+        }
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
         let mut visitor = UnwrapVisitor {
             ctx,
@@ -73,6 +93,7 @@ impl<'ast> Visit<'ast> for UnwrapVisitor<'_> {
                     "Use `.expect(\"descriptive message\")` or proper error handling".to_string(),
                 ),
                 fix: None,
+                target: None,
             });
         }
 
@@ -0,0 +1,999 @@
+//! Baseline support for ignoring known issues.
+//!
+//! A baseline file records the fingerprints of existing diagnostics so they
+//! can be filtered out of subsequent runs. This is useful for:
+//!
+//! - Gradually adopting cargo-perf in an existing codebase
+//! - Tracking technical debt without noise
+//! - CI pipelines that should only fail on new issues
+//!
+//! # Fingerprinting Strategy
+//!
+//! Diagnostics are fingerprinted using:
+//! - Rule ID
+//! - Relative file path
+//! - The path of the enclosing item, like `crate::api::handlers::list_users`
+//! - A hash of the tokens of the flagged expression
+//! - Which occurrence of those tokens in the item it is
+//!
+//! This means diagnostics remain matched even if:
+//! - Lines are added/removed elsewhere in the file
+//! - The code is reformatted or commented
+//! - Neighboring code changes
+//!
+//! But will be treated as new if:
+//! - The problematic code itself changes
+//! - Its item or file is renamed or moved
+//! - The rule ID changes
+//!
+//! # Entry Metadata
+//!
+//! Entries may name an `owner`, a `justification` and a tracking `ticket`,
+//! and carry an `expires` date. Once that date has passed, the entry no
+//! longer hides its diagnostic, so accepted debt is revisited.
+//!
+//! Version 1 baselines hashed the three source lines around the diagnostic
+//! instead. They are still read, and [`Baseline::migrate`] converts them.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::Diagnostic;
+
+mod structural;
+
+pub use structural::SourceStructure;
+
+/// Default baseline filename
+pub const BASELINE_FILENAME: &str = ".cargo-perf-baseline";
+
+/// Baseline version written by this release. Version 1 used line
+/// fingerprints, version 2 structural ones.
+pub const BASELINE_VERSION: u32 = 2;
+
+/// First baseline version with structural fingerprints
+const STRUCTURAL_VERSION: u32 = 2;
+
+/// Maximum baseline file size (10 MB) - prevents memory exhaustion from malformed files
+const MAX_BASELINE_SIZE: u64 = 10 * 1024 * 1024;
+
+/// A fingerprint that uniquely identifies a diagnostic
+#[derive(Debug, Clone, Hash, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    /// The rule that produced this diagnostic
+    pub rule_id: String,
+    /// Relative path to the file
+    pub file_path: String,
+    /// Path of the enclosing item (structural fingerprints only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub item_path: Option<String>,
+    /// Hash of the flagged expression's tokens, or of the source lines
+    /// around the diagnostic for line fingerprints
+    pub code_hash: u64,
+    /// Index among expressions with the same tokens in the item (structural
+    /// fingerprints only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub occurrence: Option<usize>,
+}
+
+impl Fingerprint {
+    /// Create a structural fingerprint from a diagnostic and its source file
+    pub fn from_diagnostic(diag: &Diagnostic, root: &Path) -> Option<Self> {
+        let source = fs::read_to_string(&diag.file_path).ok()?;
+        Self::from_source(diag, root, &SourceStructure::parse(&source)?)
+    }
+
+    /// Create a structural fingerprint from a diagnostic and its parsed
+    /// source file.
+    ///
+    /// This is more efficient when processing multiple diagnostics from
+    /// the same file, as the file is parsed once and reused.
+    pub fn from_source(diag: &Diagnostic, root: &Path, source: &SourceStructure) -> Option<Self> {
+        let relative = diag.file_path.strip_prefix(root).unwrap_or(&diag.file_path);
+        let site = source.site(diag.line, diag.column)?;
+
+        let mut item_path = structural::module_path(relative);
+        if !site.item_path.is_empty() {
+            item_path.push_str("::");
+            item_path.push_str(&site.item_path);
+        }
+
+        Some(Fingerprint {
+            rule_id: diag.rule_id.to_string(),
+            file_path: relative.to_string_lossy().to_string(),
+            item_path: Some(item_path),
+            code_hash: site.code_hash,
+            occurrence: Some(site.occurrence),
+        })
+    }
+
+    /// Create a line fingerprint, as version 1 baselines use, from a
+    /// diagnostic and the lines of its source file.
+    pub fn from_lines(diag: &Diagnostic, root: &Path, lines: &[&str]) -> Option<Self> {
+        let file_path = diag
+            .file_path
+            .strip_prefix(root)
+            .unwrap_or(&diag.file_path)
+            .to_string_lossy()
+            .to_string();
+
+        // Use cached lines for hashing
+        let code_hash = Self::hash_source_context_with_cache(lines, diag.line)?;
+
+        Some(Fingerprint {
+            rule_id: diag.rule_id.to_string(),
+            file_path,
+            item_path: None,
+            code_hash,
+            occurrence: None,
+        })
+    }
+
+    /// Create a line fingerprint from a diagnostic and the lines of its
+    /// source file.
+    #[deprecated(note = "renamed to `Fingerprint::from_lines`")]
+    pub fn from_diagnostic_with_cache(
+        diag: &Diagnostic,
+        root: &Path,
+        lines: &[&str],
+    ) -> Option<Self> {
+        Self::from_lines(diag, root, lines)
+    }
+
+    /// A stable string hash using FNV-1a algorithm.
+    /// This is guaranteed stable across Rust versions and platforms.
+    fn stable_hash(s: &str) -> u64 {
+        // FNV-1a constants for 64-bit
+        const FNV_OFFSET: u64 = 14695981039346656037;
+        const FNV_PRIME: u64 = 1099511628211;
+
+        let mut hash = FNV_OFFSET;
+        for byte in s.bytes() {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(FNV_PRIME);
+        }
+        hash
+    }
+
+    /// Hash source context with cached file content
+    pub(crate) fn hash_source_context_with_cache(
+        lines: &[&str],
+        target_line: usize,
+    ) -> Option<u64> {
+        if target_line == 0 || target_line > lines.len() {
+            return None;
+        }
+
+        // Get 3 lines of context: line-1, line, line+1 (1-indexed)
+        // For target_line=5: want lines 4,5,6 (1-indexed) = indices 3,4,5 (0-indexed) = [3..6]
+        let start = target_line.saturating_sub(2); // line-1 in 0-indexed
+        let end = (target_line + 1).min(lines.len()); // line+1 in 0-indexed (exclusive)
+
+        let mut context = String::new();
+        for line in &lines[start..end] {
+            context.push_str(line.trim());
+            context.push('\n');
+        }
+
+        if context.is_empty() {
+            return None;
+        }
+
+        Some(Self::stable_hash(&context))
+    }
+}
+
+/// A baseline entry with human-readable metadata
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BaselineEntry {
+    /// The diagnostic fingerprint
+    pub fingerprint: Fingerprint,
+    /// Human-readable description
+    pub description: String,
+    /// When this entry was added
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub added: Option<String>,
+    /// Person or team responsible for the issue
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    /// Why the issue is accepted for now
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub justification: Option<String>,
+    /// Tracking ticket ID
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ticket: Option<String>,
+    /// Last day (`YYYY-MM-DD`, UTC) the entry applies; afterwards the
+    /// diagnostic is reported again
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+}
+
+impl BaselineEntry {
+    fn new(fingerprint: Fingerprint, description: String) -> Self {
+        BaselineEntry {
+            fingerprint,
+            description,
+            added: Some(today()),
+            owner: None,
+            justification: None,
+            ticket: None,
+            expires: None,
+        }
+    }
+
+    /// The entry for `fingerprint` with this entry's description and metadata
+    fn with_fingerprint(&self, fingerprint: Fingerprint) -> Self {
+        BaselineEntry {
+            fingerprint,
+            ..self.clone()
+        }
+    }
+
+    /// Whether the entry's expiry date has passed
+    pub fn is_expired(&self) -> bool {
+        self.expired_on(&today())
+    }
+
+    /// Whether the entry's expiry date is before `date` (`YYYY-MM-DD`)
+    fn expired_on(&self, date: &str) -> bool {
+        // Zero-padded ISO dates order like the days they name
+        self.expires
+            .as_deref()
+            .is_some_and(|expires| expires < date)
+    }
+}
+
+/// Diagnostics split by a baseline, from [`Baseline::partition`].
+#[derive(Debug, Default)]
+pub struct Partition<'a> {
+    /// Diagnostics not in the baseline
+    pub new: Vec<Diagnostic>,
+    /// Diagnostics the baseline matched
+    pub baselined: Vec<Diagnostic>,
+    /// Entries that matched no diagnostic, usually because the issue was fixed
+    pub stale: Vec<&'a BaselineEntry>,
+    /// Expired entries that matched a diagnostic, which is reported as new again
+    pub expired: Vec<&'a BaselineEntry>,
+}
+
+/// A collection of baselined diagnostics
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Baseline {
+    /// Format version, which also selects the fingerprint kind
+    pub version: u32,
+    /// Baselined entries
+    pub entries: Vec<BaselineEntry>,
+
+    /// Cached set of fingerprints for O(1) lookup
+    #[serde(skip)]
+    fingerprints: HashSet<Fingerprint>,
+    /// Fingerprints of expired entries, which no longer hide diagnostics
+    #[serde(skip)]
+    expired: HashSet<Fingerprint>,
+}
+
+impl Baseline {
+    /// Create a new empty baseline
+    pub fn new() -> Self {
+        Baseline {
+            version: BASELINE_VERSION,
+            entries: Vec::new(),
+            fingerprints: HashSet::new(),
+            expired: HashSet::new(),
+        }
+    }
+
+    /// Load a baseline from the default location
+    pub fn load(root: &Path) -> std::io::Result<Self> {
+        Self::load_from(root.join(BASELINE_FILENAME))
+    }
+
+    /// Load a baseline from a specific path
+    pub fn load_from(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        // Check file size before reading to prevent memory exhaustion
+        let metadata = fs::metadata(path.as_ref())?;
+        if metadata.len() > MAX_BASELINE_SIZE {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Baseline file too large ({} bytes, max {} bytes)",
+                    metadata.len(),
+                    MAX_BASELINE_SIZE
+                ),
+            ));
+        }
+
+        let content = fs::read_to_string(path.as_ref())?;
+        let mut baseline: Baseline = serde_json::from_str(&content)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        if baseline.version > BASELINE_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Baseline version {} is newer than supported ({})",
+                    baseline.version, BASELINE_VERSION
+                ),
+            ));
+        }
+
+        if let Some(entry) = baseline
+            .entries
+            .iter()
+            .find(|e| e.expires.as_deref().is_some_and(|d| !is_valid_date(d)))
+        {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Invalid expiry date \"{}\" for {} (expected YYYY-MM-DD)",
+                    entry.expires.as_deref().unwrap_or_default(),
+                    entry.description
+                ),
+            ));
+        }
+
+        // Build lookup cache
+        baseline.rebuild_lookup();
+
+        Ok(baseline)
+    }
+
+    /// Save the baseline to the default location
+    pub fn save(&self, root: &Path) -> std::io::Result<()> {
+        self.save_to(root.join(BASELINE_FILENAME))
+    }
+
+    /// Save the baseline to a specific path
+    pub fn save_to(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        fs::write(path.as_ref(), content)?;
+        Ok(())
+    }
+
+    /// Check if a diagnostic is in the baseline
+    pub fn contains(&self, diag: &Diagnostic, root: &Path) -> bool {
+        let Ok(source) = fs::read_to_string(&diag.file_path) else {
+            return false;
+        };
+        self.fingerprint_all([diag], root, &source)
+            .pop()
+            .flatten()
+            .is_some_and(|fp| self.hides(&fp))
+    }
+
+    /// Check which of `diagnostics`, all from one file with content `source`,
+    /// are in the baseline.
+    ///
+    /// Used for unsaved editor buffers, whose content differs from disk.
+    pub fn contains_in_source(
+        &self,
+        diagnostics: &[Diagnostic],
+        root: &Path,
+        source: &str,
+    ) -> Vec<bool> {
+        self.fingerprint_all(diagnostics, root, source)
+            .iter()
+            .map(|fp| fp.as_ref().is_some_and(|fp| self.hides(fp)))
+            .collect()
+    }
+
+    /// Whether this baseline uses fingerprints of an older version, and
+    /// should be migrated.
+    pub fn needs_migration(&self) -> bool {
+        self.version < BASELINE_VERSION
+    }
+
+    /// Add a diagnostic to the baseline
+    pub fn add(&mut self, diag: &Diagnostic, root: &Path) {
+        let Ok(source) = fs::read_to_string(&diag.file_path) else {
+            return;
+        };
+        if let Some(Some(fingerprint)) = self.fingerprint_all([diag], root, &source).pop() {
+            self.insert(BaselineEntry::new(fingerprint, describe(diag)));
+        }
+    }
+
+    /// Add diagnostics to the baseline, reading and parsing each of their
+    /// files once.
+    pub fn add_all(&mut self, diagnostics: &[Diagnostic], root: &Path) {
+        let fingerprints = self.fingerprint_files(diagnostics, root);
+        for (diag, fingerprint) in diagnostics.iter().zip(fingerprints) {
+            if let Some(fingerprint) = fingerprint {
+                self.insert(BaselineEntry::new(fingerprint, describe(diag)));
+            }
+        }
+    }
+
+    /// Create a baseline from a list of diagnostics
+    ///
+    /// This method uses file caching to avoid re-reading the same file
+    /// when multiple diagnostics come from the same source file.
+    pub fn from_diagnostics(diagnostics: &[Diagnostic], root: &Path) -> Self {
+        let mut baseline = Baseline::new();
+        baseline.add_all(diagnostics, root);
+        baseline
+    }
+
+    /// Convert a baseline of an older version to the current fingerprints.
+    ///
+    /// Old fingerprints can only be recomputed from the code they point at,
+    /// so each entry is carried over, with its description and metadata, for
+    /// the current `diagnostics` it matches. Entries matching none are
+    /// dropped as fixed, but only if `covered` says the run that produced
+    /// `diagnostics` would have found them.
+    ///
+    /// # Errors
+    ///
+    /// Fails, naming how many, if entries matching no diagnostic are not
+    /// `covered`, such as those of other packages or of rules that did not
+    /// run; migrating would lose them.
+    pub fn migrate(
+        self,
+        diagnostics: &[Diagnostic],
+        root: &Path,
+        mut covered: impl FnMut(&BaselineEntry) -> bool,
+    ) -> std::io::Result<Self> {
+        if !self.needs_migration() {
+            return Ok(self);
+        }
+        let mut entries: HashMap<&Fingerprint, &BaselineEntry> = self
+            .entries
+            .iter()
+            .map(|entry| (&entry.fingerprint, entry))
+            .collect();
+        let mut migrated = Baseline::new();
+
+        let old = self.fingerprint_files(diagnostics, root);
+        let new = migrated.fingerprint_files(diagnostics, root);
+        for (old, new) in old.into_iter().zip(new) {
+            let (Some(old), Some(new)) = (old, new) else {
+                continue;
+            };
+            if let Some(entry) = entries.remove(&old) {
+                migrated.insert(entry.with_fingerprint(new));
+            }
+        }
+
+        let uncovered = entries
+            .into_values()
+            .filter(|entry| !covered(entry))
+            .count();
+        if uncovered > 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidInput,
+                format!(
+                    "{} baseline entries match no finding of this run, which did not cover \
+                     their files or rules",
+                    uncovered
+                ),
+            ));
+        }
+        Ok(migrated)
+    }
+
+    /// Filter diagnostics, removing those in the baseline
+    pub fn filter(&self, diagnostics: Vec<Diagnostic>, root: &Path) -> Vec<Diagnostic> {
+        self.partition(diagnostics, root).new
+    }
+
+    /// Split diagnostics into new and baselined ones, and find the entries
+    /// that matched none of them.
+    ///
+    /// Diagnostics that cannot be fingerprinted, for instance because their
+    /// file cannot be read, count as new, as do those of expired entries.
+    pub fn partition(&self, diagnostics: Vec<Diagnostic>, root: &Path) -> Partition<'_> {
+        let fingerprints = self.fingerprint_files(&diagnostics, root);
+        let mut partition = Partition::default();
+        let mut matched = HashSet::new();
+
+        for (diag, fingerprint) in diagnostics.into_iter().zip(fingerprints) {
+            match fingerprint.filter(|fp| self.fingerprints.contains(fp)) {
+                Some(fingerprint) => {
+                    if self.expired.contains(&fingerprint) {
+                        partition.new.push(diag);
+                    } else {
+                        partition.baselined.push(diag);
+                    }
+                    matched.insert(fingerprint);
+                }
+                None => partition.new.push(diag),
+            }
+        }
+        for entry in &self.entries {
+            if !matched.contains(&entry.fingerprint) {
+                partition.stale.push(entry);
+            } else if self.expired.contains(&entry.fingerprint) {
+                partition.expired.push(entry);
+            }
+        }
+
+        partition
+    }
+
+    /// Entries grouped by owner, in owner order with unowned entries first
+    pub fn by_owner(&self) -> BTreeMap<Option<&str>, Vec<&BaselineEntry>> {
+        let mut groups: BTreeMap<Option<&str>, Vec<&BaselineEntry>> = BTreeMap::new();
+        for entry in &self.entries {
+            groups
+                .entry(entry.owner.as_deref())
+                .or_default()
+                .push(entry);
+        }
+        groups
+    }
+
+    /// Keep only the entries for which `keep` returns true, returning how
+    /// many were removed.
+    pub fn retain(&mut self, mut keep: impl FnMut(&BaselineEntry) -> bool) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|entry| keep(entry));
+        self.rebuild_lookup();
+        before - self.entries.len()
+    }
+
+    /// Set the owner, justification, ticket and expiry date that are `Some`
+    /// in `metadata` on the entries from index `start` on, such as those just
+    /// added.
+    pub fn annotate_from(&mut self, start: usize, metadata: &EntryMetadata) {
+        for entry in self.entries.iter_mut().skip(start) {
+            let fields = [
+                (&mut entry.owner, &metadata.owner),
+                (&mut entry.justification, &metadata.justification),
+                (&mut entry.ticket, &metadata.ticket),
+                (&mut entry.expires, &metadata.expires),
+            ];
+            for (field, value) in fields {
+                if value.is_some() {
+                    field.clone_from(value);
+                }
+            }
+        }
+        self.rebuild_lookup();
+    }
+
+    /// Whether `fingerprint` belongs to an entry that has not expired
+    fn hides(&self, fingerprint: &Fingerprint) -> bool {
+        self.fingerprints.contains(fingerprint) && !self.expired.contains(fingerprint)
+    }
+
+    /// Rebuild the fingerprint lookup sets from the entries
+    fn rebuild_lookup(&mut self) {
+        let today = today();
+        self.fingerprints = self.entries.iter().map(|e| e.fingerprint.clone()).collect();
+        self.expired = self
+            .entries
+            .iter()
+            .filter(|e| e.expired_on(&today))
+            .map(|e| e.fingerprint.clone())
+            .collect();
+    }
+
+    /// Fingerprints of `diagnostics`, in order, reading and parsing each of
+    /// their files once.
+    fn fingerprint_files(
+        &self,
+        diagnostics: &[Diagnostic],
+        root: &Path,
+    ) -> Vec<Option<Fingerprint>> {
+        // Group diagnostics by file path for efficient caching
+        let mut by_file: HashMap<&PathBuf, Vec<usize>> = HashMap::new();
+        for (i, diag) in diagnostics.iter().enumerate() {
+            by_file.entry(&diag.file_path).or_default().push(i);
+        }
+
+        let mut fingerprints = vec![None; diagnostics.len()];
+        for (file_path, indices) in by_file {
+            let Ok(source) = fs::read_to_string(file_path) else {
+                continue;
+            };
+            let file_diagnostics = indices.iter().map(|&i| &diagnostics[i]);
+            for (i, fingerprint) in
+                indices
+                    .iter()
+                    .zip(self.fingerprint_all(file_diagnostics, root, &source))
+            {
+                fingerprints[*i] = fingerprint;
+            }
+        }
+        fingerprints
+    }
+
+    /// Fingerprints of `diagnostics`, all from one file with content `source`,
+    /// of the kind this baseline's version uses.
+    fn fingerprint_all<'d>(
+        &self,
+        diagnostics: impl IntoIterator<Item = &'d Diagnostic>,
+        root: &Path,
+        source: &str,
+    ) -> Vec<Option<Fingerprint>> {
+        if self.version < STRUCTURAL_VERSION {
+            let lines: Vec<&str> = source.lines().collect();
+            return diagnostics
+                .into_iter()
+                .map(|diag| Fingerprint::from_lines(diag, root, &lines))
+                .collect();
+        }
+        let structure = SourceStructure::parse(source);
+        diagnostics
+            .into_iter()
+            .map(|diag| Fingerprint::from_source(diag, root, structure.as_ref()?))
+            .collect()
+    }
+
+    fn insert(&mut self, entry: BaselineEntry) {
+        if !self.fingerprints.contains(&entry.fingerprint) {
+            // Clone needed: fingerprint goes into both HashSet and entry
+            self.fingerprints.insert(entry.fingerprint.clone());
+            if entry.is_expired() {
+                self.expired.insert(entry.fingerprint.clone());
+            }
+            self.entries.push(entry);
+        }
+    }
+
+    /// Number of entries in the baseline
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Check if baseline is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Human-readable description of a baselined diagnostic
+fn describe(diag: &Diagnostic) -> String {
+    format!(
+        "{}: {} ({}:{})",
+        diag.rule_id,
+        diag.message,
+        diag.file_path.display(),
+        diag.line
+    )
+}
+
+/// Owner, justification, ticket and expiry date to record on baseline
+/// entries, from [`Baseline::annotate_from`].
+#[derive(Debug, Clone, Default)]
+pub struct EntryMetadata {
+    /// Person or team responsible
+    pub owner: Option<String>,
+    /// Why the issues are accepted for now
+    pub justification: Option<String>,
+    /// Tracking ticket ID
+    pub ticket: Option<String>,
+    /// Last day (`YYYY-MM-DD`) the entries apply
+    pub expires: Option<String>,
+}
+
+/// Whether `date` is a calendar date in `YYYY-MM-DD` form
+pub fn is_valid_date(date: &str) -> bool {
+    let bytes = date.as_bytes();
+    if bytes.len() != 10 || bytes[4] != b'-' || bytes[7] != b'-' {
+        return false;
+    }
+    let number = |range: std::ops::Range<usize>| {
+        let digits = &date[range];
+        if digits.bytes().all(|b| b.is_ascii_digit()) {
+            digits.parse::<u32>().ok()
+        } else {
+            None
+        }
+    };
+    let (Some(year), Some(month), Some(day)) = (number(0..4), number(5..7), number(8..10)) else {
+        return false;
+    };
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return false,
+    };
+    (1..=days_in_month).contains(&day)
+}
+
+/// Today's UTC date as `YYYY-MM-DD`, without a chrono dependency.
+fn today() -> String {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    civil_date(secs / 86400)
+}
+
+/// The `YYYY-MM-DD` date `days` days after 1970-01-01, using Howard
+/// Hinnant's `civil_from_days` algorithm.
+fn civil_date(days: u64) -> String {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z % 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn create_test_diagnostic(rule_id: &'static str, file: PathBuf, line: usize) -> Diagnostic {
+        Diagnostic {
+            rule_id,
+            severity: crate::Severity::Warning,
+            message: "test message".to_string(),
+            file_path: file,
+            line,
+            column: 1,
+            end_line: None,
+            end_column: None,
+            suggestion: None,
+            fix: None,
+            target: None,
+        }
+    }
+
+    #[test]
+    fn test_fingerprint_stability() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("test.rs");
+        fs::write(&file, "fn main() {\n    println!(\"hello\");\n}\n").unwrap();
+
+        let diag = create_test_diagnostic("test-rule", file.clone(), 2);
+
+        let fp1 = Fingerprint::from_diagnostic(&diag, tmp.path()).unwrap();
+        let fp2 = Fingerprint::from_diagnostic(&diag, tmp.path()).unwrap();
+
+        assert_eq!(fp1, fp2);
+    }
+
+    #[test]
+    fn test_baseline_save_load() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("test.rs");
+        fs::write(
+            &file,
+            "fn f() {\n    let x = 1;\n    let y = x.clone();\n}\n",
+        )
+        .unwrap();
+
+        let diag = create_test_diagnostic("clone-in-hot-loop", file, 3);
+        let baseline = Baseline::from_diagnostics(&[diag], tmp.path());
+
+        baseline.save(tmp.path()).unwrap();
+        let loaded = Baseline::load(tmp.path()).unwrap();
+
+        assert_eq!(loaded.len(), 1);
+        assert_eq!(loaded.entries[0].fingerprint.rule_id, "clone-in-hot-loop");
+    }
+
+    #[test]
+    fn test_baseline_filtering() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("test.rs");
+        fs::write(&file, "fn f() {}\nfn g() {}\n").unwrap();
+
+        let diag1 = create_test_diagnostic("rule-a", file.clone(), 1);
+        let diag2 = create_test_diagnostic("rule-b", file, 2);
+
+        // Only baseline the first diagnostic
+        let baseline = Baseline::from_diagnostics(std::slice::from_ref(&diag1), tmp.path());
+
+        let filtered = baseline.filter(vec![diag1, diag2], tmp.path());
+        assert_eq!(filtered.len(), 1);
+        assert_eq!(filtered[0].rule_id, "rule-b");
+    }
+
+    #[test]
+    fn test_baseline_partition_and_retain() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("test.rs");
+        fs::write(&file, "fn f() {}\nfn g() {}\n").unwrap();
+
+        let diag1 = create_test_diagnostic("rule-a", file.clone(), 1);
+        let diag2 = create_test_diagnostic("rule-b", file.clone(), 2);
+        let mut baseline = Baseline::from_diagnostics(&[diag1.clone(), diag2], tmp.path());
+
+        // rule-b was fixed; a new rule-c finding appeared
+        let diag3 = create_test_diagnostic("rule-c", file, 2);
+        let partition = baseline.partition(vec![diag1, diag3], tmp.path());
+        assert_eq!(partition.new.len(), 1);
+        assert_eq!(partition.new[0].rule_id, "rule-c");
+        assert_eq!(partition.baselined.len(), 1);
+        assert_eq!(partition.stale.len(), 1);
+        assert_eq!(partition.stale[0].fingerprint.rule_id, "rule-b");
+
+        let removed = baseline.retain(|entry| entry.fingerprint.rule_id != "rule-b");
+        assert_eq!(removed, 1);
+        assert_eq!(baseline.len(), 1);
+    }
+
+    #[test]
+    fn test_baseline_contains_in_source() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("test.rs");
+        fs::write(&file, "fn f() {}\nfn g() {}\n").unwrap();
+
+        let diag = create_test_diagnostic("rule-a", file, 2);
+        let baseline = Baseline::from_diagnostics(std::slice::from_ref(&diag), tmp.path());
+
+        // Edits away from the finding keep it matched; edits to it do not
+        let mut shifted = diag.clone();
+        shifted.line = 3;
+        let moved = "// new\nfn f() {}\nfn g() {}\n";
+        assert_eq!(
+            baseline.contains_in_source(&[shifted], tmp.path(), moved),
+            vec![true]
+        );
+        let changed = "fn f() {}\nfn g() { h() }\n";
+        assert_eq!(
+            baseline.contains_in_source(&[diag], tmp.path(), changed),
+            vec![false]
+        );
+    }
+
+    #[test]
+    fn test_baseline_survives_reformatting() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("lib.rs");
+        fs::write(&file, "fn f(x: &String) {\n    let y = x.clone();\n}\n").unwrap();
+
+        let mut diag = create_test_diagnostic("rule-a", file, 2);
+        diag.column = 12;
+        let baseline = Baseline::from_diagnostics(std::slice::from_ref(&diag), tmp.path());
+        assert_eq!(
+            baseline.entries[0].fingerprint.item_path.as_deref(),
+            Some("crate::f")
+        );
+
+        // Comments, renamed neighbors and line breaks keep the finding matched
+        let reformatted = "// Clones x\nfn f(x: &String) {\n    let z = 1;\n    let y = x\n        .clone();\n}\n";
+        let mut moved = diag.clone();
+        moved.line = 4;
+        assert_eq!(
+            baseline.contains_in_source(&[moved], tmp.path(), reformatted),
+            vec![true]
+        );
+
+        // Changing the flagged expression does not
+        let changed = "fn f(x: &String) {\n    let y = x.to_owned();\n}\n";
+        assert_eq!(
+            baseline.contains_in_source(&[diag], tmp.path(), changed),
+            vec![false]
+        );
+    }
+
+    #[test]
+    fn test_baseline_migrate() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("lib.rs");
+        fs::write(&file, "fn f(x: &String) {\n    let y = x.clone();\n}\n").unwrap();
+
+        let mut diag = create_test_diagnostic("rule-a", file, 2);
+        diag.column = 12;
+        let stale = create_test_diagnostic("rule-b", tmp.path().join("gone.rs"), 1);
+
+        // A version 1 baseline, with line fingerprints
+        let mut old = Baseline {
+            version: 1,
+            ..Baseline::new()
+        };
+        old.add(&diag, tmp.path());
+        old.entries[0].owner = Some("perf-team".to_string());
+        old.entries.push(BaselineEntry {
+            fingerprint: Fingerprint {
+                rule_id: "rule-b".to_string(),
+                file_path: "gone.rs".to_string(),
+                item_path: None,
+                code_hash: 0,
+                occurrence: None,
+            },
+            description: "stale".to_string(),
+            added: None,
+            owner: None,
+            justification: None,
+            ticket: None,
+            expires: None,
+        });
+        old.save(tmp.path()).unwrap();
+
+        let old = Baseline::load(tmp.path()).unwrap();
+        assert!(old.needs_migration());
+        assert!(old.contains(&diag, tmp.path()));
+        assert_eq!(old.entries[0].fingerprint.item_path, None);
+
+        // The run did not cover the unmatched entry's rule
+        let err = Baseline::load(tmp.path())
+            .unwrap()
+            .migrate(&[diag.clone(), stale.clone()], tmp.path(), |entry| {
+                entry.fingerprint.rule_id != "rule-b"
+            })
+            .unwrap_err();
+        assert!(err.to_string().starts_with("1 baseline entries"));
+
+        let migrated = old
+            .migrate(&[diag.clone(), stale], tmp.path(), |_| true)
+            .unwrap();
+        assert!(!migrated.needs_migration());
+        assert_eq!(migrated.len(), 1);
+        assert!(migrated.entries[0].fingerprint.item_path.is_some());
+        assert_eq!(migrated.entries[0].owner.as_deref(), Some("perf-team"));
+        assert!(migrated.contains(&diag, tmp.path()));
+    }
+
+    #[test]
+    fn test_baseline_expired_entries() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("test.rs");
+        fs::write(&file, "fn f() {}\nfn g() {}\n").unwrap();
+
+        let diag1 = create_test_diagnostic("rule-a", file.clone(), 1);
+        let diag2 = create_test_diagnostic("rule-b", file, 2);
+        let mut baseline = Baseline::from_diagnostics(&[diag1.clone(), diag2.clone()], tmp.path());
+        baseline.annotate_from(
+            1,
+            &EntryMetadata {
+                owner: Some("storage".to_string()),
+                expires: Some("2000-01-01".to_string()),
+                ..EntryMetadata::default()
+            },
+        );
+        baseline.save(tmp.path()).unwrap();
+
+        let loaded = Baseline::load(tmp.path()).unwrap();
+        assert!(loaded.entries[1].is_expired());
+        assert!(loaded.contains(&diag1, tmp.path()));
+        assert!(!loaded.contains(&diag2, tmp.path()));
+
+        let partition = loaded.partition(vec![diag1, diag2], tmp.path());
+        assert_eq!(partition.baselined.len(), 1);
+        assert_eq!(partition.new.len(), 1);
+        assert_eq!(partition.new[0].rule_id, "rule-b");
+        assert_eq!(partition.expired.len(), 1);
+        assert!(partition.stale.is_empty());
+
+        let groups = loaded.by_owner();
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[&Some("storage")].len(), 1);
+        assert_eq!(groups[&None].len(), 1);
+    }
+
+    #[test]
+    fn test_baseline_rejects_invalid_expiry() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("test.rs");
+        fs::write(&file, "fn f() {}\n").unwrap();
+
+        let diag = create_test_diagnostic("rule-a", file, 1);
+        let mut baseline = Baseline::from_diagnostics(&[diag], tmp.path());
+        baseline.entries[0].expires = Some("next week".to_string());
+        baseline.save(tmp.path()).unwrap();
+
+        let err = Baseline::load(tmp.path()).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_dates() {
+        assert_eq!(civil_date(0), "1970-01-01");
+        assert_eq!(civil_date(11_016), "2000-02-29");
+        assert_eq!(civil_date(20_742), "2026-10-16");
+
+        assert!(is_valid_date("2024-02-29"));
+        assert!(!is_valid_date("2023-02-29"));
+        assert!(!is_valid_date("2026-13-01"));
+        assert!(!is_valid_date("2026-1-01"));
+        assert!(!is_valid_date("+026-01-01"));
+    }
+}
@@ -0,0 +1,415 @@
+//! Structural fingerprints.
+//!
+//! A diagnostic is identified by the path of the item it is in, a hash of the
+//! tokens of the expression it flags, and which occurrence of those tokens it
+//! is within the item. Tokens ignore whitespace and comments, so reformatting,
+//! comments and edits to neighboring code leave the fingerprint unchanged.
+
+use std::cell::OnceCell;
+use std::ops::Range;
+use std::path::{Component, Path};
+
+use proc_macro2::{Delimiter, LineColumn, TokenStream, TokenTree};
+use syn::spanned::Spanned;
+use syn::visit::{self, Visit};
+
+use super::Fingerprint;
+
+/// Where a diagnostic sits in the syntax of its file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Site {
+    /// Path of the enclosing item within the file, like `Handler::list_users`;
+    /// empty at the top level
+    pub item_path: String,
+    /// Hash of the flagged expression's tokens
+    pub code_hash: u64,
+    /// Earlier expressions with the same tokens in the same item
+    pub occurrence: usize,
+}
+
+/// A parsed source file, for structural fingerprints of the diagnostics in it.
+pub struct SourceStructure<'a> {
+    source: &'a str,
+    /// Items that can enclose a diagnostic, outer items before inner ones
+    scopes: Vec<Scope>,
+    /// Expressions and statements, parents before children
+    nodes: Vec<Node>,
+}
+
+struct Scope {
+    path: String,
+    start: LineColumn,
+    end: LineColumn,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum NodeKind {
+    Expr,
+    Stmt,
+}
+
+struct Node {
+    kind: NodeKind,
+    start: LineColumn,
+    end: LineColumn,
+    bytes: Range<usize>,
+    scope: Option<usize>,
+    /// Token hash, computed when first needed
+    hash: OnceCell<u64>,
+}
+
+impl Node {
+    fn contains(&self, position: LineColumn) -> bool {
+        self.start <= position && position < self.end
+    }
+
+    fn hash(&self, source: &str) -> u64 {
+        *self
+            .hash
+            .get_or_init(|| token_hash(&source[self.bytes.clone()]))
+    }
+}
+
+impl<'a> SourceStructure<'a> {
+    /// Parse `source`. `None` if it does not parse.
+    pub fn parse(source: &'a str) -> Option<Self> {
+        let file = syn::parse_file(source).ok()?;
+        let mut collector = Collector::default();
+        collector.visit_file(&file);
+        Some(Self {
+            source,
+            scopes: collector.scopes,
+            nodes: collector.nodes,
+        })
+    }
+
+    /// Locate the expression flagged at `line` (1-based) and `column`
+    /// (0-based), as rules report them.
+    ///
+    /// The flagged expression is the largest one starting at the position
+    /// within its statement, else the statement itself, else the innermost
+    /// expression around the position. Without any, the rest of the line is
+    /// used.
+    pub(crate) fn site(&self, line: usize, column: usize) -> Option<Site> {
+        let position = LineColumn { line, column };
+        let stmt = self
+            .nodes
+            .iter()
+            .rposition(|n| n.kind == NodeKind::Stmt && n.contains(position));
+        let within = |n: &Node| {
+            stmt.is_none_or(|s| n.start >= self.nodes[s].start && n.end <= self.nodes[s].end)
+        };
+        let flagged = self
+            .nodes
+            .iter()
+            .position(|n| n.kind == NodeKind::Expr && n.start == position && within(n))
+            .or(stmt)
+            .or_else(|| self.nodes.iter().rposition(|n| n.contains(position)));
+
+        let Some(index) = flagged else {
+            let rest = self.source.lines().nth(line.checked_sub(1)?)?;
+            let rest = rest.get(char_offset(rest, column)..)?;
+            return Some(Site {
+                item_path: self.scope_at(position).unwrap_or_default(),
+                code_hash: token_hash(rest),
+                occurrence: 0,
+            });
+        };
+
+        let node = &self.nodes[index];
+        let code_hash = node.hash(self.source);
+        let occurrence = self.nodes[..index]
+            .iter()
+            .filter(|n| {
+                n.scope == node.scope
+                    && n.kind == node.kind
+                    && n.start < node.start
+                    && n.hash(self.source) == code_hash
+            })
+            .count();
+        Some(Site {
+            item_path: node
+                .scope
+                .map(|s| self.scopes[s].path.clone())
+                .unwrap_or_default(),
+            code_hash,
+            occurrence,
+        })
+    }
+
+    fn scope_at(&self, position: LineColumn) -> Option<String> {
+        self.scopes
+            .iter()
+            .rev()
+            .find(|s| s.start <= position && position < s.end)
+            .map(|s| s.path.clone())
+    }
+}
+
+/// Module path of a file from its path relative to the workspace root:
+/// `crates/api/src/handlers/mod.rs` is `crate::handlers`. Files outside a
+/// `src` directory are named by their whole path.
+pub(crate) fn module_path(file: &Path) -> String {
+    let parts: Vec<&str> = file
+        .components()
+        .filter_map(|c| match c {
+            Component::Normal(part) => part.to_str(),
+            _ => None,
+        })
+        .collect();
+    let parts = match parts.iter().position(|&part| part == "src") {
+        Some(src) => &parts[src + 1..],
+        None => &parts[..],
+    };
+
+    let mut path = String::from("crate");
+    for (i, part) in parts.iter().enumerate() {
+        let is_file = i + 1 == parts.len();
+        let part = if is_file {
+            part.strip_suffix(".rs").unwrap_or(part)
+        } else {
+            part
+        };
+        let is_root = part == "mod" || (i == 0 && (part == "lib" || part == "main"));
+        if is_file && is_root {
+            continue;
+        }
+        path.push_str("::");
+        path.push_str(part);
+    }
+    path
+}
+
+/// Hash of the tokens in `text`, ignoring whitespace, comments and trailing
+/// commas, which rustfmt adds when it splits a list across lines.
+fn token_hash(text: &str) -> u64 {
+    match text.parse::<TokenStream>() {
+        Ok(tokens) => {
+            let mut normalized = String::new();
+            normalize(tokens, &mut normalized);
+            Fingerprint::stable_hash(&normalized)
+        }
+        // Unbalanced delimiters in a line fallback; collapse whitespace instead
+        Err(_) => Fingerprint::stable_hash(&text.split_whitespace().collect::<Vec<_>>().join(" ")),
+    }
+}
+
+fn normalize(tokens: TokenStream, out: &mut String) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                out.push_str(open);
+                normalize(group.stream(), out);
+                out.push_str(close);
+            }
+            TokenTree::Punct(punct) if punct.as_char() == ',' && tokens.peek().is_none() => {
+                continue;
+            }
+            other => out.push_str(&other.to_string()),
+        }
+        out.push(' ');
+    }
+}
+
+/// Byte offset of the `column`th character of `line`.
+fn char_offset(line: &str, column: usize) -> usize {
+    line.char_indices()
+        .nth(column)
+        .map_or(line.len(), |(offset, _)| offset)
+}
+
+#[derive(Default)]
+struct Collector {
+    scopes: Vec<Scope>,
+    nodes: Vec<Node>,
+    /// Indices into `scopes` of the items being visited
+    stack: Vec<usize>,
+}
+
+impl Collector {
+    fn scoped(&mut self, name: String, node: &impl Spanned, visit: impl FnOnce(&mut Self)) {
+        let span = node.span();
+        let path = match self.stack.last() {
+            Some(&parent) => format!("{}::{}", self.scopes[parent].path, name),
+            None => name,
+        };
+        self.scopes.push(Scope {
+            path,
+            start: span.start(),
+            end: span.end(),
+        });
+        self.stack.push(self.scopes.len() - 1);
+        visit(self);
+        self.stack.pop();
+    }
+
+    fn push(&mut self, kind: NodeKind, node: &impl Spanned) {
+        let span = node.span();
+        self.nodes.push(Node {
+            kind,
+            start: span.start(),
+            end: span.end(),
+            bytes: span.byte_range(),
+            scope: self.stack.last().copied(),
+            hash: OnceCell::new(),
+        });
+    }
+}
+
+/// `Type` or `<Type as Trait>`, without generic arguments.
+fn impl_name(node: &syn::ItemImpl) -> String {
+    let self_ty = match &*node.self_ty {
+        syn::Type::Path(ty) => path_name(&ty.path),
+        _ => "_".to_string(),
+    };
+    match &node.trait_ {
+        Some((_, path, _)) => format!("<{} as {}>", self_ty, path_name(path)),
+        None => self_ty,
+    }
+}
+
+fn path_name(path: &syn::Path) -> String {
+    path.segments
+        .iter()
+        .map(|segment| segment.ident.to_string())
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+impl<'ast> Visit<'ast> for Collector {
+    fn visit_item_fn(&mut self, node: &'ast syn::ItemFn) {
+        self.scoped(node.sig.ident.to_string(), node, |c| {
+            visit::visit_item_fn(c, node)
+        });
+    }
+
+    fn visit_item_impl(&mut self, node: &'ast syn::ItemImpl) {
+        self.scoped(impl_name(node), node, |c| visit::visit_item_impl(c, node));
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast syn::ImplItemFn) {
+        self.scoped(node.sig.ident.to_string(), node, |c| {
+            visit::visit_impl_item_fn(c, node)
+        });
+    }
+
+    fn visit_impl_item_const(&mut self, node: &'ast syn::ImplItemConst) {
+        self.scoped(node.ident.to_string(), node, |c| {
+            visit::visit_impl_item_const(c, node)
+        });
+    }
+
+    fn visit_item_trait(&mut self, node: &'ast syn::ItemTrait) {
+        self.scoped(node.ident.to_string(), node, |c| {
+            visit::visit_item_trait(c, node)
+        });
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast syn::TraitItemFn) {
+        self.scoped(node.sig.ident.to_string(), node, |c| {
+            visit::visit_trait_item_fn(c, node)
+        });
+    }
+
+    fn visit_item_mod(&mut self, node: &'ast syn::ItemMod) {
+        self.scoped(node.ident.to_string(), node, |c| {
+            visit::visit_item_mod(c, node)
+        });
+    }
+
+    fn visit_item_const(&mut self, node: &'ast syn::ItemConst) {
+        self.scoped(node.ident.to_string(), node, |c| {
+            visit::visit_item_const(c, node)
+        });
+    }
+
+    fn visit_item_static(&mut self, node: &'ast syn::ItemStatic) {
+        self.scoped(node.ident.to_string(), node, |c| {
+            visit::visit_item_static(c, node)
+        });
+    }
+
+    fn visit_stmt(&mut self, node: &'ast syn::Stmt) {
+        self.push(NodeKind::Stmt, node);
+        visit::visit_stmt(self, node);
+    }
+
+    fn visit_expr(&mut self, node: &'ast syn::Expr) {
+        self.push(NodeKind::Expr, node);
+        visit::visit_expr(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = "\
+impl Handler {
+    fn list_users(&self, xs: &[String]) {
+        for x in xs {
+            let y = x.clone();
+            let z = x.clone();
+        }
+    }
+}
+";
+
+    #[test]
+    fn test_site() {
+        let structure = SourceStructure::parse(SOURCE).unwrap();
+        let first = structure.site(4, 20).unwrap();
+        assert_eq!(first.item_path, "Handler::list_users");
+        assert_eq!(first.occurrence, 0);
+
+        // Same tokens later in the item: same hash, next occurrence
+        let second = structure.site(5, 20).unwrap();
+        assert_eq!(second.code_hash, first.code_hash);
+        assert_eq!(second.occurrence, 1);
+
+        // Flagged on the statement rather than an expression
+        let stmt = structure.site(4, 12).unwrap();
+        assert_ne!(stmt.code_hash, first.code_hash);
+    }
+
+    #[test]
+    fn test_site_survives_reformatting() {
+        let reformatted = "\
+impl Handler {
+    // Lists every user
+    fn list_users(&self, xs: &[String]) {
+        for x in xs {
+            let renamed = 1;
+            let y = x
+                .clone();
+            let z = x.clone();
+        }
+    }
+}
+";
+        let before = SourceStructure::parse(SOURCE).unwrap().site(4, 20);
+        let after = SourceStructure::parse(reformatted).unwrap().site(6, 20);
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn test_module_path() {
+        assert_eq!(module_path(Path::new("src/lib.rs")), "crate");
+        assert_eq!(
+            module_path(Path::new("crates/api/src/handlers/mod.rs")),
+            "crate::handlers"
+        );
+        assert_eq!(
+            module_path(Path::new("src/api/handlers.rs")),
+            "crate::api::handlers"
+        );
+        assert_eq!(module_path(Path::new("tests/cli.rs")), "crate::tests::cli");
+    }
+}
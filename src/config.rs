@@ -1,24 +1,203 @@
-use serde::{Deserialize, Serialize};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::borrow::Cow;
 use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
 
+use crate::rules::selection::wildcard_match;
+use crate::workspace::TargetKind;
 use crate::Severity;
 
 /// Maximum config file size (1 MB) - prevents memory exhaustion from malformed files
 const MAX_CONFIG_SIZE: u64 = 1024 * 1024;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "ConfigFile")]
 pub struct Config {
-    #[serde(default)]
     pub rules: HashMap<String, RuleSeverity>,
 
+    /// Options from `[rules.<id>]` tables, by rule ID.
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub rule_options: HashMap<String, RuleOptions>,
+
     #[serde(default)]
     pub output: OutputConfig,
 
     #[serde(default)]
     pub database: DatabaseConfig,
+
+    /// `[[overrides]]` sections, applied in order on top of `rules`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overrides: Vec<RuleOverride>,
+
+    /// Directory the config was loaded from. Override `paths` are relative
+    /// to it; when unset they are matched against paths as given.
+    #[serde(skip)]
+    pub root: Option<PathBuf>,
+}
+
+/// `cargo-perf.toml` as written, before `[rules]` entries are split into
+/// levels and option tables.
+#[derive(Deserialize)]
+struct ConfigFile {
+    #[serde(default)]
+    rules: HashMap<String, RuleEntry>,
+
+    #[serde(default)]
+    output: OutputConfig,
+
+    #[serde(default)]
+    database: DatabaseConfig,
+
+    #[serde(default)]
+    overrides: Vec<RuleOverride>,
+}
+
+impl From<ConfigFile> for Config {
+    fn from(file: ConfigFile) -> Self {
+        let mut rules = HashMap::new();
+        let mut rule_options = HashMap::new();
+        for (id, entry) in file.rules {
+            if let Some(level) = entry.level {
+                rules.insert(id.clone(), level);
+            }
+            if !entry.options.is_empty() {
+                rule_options.insert(id, entry.options);
+            }
+        }
+        Self {
+            rules,
+            rule_options,
+            output: file.output,
+            database: file.database,
+            overrides: file.overrides,
+            root: None,
+        }
+    }
+}
+
+/// A `[rules]` entry: either `id = "warn"` or a `[rules.<id>]` table with an
+/// optional `level` and rule-specific options.
+struct RuleEntry {
+    level: Option<RuleSeverity>,
+    options: RuleOptions,
+}
+
+impl<'de> Deserialize<'de> for RuleEntry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        match toml::Value::deserialize(deserializer)? {
+            toml::Value::Table(mut table) => {
+                let level = table
+                    .remove("level")
+                    .map(RuleSeverity::deserialize)
+                    .transpose()
+                    .map_err(|e| D::Error::custom(format!("level: {}", e)))?;
+                Ok(Self {
+                    level,
+                    options: RuleOptions(table),
+                })
+            }
+            value => Ok(Self {
+                level: Some(RuleSeverity::deserialize(value).map_err(D::Error::custom)?),
+                options: RuleOptions::default(),
+            }),
+        }
+    }
+}
+
+/// Rule-specific settings from a `[rules.<id>]` table, e.g.
+///
+/// ```toml
+/// [rules.unbounded-channel]
+/// level = "deny"
+/// suggested-capacity = 256
+/// ```
+///
+/// Rules read them with [`AnalysisContext::rule_options`] into their own
+/// options type and check them in [`Rule::validate_options`].
+///
+/// [`AnalysisContext::rule_options`]: crate::engine::AnalysisContext::rule_options
+/// [`Rule::validate_options`]: crate::rules::Rule::validate_options
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct RuleOptions(toml::Table);
+
+impl RuleOptions {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Deserialize the table into a rule's options type.
+    pub fn parse<T: DeserializeOwned>(&self) -> Result<T, String> {
+        toml::Value::Table(self.0.clone())
+            .try_into()
+            .map_err(|e: toml::de::Error| e.message().to_string())
+    }
+}
+
+impl From<toml::Table> for RuleOptions {
+    fn from(table: toml::Table) -> Self {
+        Self(table)
+    }
+}
+
+/// Rule levels for a subset of files.
+///
+/// A file matches when one of `paths` matches it and its target kind is one
+/// of `targets`; an empty list matches everything, but at least one of the
+/// two must be given. Paths are globs where `*` and `?` also match `/`, and
+/// a pattern matching a directory covers every file below it (`tests`,
+/// `src/hot_path/**`, `**/fixtures`). Target kinds need workspace metadata,
+/// so they never match when analyzing plain directories.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleOverride {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<TargetKind>,
+
+    #[serde(default)]
+    pub rules: HashMap<String, RuleSeverity>,
+}
+
+impl RuleOverride {
+    /// Whether this override applies to `path` (relative to the config root)
+    /// in a target of kind `target`.
+    pub fn matches(&self, path: &Path, target: Option<TargetKind>) -> bool {
+        let path_matches = self.paths.is_empty()
+            || self
+                .paths
+                .iter()
+                .any(|pattern| glob_matches_path(pattern, path));
+        let target_matches =
+            self.targets.is_empty() || target.is_some_and(|kind| self.targets.contains(&kind));
+        path_matches && target_matches
+    }
+}
+
+/// Match `pattern` against `path` or any of the directories containing it.
+fn glob_matches_path(pattern: &str, path: &Path) -> bool {
+    let pattern = pattern.trim_start_matches("./").trim_end_matches('/');
+    let mut prefix = String::new();
+    for component in path.components() {
+        let Component::Normal(name) = component else {
+            continue;
+        };
+        if !prefix.is_empty() {
+            prefix.push('/');
+        }
+        prefix.push_str(&name.to_string_lossy());
+        if wildcard_match(pattern.as_bytes(), prefix.as_bytes()) {
+            return true;
+        }
+    }
+    false
 }
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
@@ -67,7 +246,43 @@ impl Default for OutputConfig {
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DatabaseConfig {
-    pub orm: Option<String>,
+    /// ORMs the project uses, written as one name or a list. Empty means
+    /// unknown, and database rules consider every supported ORM.
+    #[serde(
+        default,
+        deserialize_with = "one_or_many",
+        skip_serializing_if = "Vec::is_empty"
+    )]
+    pub orm: Vec<Orm>,
+}
+
+impl DatabaseConfig {
+    /// Whether code may be using `orm`: it was configured, or nothing was.
+    pub fn uses(&self, orm: Orm) -> bool {
+        self.orm.is_empty() || self.orm.contains(&orm)
+    }
+}
+
+/// Database libraries `[database] orm` can name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Orm {
+    Sqlx,
+    Diesel,
+    #[serde(alias = "sea_orm", alias = "seaorm")]
+    SeaOrm,
+}
+
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Orm>, D::Error> {
+    use serde::de::Error;
+
+    match toml::Value::deserialize(deserializer)? {
+        toml::Value::Array(values) => values
+            .into_iter()
+            .map(|value| Orm::deserialize(value).map_err(D::Error::custom))
+            .collect(),
+        value => Ok(vec![Orm::deserialize(value).map_err(D::Error::custom)?]),
+    }
 }
 
 impl Config {
@@ -120,10 +335,22 @@ impl Config {
         let mut content = String::with_capacity(metadata.len() as usize);
         file.read_to_string(&mut content)?;
 
-        let config: Config = toml::from_str(&content)?;
+        let mut config: Config = toml::from_str(&content)?;
+        config.root = Some(dir_path.to_path_buf());
+
+        for (i, rule_override) in config.overrides.iter().enumerate() {
+            if rule_override.paths.is_empty() && rule_override.targets.is_empty() {
+                anyhow::bail!(
+                    "[[overrides]] entry {} in {} needs `paths` or `targets`",
+                    i + 1,
+                    config_path.display()
+                );
+            }
+        }
 
         // Validate rule IDs against known rules
         Self::validate_rule_ids(&config);
+        config.validate_rule_options()?;
 
         Ok(config)
     }
@@ -132,7 +359,16 @@ impl Config {
     fn validate_rule_ids(config: &Config) {
         use crate::rules::registry;
 
-        for rule_id in config.rules.keys() {
+        let override_ids = config.overrides.iter().flat_map(|o| o.rules.keys());
+        let mut rule_ids: Vec<&String> = config
+            .rules
+            .keys()
+            .chain(config.rule_options.keys())
+            .chain(override_ids)
+            .collect();
+        rule_ids.sort();
+        rule_ids.dedup();
+        for rule_id in rule_ids {
             if !registry::has_rule(rule_id) {
                 eprintln!(
                     "Warning: Unknown rule '{}' in cargo-perf.toml (will be ignored)",
@@ -142,6 +378,53 @@ impl Config {
         }
     }
 
+    /// Check `[rules.<id>]` options against the rules they configure.
+    fn validate_rule_options(&self) -> anyhow::Result<()> {
+        use crate::rules::registry;
+
+        for (rule_id, options) in &self.rule_options {
+            if let Some(rule) = registry::get_rule(rule_id) {
+                if let Err(e) = rule.validate_options(options) {
+                    anyhow::bail!("Invalid options in [rules.{}]: {}", rule_id, e);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The config in effect for one file: `rules` with every matching
+    /// `[[overrides]]` section applied in order, later sections winning.
+    ///
+    /// Borrows `self` when no override matches.
+    pub fn for_file(&self, path: &Path, target: Option<TargetKind>) -> Cow<'_, Config> {
+        if self.overrides.is_empty() {
+            return Cow::Borrowed(self);
+        }
+        let relative = self
+            .root
+            .as_deref()
+            .and_then(|root| path.strip_prefix(root).ok())
+            .unwrap_or(path);
+        let mut matching = self
+            .overrides
+            .iter()
+            .filter(|o| o.matches(relative, target))
+            .peekable();
+        if matching.peek().is_none() {
+            return Cow::Borrowed(self);
+        }
+        let mut config = self.clone();
+        for rule_override in matching {
+            config.rules.extend(
+                rule_override
+                    .rules
+                    .iter()
+                    .map(|(id, severity)| (id.clone(), *severity)),
+            );
+        }
+        Cow::Owned(config)
+    }
+
     /// Get the effective severity for a rule
     pub fn rule_severity(&self, rule_id: &str, default: Severity) -> Option<Severity> {
         match self.rules.get(rule_id) {
@@ -180,12 +463,29 @@ impl Config {
 # clone-in-hot-loop = "warn"
 # vec-no-capacity = "allow"
 
+# Rules with options take a table; `level` sets the severity
+# [rules.async-block-in-async]
+# extra-blocking = ["crate::legacy::sync_fetch"]
+#
+# [rules.unbounded-channel]
+# suggested-capacity = 256
+
 [output]
 format = "console"  # "console", "json", "sarif"
 color = "auto"      # "auto", "always", "never"
 
 [database]
-# orm = "sqlx"  # "sqlx", "diesel", "sea-orm"
+# orm = "sqlx"  # "sqlx", "diesel", "sea-orm", or a list like ["sqlx", "sea-orm"]
+
+# Different levels for some files: match path globs and/or target kinds
+# ("lib", "bin", "test", "bench", "example", "custom-build")
+# [[overrides]]
+# paths = ["tests", "benches"]
+# rules = { clone-in-hot-loop = "allow" }
+#
+# [[overrides]]
+# paths = ["src/hot_path/**"]
+# rules = { clone-in-hot-loop = "deny" }
 "#
     }
 }
@@ -330,4 +630,180 @@ test-rule = "warn"
         );
         assert_eq!(Option::<Severity>::from(RuleSeverity::Allow), None);
     }
+
+    #[test]
+    fn test_overrides_by_path_and_target() {
+        let config: Config = toml::from_str(
+            r#"
+[rules]
+clone-in-hot-loop = "warn"
+
+[[overrides]]
+paths = ["tests", "benches/"]
+rules = { clone-in-hot-loop = "allow" }
+
+[[overrides]]
+paths = ["src/hot_path/**"]
+rules = { clone-in-hot-loop = "deny" }
+
+[[overrides]]
+targets = ["example", "build"]
+rules = { vec-no-capacity = "allow" }
+"#,
+        )
+        .unwrap();
+        let severity = |path: &str, target: Option<TargetKind>, rule: &str| {
+            config
+                .for_file(Path::new(path), target)
+                .rule_severity(rule, Severity::Info)
+        };
+
+        assert_eq!(
+            severity("src/lib.rs", None, "clone-in-hot-loop"),
+            Some(Severity::Warning)
+        );
+        assert_eq!(
+            severity("tests/it/main.rs", None, "clone-in-hot-loop"),
+            None
+        );
+        assert_eq!(severity("./benches/b.rs", None, "clone-in-hot-loop"), None);
+        assert_eq!(
+            severity("src/hot_path/mod.rs", None, "clone-in-hot-loop"),
+            Some(Severity::Error)
+        );
+        // A glob matches whole components, not a name prefix
+        assert_eq!(
+            severity("testsuite/a.rs", None, "clone-in-hot-loop"),
+            Some(Severity::Warning)
+        );
+        assert_eq!(
+            severity("src/main.rs", Some(TargetKind::Example), "vec-no-capacity"),
+            None
+        );
+        assert_eq!(
+            severity("build.rs", Some(TargetKind::CustomBuild), "vec-no-capacity"),
+            None
+        );
+        assert!(matches!(
+            config.for_file(Path::new("src/lib.rs"), Some(TargetKind::Lib)),
+            Cow::Borrowed(_)
+        ));
+    }
+
+    #[test]
+    fn test_override_paths_and_targets_both_apply() {
+        let rule_override = RuleOverride {
+            paths: vec!["crates/core/**".to_string()],
+            targets: vec![TargetKind::Test],
+            ..RuleOverride::default()
+        };
+        let path = Path::new("crates/core/tests/a.rs");
+        assert!(rule_override.matches(path, Some(TargetKind::Test)));
+        assert!(!rule_override.matches(path, Some(TargetKind::Lib)));
+        assert!(!rule_override.matches(path, None));
+        assert!(!rule_override.matches(Path::new("crates/app/tests/a.rs"), Some(TargetKind::Test)));
+    }
+
+    #[test]
+    fn test_override_paths_relative_to_config_root() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("cargo-perf.toml"),
+            "[[overrides]]\npaths = [\"tests\"]\nrules = { clone-in-hot-loop = \"allow\" }\n",
+        )
+        .unwrap();
+        let config = Config::load_or_default(tmp.path()).unwrap();
+        let file = tmp.path().join("tests/a.rs");
+        assert_eq!(
+            config
+                .for_file(&file, None)
+                .rule_severity("clone-in-hot-loop", Severity::Warning),
+            None
+        );
+    }
+
+    #[test]
+    fn test_override_without_paths_or_targets_is_an_error() {
+        let tmp = TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("cargo-perf.toml"),
+            "[[overrides]]\nrules = { clone-in-hot-loop = \"allow\" }\n",
+        )
+        .unwrap();
+        let err = Config::load_or_default(tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("needs `paths` or `targets`"));
+    }
+
+    #[test]
+    fn test_rule_tables_split_into_level_and_options() {
+        let config: Config = toml::from_str(
+            r#"
+[rules]
+clone-in-hot-loop = "allow"
+
+[rules.unbounded-channel]
+level = "deny"
+suggested-capacity = 256
+
+[rules.async-block-in-async]
+extra-blocking = ["crate::legacy::sync_fetch"]
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.rules["clone-in-hot-loop"], RuleSeverity::Allow);
+        assert_eq!(config.rules["unbounded-channel"], RuleSeverity::Deny);
+        assert!(!config.rules.contains_key("async-block-in-async"));
+        let channel: toml::Table = config.rule_options["unbounded-channel"].parse().unwrap();
+        assert_eq!(
+            channel.get("suggested-capacity"),
+            Some(&toml::Value::Integer(256))
+        );
+        assert!(channel.get("level").is_none());
+        let options: toml::Table = config.rule_options["async-block-in-async"].parse().unwrap();
+        assert_eq!(
+            options["extra-blocking"][0].as_str(),
+            Some("crate::legacy::sync_fetch")
+        );
+    }
+
+    #[test]
+    fn test_invalid_rule_level_is_an_error() {
+        let err = toml::from_str::<Config>("[rules]\nclone-in-hot-loop = \"loud\"\n").unwrap_err();
+        assert!(err.to_string().contains("loud"), "{}", err);
+        let err =
+            toml::from_str::<Config>("[rules.clone-in-hot-loop]\nlevel = \"loud\"\n").unwrap_err();
+        assert!(err.to_string().contains("level"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_rejects_invalid_rule_options() {
+        let tmp = TempDir::new().unwrap();
+        let write = |content: &str| std::fs::write(tmp.path().join("cargo-perf.toml"), content);
+
+        write("[rules.unbounded-channel]\nsuggested-capacity = \"lots\"\n").unwrap();
+        let err = Config::load_or_default(tmp.path()).unwrap_err();
+        assert!(
+            err.to_string().contains("[rules.unbounded-channel]"),
+            "{}",
+            err
+        );
+
+        write("[rules.clone-in-hot-loop]\nlevel = \"warn\"\nthreshold = 3\n").unwrap();
+        let err = Config::load_or_default(tmp.path()).unwrap_err();
+        assert!(err.to_string().contains("takes no options"), "{}", err);
+    }
+
+    #[test]
+    fn test_database_orm_accepts_one_or_many() {
+        let config: Config = toml::from_str("[database]\norm = \"diesel\"\n").unwrap();
+        assert_eq!(config.database.orm, vec![Orm::Diesel]);
+        assert!(config.database.uses(Orm::Diesel));
+        assert!(!config.database.uses(Orm::Sqlx));
+
+        let config: Config = toml::from_str("[database]\norm = [\"sqlx\", \"sea-orm\"]\n").unwrap();
+        assert_eq!(config.database.orm, vec![Orm::Sqlx, Orm::SeaOrm]);
+
+        assert!(Config::default().database.uses(Orm::SeaOrm));
+        assert!(toml::from_str::<Config>("[database]\norm = \"hibernate\"\n").is_err());
+    }
 }
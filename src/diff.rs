@@ -0,0 +1,231 @@
+//! Changed lines from `git diff`, for checking only what a change touched.
+//!
+//! `cargo perf check --changed-since <rev>` and `--staged` ask git which lines
+//! were added or modified and report only diagnostics on those lines, so a PR
+//! gate answers "did this change introduce a performance issue" without a
+//! committed baseline.
+
+use crate::error::{Error, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// What to diff the working tree against.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffBase {
+    /// Everything changed since a revision: committed, staged, unstaged and
+    /// untracked files.
+    Since(String),
+    /// Changes staged in the index.
+    Staged,
+}
+
+/// Added or modified line ranges per file, keyed by absolute path.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChangedLines {
+    files: HashMap<PathBuf, Vec<(usize, usize)>>,
+}
+
+impl ChangedLines {
+    /// Ask git for the lines changed relative to `base` in the repository
+    /// containing `path`.
+    ///
+    /// Files are analyzed as they are in the working tree, so with
+    /// [`DiffBase::Staged`] unstaged edits to a staged file can shift lines.
+    pub fn from_git(path: &Path, base: &DiffBase) -> Result<Self> {
+        let dir = if path.is_dir() {
+            path
+        } else {
+            match path.parent() {
+                Some(parent) if !parent.as_os_str().is_empty() => parent,
+                _ => Path::new("."),
+            }
+        };
+        let root = PathBuf::from(git(dir, &["rev-parse", "--show-toplevel"])?.trim_end());
+
+        let mut args = vec![
+            "diff",
+            "--unified=0",
+            "--no-color",
+            "--no-ext-diff",
+            "--src-prefix=a/",
+            "--dst-prefix=b/",
+            // Deleted files have no lines to report on
+            "--diff-filter=d",
+        ];
+        match base {
+            DiffBase::Since(rev) => {
+                if rev.starts_with('-') {
+                    return Err(Error::git(format!("invalid revision `{}`", rev)));
+                }
+                args.push(rev);
+            }
+            DiffBase::Staged => args.push("--cached"),
+        }
+        args.push("--");
+        let mut changed = Self::parse(&git(&root, &args)?, &root);
+
+        if matches!(base, DiffBase::Since(_)) {
+            let untracked = git(&root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+            for file in untracked.split('\0').filter(|f| !f.is_empty()) {
+                changed.files.insert(root.join(file), vec![(1, usize::MAX)]);
+            }
+        }
+
+        // Analyzed paths are matched after canonicalization
+        changed.files = changed
+            .files
+            .into_iter()
+            .map(|(file, ranges)| (file.canonicalize().unwrap_or(file), ranges))
+            .collect();
+        Ok(changed)
+    }
+
+    /// Parse `git diff --unified=0` output whose paths are relative to `root`.
+    pub fn parse(diff: &str, root: &Path) -> Self {
+        let mut files: HashMap<PathBuf, Vec<(usize, usize)>> = HashMap::new();
+        let mut current: Option<PathBuf> = None;
+        let mut previous = "";
+        for line in diff.lines() {
+            // An added line reading `++ x` also starts with `+++ `; a file
+            // header always follows the `--- ` header.
+            let header = previous.starts_with("--- ");
+            previous = line;
+            if let Some(target) = line.strip_prefix("+++ ").filter(|_| header) {
+                current = target
+                    .trim_matches('"')
+                    .strip_prefix("b/")
+                    .map(|file| root.join(file));
+                if let Some(file) = &current {
+                    files.entry(file.clone()).or_default();
+                }
+            } else if let Some(hunk) = line.strip_prefix("@@ ") {
+                let (Some(file), Some((start, count))) = (&current, parse_hunk(hunk)) else {
+                    continue;
+                };
+                // A pure deletion adds no lines
+                if count > 0 {
+                    if let Some(ranges) = files.get_mut(file) {
+                        ranges.push((start, start + count - 1));
+                    }
+                }
+            }
+        }
+        Self { files }
+    }
+
+    /// Number of files with changes.
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// Whether `file` was added or modified.
+    pub fn contains_file(&self, file: &Path) -> bool {
+        self.files.contains_key(file)
+    }
+
+    /// Whether any of the lines `start..=end` of `file` changed.
+    pub fn touches(&self, file: &Path, start: usize, end: usize) -> bool {
+        self.files.get(file).is_some_and(|ranges| {
+            ranges
+                .iter()
+                .any(|&(from, to)| from <= end.max(start) && start <= to)
+        })
+    }
+}
+
+/// The new-file range of a hunk header (`-a,b +c,d @@ ...`) as
+/// `(start, count)`; a missing count means one line.
+fn parse_hunk(hunk: &str) -> Option<(usize, usize)> {
+    let new = hunk
+        .split_whitespace()
+        .find_map(|part| part.strip_prefix('+'))?;
+    let (start, count) = match new.split_once(',') {
+        Some((start, count)) => (start.parse().ok()?, count.parse().ok()?),
+        None => (new.parse().ok()?, 1),
+    };
+    Some((start, count))
+}
+
+fn git(dir: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .map_err(|e| Error::git(format!("failed to run git: {}", e)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(Error::git(stderr.trim()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIFF: &str = "\
+diff --git a/src/lib.rs b/src/lib.rs
+index 1111111..2222222 100644
+--- a/src/lib.rs
++++ b/src/lib.rs
+@@ -3,0 +4,2 @@ fn setup() {
++    let a = 1;
++    let b = 2;
+@@ -10 +12 @@ fn run() {
+-    old();
++    new();
+@@ -20,3 +21,0 @@ fn gone() {
+-    x();
+-    y();
+-    z();
+diff --git a/src/new.rs b/src/new.rs
+new file mode 100644
+--- /dev/null
++++ b/src/new.rs
+@@ -0,0 +1,3 @@
++fn main() {
++++ counter;
++}
+";
+
+    #[test]
+    fn test_parse_hunks() {
+        let root = Path::new("/repo");
+        let changed = ChangedLines::parse(DIFF, root);
+        assert_eq!(changed.len(), 2);
+        assert_eq!(
+            changed.files[Path::new("/repo/src/lib.rs")],
+            vec![(4, 5), (12, 12)]
+        );
+        assert_eq!(changed.files[Path::new("/repo/src/new.rs")], vec![(1, 3)]);
+    }
+
+    #[test]
+    fn test_touches() {
+        let changed = ChangedLines::parse(DIFF, Path::new("/repo"));
+        let lib = Path::new("/repo/src/lib.rs");
+        assert!(changed.touches(lib, 4, 4));
+        assert!(changed.touches(lib, 12, 12));
+        assert!(!changed.touches(lib, 6, 6));
+        // Deleted lines leave nothing behind to report on
+        assert!(!changed.touches(lib, 21, 21));
+        // A diagnostic spanning a changed line counts
+        assert!(changed.touches(lib, 10, 13));
+        assert!(!changed.touches(Path::new("/repo/src/other.rs"), 1, 1));
+    }
+
+    #[test]
+    fn test_parse_hunk_header() {
+        assert_eq!(parse_hunk("-1,2 +3,4 @@"), Some((3, 4)));
+        assert_eq!(parse_hunk("-1 +7 @@ fn f()"), Some((7, 1)));
+        assert_eq!(parse_hunk("-5,2 +4,0 @@"), Some((4, 0)));
+        assert_eq!(parse_hunk("garbage"), None);
+    }
+}
@@ -0,0 +1,459 @@
+//! Incremental analysis cache.
+//!
+//! Re-parsing unchanged files dominates the run time of repeated checks
+//! (pre-commit hooks, editor saves). The cache stores, per file:
+//!
+//! - a hash of the file content,
+//! - the file's [`FileSummary`], so the crate graph can be rebuilt without
+//!   parsing the file again,
+//! - the diagnostics of the last run, with the settings hash and crate digest
+//!   they were computed under.
+//!
+//! Diagnostics are replayed only when the content hash, the settings hash
+//! (config + rule set) and the crate digest all match. The crate digest covers
+//! the summaries of every file in the same crate, because cross-file rules
+//! (imports through re-exports, interprocedural blocking detection) can change
+//! a file's diagnostics when another file's items, imports or calls change.
+//! Edits that leave a file's summary unchanged, like most edits inside a
+//! function body, only invalidate that file.
+//!
+//! The cache lives in `target/cargo-perf/` (honoring `CARGO_TARGET_DIR`) and
+//! is discarded wholesale when the cargo-perf version changes. Custom plugin
+//! rules are keyed by ID only; changing a plugin's behavior without renaming
+//! it requires `--no-cache` or deleting the cache directory.
+
+use crate::engine::crate_graph::FileSummary;
+use crate::rules::{Diagnostic, Fix, Rule, Severity};
+use crate::Config;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+/// Cache file name inside the cache directory.
+pub const CACHE_FILENAME: &str = "analysis-cache.json";
+
+/// Maximum cache file size (256 MB); larger files are ignored and rebuilt.
+const MAX_CACHE_SIZE: u64 = 256 * 1024 * 1024;
+
+/// Default cache directory for the Cargo project containing `path`:
+/// `<target>/cargo-perf`, where `<target>` is `$CARGO_TARGET_DIR` if set and
+/// otherwise `target/` next to the workspace (or package) `Cargo.toml`.
+///
+/// Returns `None` if `path` is not inside a Cargo project, so analyzing a loose
+/// directory never litters it with a cache.
+pub fn default_cache_dir(path: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().ok()?;
+    let mut package_root = None;
+    for dir in path.ancestors() {
+        let manifest = dir.join("Cargo.toml");
+        if !manifest.is_file() {
+            continue;
+        }
+        package_root.get_or_insert(dir);
+        let is_workspace = fs::read_to_string(&manifest)
+            .map(|content| content.lines().any(|l| l.trim() == "[workspace]"))
+            .unwrap_or(false);
+        if is_workspace {
+            package_root = Some(dir);
+            break;
+        }
+    }
+    let root = package_root?;
+
+    let target = match std::env::var_os("CARGO_TARGET_DIR") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => root.join("target"),
+    };
+    Some(target.join("cargo-perf"))
+}
+
+/// On-disk cache of per-file summaries and diagnostics.
+#[derive(Debug)]
+pub struct AnalysisCache {
+    /// `None` for a cache that only lives in memory.
+    dir: Option<PathBuf>,
+    /// Hash of the config and the IDs of the rules being run.
+    settings: u64,
+    data: CacheData,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheData {
+    /// cargo-perf version that wrote the cache.
+    version: String,
+    files: HashMap<PathBuf, CachedFile>,
+}
+
+/// Cached state of one file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CachedFile {
+    pub content_hash: u64,
+    pub summary: FileSummary,
+    /// Hash of `summary`, used to compute crate digests.
+    pub summary_hash: u64,
+    /// Settings hash the diagnostics were computed under.
+    pub settings: u64,
+    /// Digest of the file's crate the diagnostics were computed against.
+    pub crate_digest: u64,
+    /// `None` if the file was only read for the crate graph, not checked.
+    pub diagnostics: Option<Vec<CachedDiagnostic>>,
+}
+
+/// A [`Diagnostic`] without its file path, with an owned rule ID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub(crate) struct CachedDiagnostic {
+    rule_id: String,
+    severity: Severity,
+    message: String,
+    line: usize,
+    column: usize,
+    end_line: Option<usize>,
+    end_column: Option<usize>,
+    suggestion: Option<String>,
+    fix: Option<Fix>,
+}
+
+impl From<&Diagnostic> for CachedDiagnostic {
+    fn from(diag: &Diagnostic) -> Self {
+        Self {
+            rule_id: diag.rule_id.to_string(),
+            severity: diag.severity,
+            message: diag.message.clone(),
+            line: diag.line,
+            column: diag.column,
+            end_line: diag.end_line,
+            end_column: diag.end_column,
+            suggestion: diag.suggestion.clone(),
+            fix: diag.fix.clone(),
+        }
+    }
+}
+
+impl AnalysisCache {
+    /// Load the cache from `dir`, or start empty if there is none or it was
+    /// written by another cargo-perf version or cannot be read.
+    pub fn load(dir: impl Into<PathBuf>, config: &Config, rules: &[&dyn Rule]) -> Self {
+        let dir = dir.into();
+        let data = Self::read(&dir.join(CACHE_FILENAME))
+            .filter(|data| data.version == env!("CARGO_PKG_VERSION"))
+            .unwrap_or_else(|| CacheData {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                files: HashMap::new(),
+            });
+        Self {
+            dir: Some(dir),
+            settings: settings_hash(config, rules),
+            data,
+        }
+    }
+
+    /// An empty cache that is never written to disk.
+    pub fn in_memory(config: &Config, rules: &[&dyn Rule]) -> Self {
+        Self {
+            dir: None,
+            settings: settings_hash(config, rules),
+            data: CacheData {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                files: HashMap::new(),
+            },
+        }
+    }
+
+    fn read(path: &Path) -> Option<CacheData> {
+        let metadata = fs::metadata(path).ok()?;
+        if metadata.len() > MAX_CACHE_SIZE {
+            return None;
+        }
+        let content = fs::read(path).ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    /// Write the cache back to disk, dropping entries for files that no
+    /// longer exist. The file is written to a temporary name and renamed so
+    /// a concurrent run never reads a partial cache. An in-memory cache
+    /// only drops the entries.
+    pub fn save(&mut self) -> std::io::Result<()> {
+        self.data.files.retain(|path, _| path.is_file());
+        let Some(dir) = &self.dir else {
+            return Ok(());
+        };
+        fs::create_dir_all(dir)?;
+        let content = serde_json::to_vec(&self.data)?;
+        let tmp = dir.join(format!("{CACHE_FILENAME}.{}.tmp", std::process::id()));
+        fs::write(&tmp, content)?;
+        fs::rename(&tmp, dir.join(CACHE_FILENAME))
+    }
+
+    /// The cached state of `path`, if its content hash still matches.
+    pub(crate) fn file(&self, path: &Path, content_hash: u64) -> Option<&CachedFile> {
+        self.data
+            .files
+            .get(path)
+            .filter(|cached| cached.content_hash == content_hash)
+    }
+
+    /// Stored diagnostics for `path`, if they are still valid for this content,
+    /// these settings and this crate digest.
+    pub(crate) fn diagnostics(
+        &self,
+        path: &Path,
+        content_hash: u64,
+        crate_digest: u64,
+        rules: &[&dyn Rule],
+    ) -> Option<Vec<Diagnostic>> {
+        let cached = self.file(path, content_hash)?;
+        if cached.settings != self.settings || cached.crate_digest != crate_digest {
+            return None;
+        }
+        cached
+            .diagnostics
+            .as_ref()?
+            .iter()
+            .map(|diag| {
+                // Rule IDs are `&'static str`; borrow them back from the rules.
+                let rule_id = rules.iter().find(|r| r.id() == diag.rule_id)?.id();
+                Some(Diagnostic {
+                    rule_id,
+                    severity: diag.severity,
+                    message: diag.message.clone(),
+                    file_path: path.to_path_buf(),
+                    line: diag.line,
+                    column: diag.column,
+                    end_line: diag.end_line,
+                    end_column: diag.end_column,
+                    suggestion: diag.suggestion.clone(),
+                    fix: diag.fix.clone(),
+                    target: None,
+                })
+            })
+            .collect()
+    }
+
+    /// Key the cache to `config` and `rules`, for a resident cache reused by
+    /// a run with other settings.
+    pub(crate) fn set_settings(&mut self, config: &Config, rules: &[&dyn Rule]) {
+        self.settings = settings_hash(config, rules);
+    }
+
+    /// Settings hash of this run.
+    pub(crate) fn settings(&self) -> u64 {
+        self.settings
+    }
+
+    /// Record the state of a file after analysis.
+    pub(crate) fn insert(&mut self, path: PathBuf, file: CachedFile) {
+        self.data.files.insert(path, file);
+    }
+}
+
+/// Hash of everything besides file contents that diagnostics depend on.
+fn settings_hash(config: &Config, rules: &[&dyn Rule]) -> u64 {
+    let mut hasher = StableHasher::new();
+    // Through `Value` so map keys are serialized in sorted order.
+    serde_json::to_value(config)
+        .map(|v| v.to_string())
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    let mut ids: Vec<&str> = rules.iter().map(|r| r.id()).collect();
+    ids.sort_unstable();
+    ids.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of a file's content.
+pub(crate) fn content_hash(source: &str) -> u64 {
+    let mut hasher = StableHasher::new();
+    source.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Hash of a file summary.
+pub(crate) fn summary_hash(summary: &FileSummary) -> u64 {
+    let mut hasher = StableHasher::new();
+    serde_json::to_vec(summary)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Digest of a crate from the `(path, summary hash)` of each of its files.
+pub(crate) fn crate_digest(mut files: Vec<(&Path, u64)>) -> u64 {
+    files.sort_unstable();
+    let mut hasher = StableHasher::new();
+    files.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// FNV-1a, which unlike `DefaultHasher` is stable across Rust versions, so a
+/// cache written by one build is readable by another.
+struct StableHasher(u64);
+
+impl StableHasher {
+    fn new() -> Self {
+        Self(14695981039346656037)
+    }
+}
+
+impl Hasher for StableHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(1099511628211);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::registry;
+    use tempfile::TempDir;
+
+    fn rules() -> Vec<&'static dyn Rule> {
+        registry::all_rules().iter().map(|r| r.as_ref()).collect()
+    }
+
+    fn summary(path: &Path) -> FileSummary {
+        FileSummary::from_ast(path, &syn::parse_file("fn f() {}").unwrap())
+    }
+
+    fn diagnostic(path: &Path) -> Diagnostic {
+        Diagnostic {
+            rule_id: "clone-in-hot-loop",
+            severity: Severity::Warning,
+            message: "msg".to_string(),
+            file_path: path.to_path_buf(),
+            line: 3,
+            column: 4,
+            end_line: None,
+            end_column: None,
+            suggestion: None,
+            fix: None,
+            target: None,
+        }
+    }
+
+    fn cached_file(path: &Path, cache: &AnalysisCache) -> CachedFile {
+        CachedFile {
+            content_hash: 1,
+            summary: summary(path),
+            summary_hash: 2,
+            settings: cache.settings(),
+            crate_digest: 3,
+            diagnostics: Some(vec![CachedDiagnostic::from(&diagnostic(path))]),
+        }
+    }
+
+    #[test]
+    fn test_round_trip_replays_diagnostics() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("lib.rs");
+        fs::write(&file, "fn f() {}").unwrap();
+        let config = Config::default();
+
+        let mut cache = AnalysisCache::load(tmp.path().join("cache"), &config, &rules());
+        let entry = cached_file(&file, &cache);
+        cache.insert(file.clone(), entry);
+        cache.save().unwrap();
+
+        let cache = AnalysisCache::load(tmp.path().join("cache"), &config, &rules());
+        let diags = cache.diagnostics(&file, 1, 3, &rules()).expect("cache hit");
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].rule_id, "clone-in-hot-loop");
+        assert_eq!(diags[0].file_path, file);
+        assert_eq!(diags[0].line, 3);
+    }
+
+    #[test]
+    fn test_misses_on_changed_content_digest_or_settings() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("lib.rs");
+        let config = Config::default();
+        let mut cache = AnalysisCache::load(tmp.path(), &config, &rules());
+        let entry = cached_file(&file, &cache);
+        cache.insert(file.clone(), entry);
+
+        assert!(cache.diagnostics(&file, 9, 3, &rules()).is_none());
+        assert!(cache.diagnostics(&file, 1, 9, &rules()).is_none());
+        assert!(
+            cache.file(&file, 1).is_some(),
+            "summary survives a digest change"
+        );
+
+        let mut config = Config::default();
+        config.rules.insert(
+            "clone-in-hot-loop".to_string(),
+            crate::config::RuleSeverity::Allow,
+        );
+        let other = AnalysisCache::load(tmp.path(), &config, &rules());
+        assert_ne!(other.settings(), cache.settings());
+    }
+
+    #[test]
+    fn test_unknown_rule_id_is_a_miss() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("lib.rs");
+        let config = Config::default();
+        let mut cache = AnalysisCache::load(tmp.path(), &config, &rules());
+        let entry = cached_file(&file, &cache);
+        cache.insert(file.clone(), entry);
+        assert!(cache.diagnostics(&file, 1, 3, &[]).is_none());
+    }
+
+    #[test]
+    fn test_other_version_is_discarded() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join(CACHE_FILENAME),
+            r#"{"version":"0.0.1","files":{}}"#,
+        )
+        .unwrap();
+        let cache = AnalysisCache::load(tmp.path(), &Config::default(), &rules());
+        assert_eq!(cache.data.version, env!("CARGO_PKG_VERSION"));
+    }
+
+    #[test]
+    fn test_default_cache_dir_uses_workspace_target() {
+        let tmp = TempDir::new().unwrap();
+        let member = tmp.path().join("crates").join("core");
+        fs::create_dir_all(member.join("src")).unwrap();
+        fs::write(tmp.path().join("Cargo.toml"), "[workspace]\nmembers = []\n").unwrap();
+        fs::write(member.join("Cargo.toml"), "[package]\nname = \"core\"\n").unwrap();
+
+        if std::env::var_os("CARGO_TARGET_DIR").is_none() {
+            let dir = default_cache_dir(&member.join("src")).unwrap();
+            assert_eq!(
+                dir,
+                tmp.path()
+                    .canonicalize()
+                    .unwrap()
+                    .join("target")
+                    .join("cargo-perf")
+            );
+        }
+    }
+
+    #[test]
+    fn test_default_cache_dir_outside_cargo_project() {
+        let tmp = TempDir::new().unwrap();
+        assert!(default_cache_dir(tmp.path()).is_none());
+    }
+
+    #[test]
+    fn test_stable_hashes() {
+        assert_eq!(content_hash("fn main() {}"), content_hash("fn main() {}"));
+        assert_ne!(content_hash("fn main() {}"), content_hash("fn main () {}"));
+        let a = Path::new("a.rs");
+        let b = Path::new("b.rs");
+        assert_eq!(
+            crate_digest(vec![(a, 1), (b, 2)]),
+            crate_digest(vec![(b, 2), (a, 1)])
+        );
+    }
+}
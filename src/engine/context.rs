@@ -1,6 +1,8 @@
 //! Analysis context and utilities for rule implementations.
 
+use crate::engine::crate_graph::CrateGraph;
 use crate::Config;
+use serde::de::DeserializeOwned;
 use std::path::Path;
 
 /// Pre-computed line index for O(log n) line/column lookups.
@@ -76,13 +78,15 @@ impl LineIndex {
 
 /// Context passed to rules during analysis.
 ///
-/// Contains all information needed to analyze a single file.
+/// Contains all information needed to analyze a single file, plus an optional
+/// crate-wide [`CrateGraph`] for resolving names defined in other files.
 pub struct AnalysisContext<'a> {
     pub file_path: &'a Path,
     pub source: &'a str,
     pub ast: &'a syn::File,
     pub config: &'a Config,
     line_index: LineIndex,
+    crate_graph: Option<&'a CrateGraph>,
 }
 
 impl<'a> AnalysisContext<'a> {
@@ -99,9 +103,35 @@ impl<'a> AnalysisContext<'a> {
             ast,
             config,
             line_index: LineIndex::new(source),
+            crate_graph: None,
         }
     }
 
+    /// Attach the crate-wide module graph built for this analysis run.
+    pub fn with_crate_graph(mut self, graph: &'a CrateGraph) -> Self {
+        self.crate_graph = Some(graph);
+        self
+    }
+
+    /// The crate-wide module graph, if the file was analyzed as part of a
+    /// multi-file run. Single-file analysis (tests, fuzzing) has none.
+    pub fn crate_graph(&self) -> Option<&'a CrateGraph> {
+        self.crate_graph
+    }
+
+    /// A rule's `[rules.<id>]` options parsed into its options type.
+    ///
+    /// Falls back to `T::default()` when the rule has no options, or when they
+    /// don't parse: configs loaded from disk were already checked with
+    /// [`Rule::validate_options`](crate::rules::Rule::validate_options).
+    pub fn rule_options<T: DeserializeOwned + Default>(&self, rule_id: &str) -> T {
+        self.config
+            .rule_options
+            .get(rule_id)
+            .and_then(|options| options.parse().ok())
+            .unwrap_or_default()
+    }
+
     /// Get line and column from a byte offset (1-indexed).
     ///
     /// This is O(log n) where n is the number of lines.
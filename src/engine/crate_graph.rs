@@ -0,0 +1,1061 @@
+//! Crate-wide module graph and symbol table.
+//!
+//! Rules see one file at a time, but the names they care about rarely live in
+//! that file: a handler calls `crate::db::Pool::get`, or imports a helper that a
+//! `util` module re-exports from `std::fs`. The [`CrateGraph`] is built once per
+//! analysis run from every parsed file. It reconstructs each crate's module tree
+//! from its root (`lib.rs`, `main.rs`, `src/bin/*.rs`, ...) by following `mod`
+//! declarations, records every item under its crate-absolute path
+//! (`crate::db::Pool`), and keeps each module's `use` map so that a path written
+//! in one file can be resolved to where it is actually defined.
+//!
+//! For every synchronous function it also keeps the calls made from its body
+//! ([`CallSite`]), resolved to canonical paths, so rules can follow a call from
+//! one function into the functions it calls.
+//!
+//! `syn` ASTs are not `Send`, so the graph never holds them. Each file is
+//! reduced to a [`FileSummary`] on the thread that parsed it, and the graph is
+//! built from those summaries.
+//!
+//! Resolution is still syntactic: there is no type inference, glob imports are
+//! not expanded, and macros are opaque. Files that are not reachable from any
+//! crate root (fixtures, single files given on the command line) are treated as
+//! crate roots of their own, so in-file names still resolve. When the roots are
+//! known from Cargo ([`CrateGraph::build_with_roots`]), such files belong to no
+//! crate instead.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use syn::visit::Visit;
+use syn::{Expr, Item, UseTree};
+
+/// Upper bound on re-export hops followed by [`CrateGraph::resolve`], so a
+/// `use` cycle (which would not compile anyway) can never loop forever.
+const MAX_REEXPORT_HOPS: usize = 16;
+
+/// Upper bound on inline `mod` nesting recorded per file.
+const MAX_MODULE_DEPTH: usize = 64;
+
+/// Upper bound on expression nesting walked when collecting call sites.
+const MAX_CALL_EXPR_DEPTH: usize = 256;
+
+/// The kind of item a [`Symbol`] names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SymbolKind {
+    Fn,
+    /// An associated function in an `impl` block, keyed as `Type::method`.
+    Method,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    TypeAlias,
+    Const,
+    Static,
+    Mod,
+}
+
+/// An item defined somewhere in an analyzed crate.
+#[derive(Debug, Clone)]
+pub struct Symbol {
+    pub kind: SymbolKind,
+    /// Crate-absolute path, e.g. `crate::db::Pool` or `crate::db::Pool::get`.
+    pub path: String,
+    /// File the item is defined in.
+    pub file: PathBuf,
+    /// 1-indexed line of the item's name.
+    pub line: usize,
+    /// Whether the item is an `async fn` (always `false` for non-functions).
+    pub is_async: bool,
+}
+
+/// A call made from the body of a synchronous function.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallSite {
+    pub callee: Callee,
+    /// 1-indexed line of the call in the caller's file.
+    pub line: usize,
+}
+
+/// What a [`CallSite`] calls, with paths resolved as far as the graph can.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Callee {
+    /// `path(..)`, e.g. `crate::config::read_raw` or `std::fs::read_to_string`.
+    /// `self.method(..)` and `Self::method(..)` inside an impl are recorded as
+    /// `crate::module::Type::method`.
+    Path(String),
+    /// `receiver.method(..)` on any other receiver. `receiver` is the path at
+    /// the root of the receiver chain, e.g. `std::process::Command::new` for
+    /// `Command::new("ls").arg("-l").output()`, if the chain starts at one.
+    Method {
+        name: String,
+        receiver: Option<String>,
+    },
+}
+
+/// The parts of one parsed file the crate graph needs.
+///
+/// Summaries are serializable so the analysis cache can rebuild the graph
+/// without re-parsing unchanged files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileSummary {
+    pub path: PathBuf,
+    module: ModuleSummary,
+}
+
+/// Items, imports and child modules declared directly in one module.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ModuleSummary {
+    items: Vec<ItemSummary>,
+    /// Associated functions, named `Type::method`.
+    methods: Vec<ItemSummary>,
+    /// `use` leaves as (leaf-or-alias, path segments as written).
+    uses: Vec<(String, Vec<String>)>,
+    mods: Vec<ModSummary>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ItemSummary {
+    name: String,
+    kind: SymbolKind,
+    line: usize,
+    is_async: bool,
+    /// Calls made from the body; only collected for synchronous functions.
+    calls: Vec<RawCall>,
+}
+
+/// A call site as written, before path resolution.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RawCall {
+    callee: RawCallee,
+    line: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum RawCallee {
+    Path(Vec<String>),
+    Method {
+        name: String,
+        receiver: Option<Vec<String>>,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ModSummary {
+    name: String,
+    /// Value of a `#[path = "..."]` attribute.
+    path_attr: Option<String>,
+    /// Body of an inline `mod name { ... }`; `None` for `mod name;`.
+    inline: Option<ModuleSummary>,
+}
+
+impl FileSummary {
+    /// Summarize a parsed file.
+    pub fn from_ast(path: &Path, ast: &syn::File) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            module: ModuleSummary::from_items(&ast.items, 0),
+        }
+    }
+}
+
+impl ModuleSummary {
+    fn from_items(items: &[Item], depth: usize) -> Self {
+        let mut module = ModuleSummary::default();
+        if depth > MAX_MODULE_DEPTH {
+            return module;
+        }
+        for item in items {
+            let (ident, kind, is_async) = match item {
+                Item::Fn(f) => (&f.sig.ident, SymbolKind::Fn, f.sig.asyncness.is_some()),
+                Item::Struct(s) => (&s.ident, SymbolKind::Struct, false),
+                Item::Enum(e) => (&e.ident, SymbolKind::Enum, false),
+                Item::Union(u) => (&u.ident, SymbolKind::Union, false),
+                Item::Trait(t) => (&t.ident, SymbolKind::Trait, false),
+                Item::Type(t) => (&t.ident, SymbolKind::TypeAlias, false),
+                Item::Const(c) => (&c.ident, SymbolKind::Const, false),
+                Item::Static(s) => (&s.ident, SymbolKind::Static, false),
+                Item::Mod(m) => {
+                    module.mods.push(ModSummary {
+                        name: m.ident.to_string(),
+                        path_attr: path_attr(&m.attrs),
+                        inline: m
+                            .content
+                            .as_ref()
+                            .map(|(_, items)| ModuleSummary::from_items(items, depth + 1)),
+                    });
+                    (&m.ident, SymbolKind::Mod, false)
+                }
+                Item::Impl(i) => {
+                    module.record_impl(i);
+                    continue;
+                }
+                Item::Use(u) => {
+                    collect_use_tree(&u.tree, Vec::new(), &mut module.uses);
+                    continue;
+                }
+                _ => continue,
+            };
+            let calls = match item {
+                Item::Fn(f) if !is_async => collect_calls(&f.block, None),
+                _ => Vec::new(),
+            };
+            module.items.push(ItemSummary {
+                name: ident.to_string(),
+                kind,
+                line: ident.span().start().line,
+                is_async,
+                calls,
+            });
+        }
+        module
+    }
+
+    /// Record the associated functions of an impl block as `Type::method`.
+    fn record_impl(&mut self, node: &syn::ItemImpl) {
+        let syn::Type::Path(self_ty) = &*node.self_ty else {
+            return;
+        };
+        let Some(type_name) = self_ty.path.segments.last().map(|s| s.ident.to_string()) else {
+            return;
+        };
+        for impl_item in &node.items {
+            if let syn::ImplItem::Fn(f) = impl_item {
+                let is_async = f.sig.asyncness.is_some();
+                let calls = if is_async {
+                    Vec::new()
+                } else {
+                    collect_calls(&f.block, Some(&type_name))
+                };
+                self.methods.push(ItemSummary {
+                    name: format!("{type_name}::{}", f.sig.ident),
+                    kind: SymbolKind::Method,
+                    line: f.sig.ident.span().start().line,
+                    is_async,
+                    calls,
+                });
+            }
+        }
+    }
+}
+
+/// Collect the calls made directly in a function body. `self_type` is the impl
+/// type for methods, used to resolve `self.method()` and `Self::method()`.
+fn collect_calls(block: &syn::Block, self_type: Option<&str>) -> Vec<RawCall> {
+    let mut collector = CallCollector {
+        self_type,
+        calls: Vec::new(),
+        depth: 0,
+    };
+    collector.visit_block(block);
+    collector.calls
+}
+
+struct CallCollector<'a> {
+    self_type: Option<&'a str>,
+    calls: Vec<RawCall>,
+    depth: usize,
+}
+
+impl CallCollector<'_> {
+    fn path_segments(&self, path: &syn::Path) -> Vec<String> {
+        let mut segments: Vec<String> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+        if let (Some(first), Some(ty)) = (segments.first_mut(), self.self_type) {
+            if first == "Self" {
+                *first = ty.to_string();
+            }
+        }
+        segments
+    }
+
+    /// The path at the root of a method receiver chain, if it starts at one.
+    fn receiver_root(&self, expr: &Expr) -> Option<Vec<String>> {
+        match expr {
+            Expr::MethodCall(m) => self.receiver_root(&m.receiver),
+            Expr::Call(c) => match &*c.func {
+                Expr::Path(p) => Some(self.path_segments(&p.path)),
+                _ => None,
+            },
+            Expr::Path(p) => Some(self.path_segments(&p.path)),
+            Expr::Reference(r) => self.receiver_root(&r.expr),
+            Expr::Paren(p) => self.receiver_root(&p.expr),
+            Expr::Field(f) => self.receiver_root(&f.base),
+            Expr::Try(t) => self.receiver_root(&t.expr),
+            _ => None,
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for CallCollector<'_> {
+    fn visit_expr(&mut self, node: &'ast Expr) {
+        if self.depth >= MAX_CALL_EXPR_DEPTH {
+            return;
+        }
+        self.depth += 1;
+        syn::visit::visit_expr(self, node);
+        self.depth -= 1;
+    }
+
+    // Closures are usually handed to something else to run (often an offloader
+    // such as `spawn_blocking`), and async blocks are their own async context, so
+    // neither counts as the function calling what they contain.
+    fn visit_expr_closure(&mut self, _node: &'ast syn::ExprClosure) {}
+
+    fn visit_expr_async(&mut self, _node: &'ast syn::ExprAsync) {}
+
+    // Nested items are functions of their own.
+    fn visit_item(&mut self, _node: &'ast Item) {}
+
+    fn visit_expr_call(&mut self, node: &'ast syn::ExprCall) {
+        if let Expr::Path(p) = &*node.func {
+            if let Some(first) = p.path.segments.first() {
+                self.calls.push(RawCall {
+                    callee: RawCallee::Path(self.path_segments(&p.path)),
+                    line: first.ident.span().start().line,
+                });
+            }
+        }
+        syn::visit::visit_expr_call(self, node);
+    }
+
+    fn visit_expr_method_call(&mut self, node: &'ast syn::ExprMethodCall) {
+        let name = node.method.to_string();
+        let line = node.method.span().start().line;
+        let is_self = matches!(&*node.receiver, Expr::Path(p) if p.path.is_ident("self"));
+        let callee = match (is_self, self.self_type) {
+            (true, Some(ty)) => RawCallee::Path(vec![ty.to_string(), name]),
+            _ => RawCallee::Method {
+                name,
+                receiver: self
+                    .receiver_root(&node.receiver)
+                    .filter(|r| r.first().map(String::as_str) != Some("self")),
+            },
+        };
+        self.calls.push(RawCall { callee, line });
+        syn::visit::visit_expr_method_call(self, node);
+    }
+}
+
+/// Flatten a `use` tree into (leaf-or-alias, segments) pairs.
+fn collect_use_tree(tree: &UseTree, prefix: Vec<String>, out: &mut Vec<(String, Vec<String>)>) {
+    match tree {
+        UseTree::Path(p) => {
+            let mut next = prefix;
+            next.push(p.ident.to_string());
+            collect_use_tree(&p.tree, next, out);
+        }
+        UseTree::Name(n) => {
+            let leaf = n.ident.to_string();
+            // `use foo::{self}` imports `foo` itself.
+            if leaf == "self" {
+                if let Some(last) = prefix.last().cloned() {
+                    out.push((last, prefix));
+                }
+                return;
+            }
+            let mut full = prefix;
+            full.push(leaf.clone());
+            out.push((leaf, full));
+        }
+        UseTree::Rename(r) => {
+            let mut full = prefix;
+            full.push(r.ident.to_string());
+            out.push((r.rename.to_string(), full));
+        }
+        UseTree::Group(g) => {
+            for item in &g.items {
+                collect_use_tree(item, prefix.clone(), out);
+            }
+        }
+        // Glob imports name things we cannot enumerate without expanding them.
+        UseTree::Glob(_) => {}
+    }
+}
+
+/// Names in scope for one module.
+#[derive(Debug, Default)]
+struct ModuleScope {
+    /// Idents of items defined directly in this module (including child modules).
+    items: HashSet<String>,
+    /// Imported leaf-or-alias -> absolute path, e.g. `Pool` -> `crate::db::Pool`,
+    /// `fs` -> `std::fs`.
+    use_map: HashMap<String, String>,
+}
+
+/// Module tree and symbol table for a single crate.
+#[derive(Debug, Default)]
+struct CrateModules {
+    /// The crate root file.
+    root: PathBuf,
+    /// Module path (`crate`, `crate::db`, ...) -> scope.
+    modules: HashMap<String, ModuleScope>,
+    /// Crate-absolute item path -> symbol.
+    symbols: HashMap<String, Symbol>,
+    /// Crate-absolute path of a synchronous function -> its resolved calls.
+    calls: HashMap<String, Vec<CallSite>>,
+}
+
+/// Module graph and symbol table for every crate found in an analysis run.
+#[derive(Debug, Default)]
+pub struct CrateGraph {
+    crates: Vec<CrateModules>,
+    /// File path -> (crate index, module path of the file).
+    file_modules: HashMap<PathBuf, (usize, String)>,
+}
+
+impl CrateGraph {
+    /// Build the graph from per-file summaries.
+    ///
+    /// Every file named `lib.rs` or `main.rs`, and every file directly inside a
+    /// `bin`, `tests`, `benches` or `examples` directory, is treated as a crate
+    /// root. Roots are walked in path order and a file reached from more than
+    /// one root belongs to the first. Files left over afterwards become roots
+    /// of their own.
+    pub fn build(files: &[FileSummary]) -> Self {
+        Self::build_from(files, None)
+    }
+
+    /// Build the graph with an explicit set of crate roots, such as the
+    /// targets reported by `cargo metadata`.
+    ///
+    /// Unlike [`CrateGraph::build`], files not reachable from any of `roots`
+    /// belong to no crate.
+    pub fn build_with_roots(files: &[FileSummary], roots: &[PathBuf]) -> Self {
+        Self::build_from(files, Some(roots))
+    }
+
+    fn build_from(files: &[FileSummary], roots: Option<&[PathBuf]>) -> Self {
+        let mut files: Vec<&FileSummary> = files.iter().collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        let index: HashMap<&Path, usize> = files
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (f.path.as_path(), i))
+            .collect();
+
+        let mut builder = GraphBuilder {
+            files: &files,
+            index: &index,
+            graph: CrateGraph::default(),
+            pending_calls: Vec::new(),
+        };
+        let is_root = |path: &Path| match roots {
+            Some(roots) => roots.iter().any(|r| r == path),
+            None => is_crate_root(path),
+        };
+        for (idx, file) in files.iter().enumerate() {
+            if is_root(&file.path) && !builder.graph.file_modules.contains_key(&file.path) {
+                builder.walk_crate(idx);
+            }
+        }
+        if roots.is_none() {
+            for (idx, file) in files.iter().enumerate() {
+                if !builder.graph.file_modules.contains_key(&file.path) {
+                    builder.walk_crate(idx);
+                }
+            }
+        }
+
+        // Calls can name items re-exported by modules walked later, so they
+        // are resolved only once every module's scope is known.
+        let mut graph = builder.graph;
+        for (krate, module, symbol, raw) in builder.pending_calls {
+            let krate_modules = &graph.crates[krate];
+            let calls = raw
+                .iter()
+                .map(|call| CallSite {
+                    callee: match &call.callee {
+                        RawCallee::Path(segments) => {
+                            Callee::Path(resolve_in(krate_modules, &module, &segments.join("::")))
+                        }
+                        RawCallee::Method { name, receiver } => Callee::Method {
+                            name: name.clone(),
+                            receiver: receiver
+                                .as_ref()
+                                .map(|r| resolve_in(krate_modules, &module, &r.join("::"))),
+                        },
+                    },
+                    line: call.line,
+                })
+                .collect();
+            graph.crates[krate].calls.insert(symbol, calls);
+        }
+        graph
+    }
+
+    /// Module path of a file (e.g. `crate::db`), or `None` if the file is not
+    /// reachable from any crate root.
+    pub fn module_of(&self, file: &Path) -> Option<&str> {
+        self.file_modules.get(file).map(|(_, m)| m.as_str())
+    }
+
+    /// Root file of the crate `file` belongs to.
+    pub fn crate_root(&self, file: &Path) -> Option<&Path> {
+        let (krate, _) = self.file_modules.get(file)?;
+        Some(&self.crates[*krate].root)
+    }
+
+    /// Resolve `path` as written in `file` to its canonical form.
+    ///
+    /// The leading segment is rewritten through the file module's `use` map
+    /// (or `crate` / `self` / `super`), and the result is then chased through
+    /// re-exports in other modules: with `use crate::util::slurp;` in the file
+    /// and `pub use std::fs::read_to_string as slurp;` in `util`, the path
+    /// `slurp` resolves to `std::fs::read_to_string`. Crate-local results start
+    /// with `crate::`; anything else is an external path.
+    ///
+    /// Returns `None` if the file is not part of any crate.
+    pub fn resolve(&self, file: &Path, path: &str) -> Option<String> {
+        let (krate, module) = self.file_modules.get(file)?;
+        Some(resolve_in(&self.crates[*krate], module, path))
+    }
+
+    /// Resolve `path` as written in `file` and return the crate item it names.
+    pub fn symbol(&self, file: &Path, path: &str) -> Option<&Symbol> {
+        let canonical = self.resolve(file, path)?;
+        let (krate, _) = self.file_modules.get(file)?;
+        self.crates[*krate].symbols.get(&canonical)
+    }
+
+    /// Look up an item by canonical path in the crate that `file` belongs to.
+    pub fn lookup(&self, file: &Path, canonical: &str) -> Option<&Symbol> {
+        let (krate, _) = self.file_modules.get(file)?;
+        self.crates[*krate].symbols.get(canonical)
+    }
+
+    /// The calls made from a synchronous function's body, in source order.
+    /// Empty for async functions and non-function symbols.
+    pub fn calls(&self, symbol: &Symbol) -> &[CallSite] {
+        self.file_modules
+            .get(&symbol.file)
+            .and_then(|(krate, _)| self.crates[*krate].calls.get(&symbol.path))
+            .map(Vec::as_slice)
+            .unwrap_or_default()
+    }
+
+    /// The `use` map of a file's module with every target fully resolved,
+    /// i.e. leaf-or-alias -> canonical path after following re-exports.
+    pub fn resolved_imports(&self, file: &Path) -> HashMap<String, String> {
+        let Some((krate, module)) = self.file_modules.get(file) else {
+            return HashMap::new();
+        };
+        let Some(scope) = self.crates[*krate].modules.get(module) else {
+            return HashMap::new();
+        };
+        scope
+            .use_map
+            .keys()
+            .filter_map(|name| Some((name.clone(), self.resolve(file, name)?)))
+            .collect()
+    }
+}
+
+/// Transient state while walking module trees.
+struct GraphBuilder<'f> {
+    files: &'f [&'f FileSummary],
+    index: &'f HashMap<&'f Path, usize>,
+    graph: CrateGraph,
+    /// (crate, module, caller path, calls) awaiting resolution.
+    pending_calls: Vec<(usize, String, String, &'f [RawCall])>,
+}
+
+impl<'f> GraphBuilder<'f> {
+    fn walk_crate(&mut self, root: usize) {
+        let krate = self.graph.crates.len();
+        self.graph.crates.push(CrateModules {
+            root: self.files[root].path.clone(),
+            ..CrateModules::default()
+        });
+        let dir = self.files[root]
+            .path
+            .parent()
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        self.walk_file(krate, root, "crate".to_string(), dir);
+    }
+
+    fn walk_file(&mut self, krate: usize, file_idx: usize, module: String, child_dir: PathBuf) {
+        let files = self.files;
+        let file = &files[file_idx];
+        if self.graph.file_modules.contains_key(&file.path) {
+            return;
+        }
+        self.graph
+            .file_modules
+            .insert(file.path.clone(), (krate, module.clone()));
+        self.walk_module(krate, &file.path, &module, &file.module, &child_dir);
+    }
+
+    fn walk_module(
+        &mut self,
+        krate: usize,
+        file_path: &Path,
+        module: &str,
+        summary: &'f ModuleSummary,
+        child_dir: &Path,
+    ) {
+        let crate_modules = &mut self.graph.crates[krate];
+
+        let mut scope = ModuleScope::default();
+        for item in summary.items.iter().chain(&summary.methods) {
+            if item.kind != SymbolKind::Method {
+                scope.items.insert(item.name.clone());
+            }
+            let path = format!("{module}::{}", item.name);
+            // The first definition of a path wins; later duplicates come from
+            // `cfg`-gated alternatives or trait impls sharing a method name.
+            if crate_modules.symbols.contains_key(&path) {
+                continue;
+            }
+            if !item.calls.is_empty() {
+                self.pending_calls
+                    .push((krate, module.to_string(), path.clone(), &item.calls));
+            }
+            crate_modules.symbols.insert(
+                path.clone(),
+                Symbol {
+                    kind: item.kind,
+                    path,
+                    file: file_path.to_path_buf(),
+                    line: item.line,
+                    is_async: item.is_async,
+                },
+            );
+        }
+        for (alias, segments) in &summary.uses {
+            let target = absolute_use_path(module, &scope.items, segments);
+            scope.use_map.insert(alias.clone(), target);
+        }
+        crate_modules.modules.insert(module.to_string(), scope);
+
+        for m in &summary.mods {
+            let child_module = format!("{module}::{}", m.name);
+            match &m.inline {
+                Some(inline) => {
+                    let dir = match &m.path_attr {
+                        Some(p) => child_dir.join(p),
+                        None => child_dir.join(&m.name),
+                    };
+                    self.walk_module(krate, file_path, &child_module, inline, &dir);
+                }
+                None => {
+                    let candidates = match &m.path_attr {
+                        Some(p) => vec![file_path.parent().unwrap_or(Path::new("")).join(p)],
+                        None => vec![
+                            child_dir.join(format!("{}.rs", m.name)),
+                            child_dir.join(&m.name).join("mod.rs"),
+                        ],
+                    };
+                    let found = candidates
+                        .iter()
+                        .find_map(|c| self.index.get(c.as_path()).copied());
+                    if let Some(idx) = found {
+                        let dir = module_child_dir(&self.files[idx].path);
+                        self.walk_file(krate, idx, child_module, dir);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Turn the segments of a `use` path into an absolute path: `crate::`, `self::`
+/// and `super::` are resolved against `module`, and a leading name that is an
+/// item of the module itself (2018-edition relative import) is prefixed with
+/// the module path. Anything else is an external crate path and kept as-is.
+fn absolute_use_path(module: &str, module_items: &HashSet<String>, segments: &[String]) -> String {
+    let Some(first) = segments.first() else {
+        return String::new();
+    };
+    match first.as_str() {
+        "crate" => segments.join("::"),
+        "self" | "super" => relative_path(module, segments).join("::"),
+        name if module_items.contains(name) => format!("{module}::{}", segments.join("::")),
+        _ => segments.join("::"),
+    }
+}
+
+/// Resolve leading `self` / `super` segments against `module`.
+fn relative_path(module: &str, segments: &[String]) -> Vec<String> {
+    let mut base: Vec<String> = module.split("::").map(str::to_string).collect();
+    let mut rest = segments;
+    while let Some(first) = rest.first() {
+        match first.as_str() {
+            "self" => {}
+            "super" => {
+                if base.len() > 1 {
+                    base.pop();
+                }
+            }
+            _ => break,
+        }
+        rest = &rest[1..];
+    }
+    base.extend(rest.iter().cloned());
+    base
+}
+
+/// Resolve `path` as written in `module`: absolutize its leading segment, then
+/// chase re-exports.
+fn resolve_in(krate: &CrateModules, module: &str, path: &str) -> String {
+    let mut segments = absolutize(krate, module, path);
+    for _ in 0..MAX_REEXPORT_HOPS {
+        match follow_reexport(krate, &segments) {
+            Some(next) => segments = next,
+            None => break,
+        }
+    }
+    segments.join("::")
+}
+
+/// Rewrite the leading segment of `path` through `module`'s scope.
+fn absolutize(krate: &CrateModules, module: &str, path: &str) -> Vec<String> {
+    let segments: Vec<String> = path.split("::").map(str::to_string).collect();
+    let Some(first) = segments.first() else {
+        return segments;
+    };
+    match first.as_str() {
+        "crate" => segments,
+        "self" | "super" => relative_path(module, &segments),
+        name => {
+            let Some(scope) = krate.modules.get(module) else {
+                return segments;
+            };
+            if scope.items.contains(name) {
+                let mut out: Vec<String> = module.split("::").map(str::to_string).collect();
+                out.extend(segments);
+                out
+            } else if let Some(target) = scope.use_map.get(name) {
+                let mut out: Vec<String> = target.split("::").map(str::to_string).collect();
+                out.extend(segments.into_iter().skip(1));
+                out
+            } else {
+                segments
+            }
+        }
+    }
+}
+
+/// If some prefix of `segments` names a crate module that re-exports (rather
+/// than defines) the next segment, rewrite the path through that `use`.
+fn follow_reexport(krate: &CrateModules, segments: &[String]) -> Option<Vec<String>> {
+    if segments.first().map(String::as_str) != Some("crate") {
+        return None;
+    }
+    for i in 1..segments.len() {
+        let module = segments[..i].join("::");
+        let scope = krate.modules.get(&module)?;
+        let name = &segments[i];
+        if scope.items.contains(name) {
+            continue;
+        }
+        let target = scope.use_map.get(name)?;
+        let mut out: Vec<String> = target.split("::").map(str::to_string).collect();
+        out.extend(segments[i + 1..].iter().cloned());
+        return Some(out);
+    }
+    None
+}
+
+/// Directory in which a file module's own `mod foo;` children live: next to
+/// `mod.rs` / `lib.rs` / `main.rs`, or in a same-named directory for `foo.rs`.
+fn module_child_dir(path: &Path) -> PathBuf {
+    let parent = path.parent().unwrap_or(Path::new(""));
+    match path.file_stem().and_then(|s| s.to_str()) {
+        Some("mod") | Some("lib") | Some("main") | None => parent.to_path_buf(),
+        Some(stem) => parent.join(stem),
+    }
+}
+
+/// True if a file is the root of a crate by Cargo's conventional layout.
+fn is_crate_root(path: &Path) -> bool {
+    let file_name = path.file_name().and_then(|s| s.to_str());
+    if matches!(file_name, Some("lib.rs") | Some("main.rs")) {
+        return true;
+    }
+    let parent = path
+        .parent()
+        .and_then(|p| p.file_name())
+        .and_then(|s| s.to_str());
+    matches!(
+        parent,
+        Some("bin") | Some("tests") | Some("benches") | Some("examples")
+    )
+}
+
+/// The value of a `#[path = "..."]` attribute, if present.
+fn path_attr(attrs: &[syn::Attribute]) -> Option<String> {
+    attrs.iter().find_map(|attr| {
+        if !attr.path().is_ident("path") {
+            return None;
+        }
+        let syn::Meta::NameValue(nv) = &attr.meta else {
+            return None;
+        };
+        match &nv.value {
+            syn::Expr::Lit(syn::ExprLit {
+                lit: syn::Lit::Str(s),
+                ..
+            }) => Some(s.value()),
+            _ => None,
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn graph(files: &[(&str, &str)]) -> CrateGraph {
+        let summaries: Vec<FileSummary> = files
+            .iter()
+            .map(|(path, src)| {
+                FileSummary::from_ast(Path::new(path), &syn::parse_file(src).expect("parse"))
+            })
+            .collect();
+        CrateGraph::build(&summaries)
+    }
+
+    #[test]
+    fn test_module_tree_from_mod_declarations() {
+        let g = graph(&[
+            ("src/lib.rs", "mod db; mod api;"),
+            ("src/db.rs", "pub struct Pool;"),
+            ("src/api/mod.rs", "mod handlers;"),
+            ("src/api/handlers.rs", "pub fn list_users() {}"),
+        ]);
+        assert_eq!(g.module_of(Path::new("src/lib.rs")), Some("crate"));
+        assert_eq!(g.module_of(Path::new("src/db.rs")), Some("crate::db"));
+        assert_eq!(
+            g.module_of(Path::new("src/api/handlers.rs")),
+            Some("crate::api::handlers")
+        );
+    }
+
+    #[test]
+    fn test_non_mod_rs_children_live_in_named_directory() {
+        let g = graph(&[
+            ("src/lib.rs", "mod api;"),
+            ("src/api.rs", "mod handlers;"),
+            ("src/api/handlers.rs", "pub fn list() {}"),
+        ]);
+        assert_eq!(
+            g.module_of(Path::new("src/api/handlers.rs")),
+            Some("crate::api::handlers")
+        );
+    }
+
+    #[test]
+    fn test_unreachable_file_is_its_own_crate() {
+        let g = graph(&[
+            ("src/lib.rs", "mod db; pub fn shared() {}"),
+            ("src/db.rs", ""),
+            ("src/dead.rs", "fn local() {}"),
+        ]);
+        let dead = Path::new("src/dead.rs");
+        assert_eq!(g.module_of(dead), Some("crate"));
+        assert!(g.symbol(dead, "local").is_some());
+        assert!(g.symbol(dead, "shared").is_none());
+    }
+
+    #[test]
+    fn test_explicit_roots_leave_unreachable_files_out() {
+        let summaries: Vec<FileSummary> = [
+            ("src/tool.rs", "mod db; fn main() {}"),
+            ("src/db.rs", "pub fn q() {}"),
+            ("src/dead.rs", "fn local() {}"),
+            ("src/lib.rs", ""),
+        ]
+        .iter()
+        .map(|(path, src)| {
+            FileSummary::from_ast(Path::new(path), &syn::parse_file(src).expect("parse"))
+        })
+        .collect();
+        let g = CrateGraph::build_with_roots(&summaries, &[PathBuf::from("src/tool.rs")]);
+
+        assert_eq!(g.module_of(Path::new("src/db.rs")), Some("crate::db"));
+        assert_eq!(
+            g.crate_root(Path::new("src/db.rs")),
+            Some(Path::new("src/tool.rs"))
+        );
+        assert_eq!(g.module_of(Path::new("src/dead.rs")), None);
+        assert_eq!(g.module_of(Path::new("src/lib.rs")), None);
+    }
+
+    #[test]
+    fn test_resolve_use_crate_path_across_files() {
+        let g = graph(&[
+            ("src/lib.rs", "mod db; mod api;"),
+            (
+                "src/db.rs",
+                "pub struct Pool; impl Pool { pub fn get(&self) {} }",
+            ),
+            ("src/api.rs", "use crate::db::Pool;"),
+        ]);
+        let api = Path::new("src/api.rs");
+        assert_eq!(
+            g.resolve(api, "Pool::get").as_deref(),
+            Some("crate::db::Pool::get")
+        );
+        let sym = g.symbol(api, "Pool").expect("Pool resolves");
+        assert_eq!(sym.kind, SymbolKind::Struct);
+        assert_eq!(sym.file, PathBuf::from("src/db.rs"));
+        assert_eq!(g.symbol(api, "Pool::get").unwrap().kind, SymbolKind::Method);
+    }
+
+    #[test]
+    fn test_resolve_follows_reexports() {
+        let g = graph(&[
+            ("src/lib.rs", "mod util; mod api;"),
+            ("src/util.rs", "pub use std::fs::read_to_string as slurp;"),
+            ("src/api.rs", "use crate::util::slurp;"),
+        ]);
+        assert_eq!(
+            g.resolve(Path::new("src/api.rs"), "slurp").as_deref(),
+            Some("std::fs::read_to_string")
+        );
+    }
+
+    #[test]
+    fn test_resolve_self_and_super() {
+        let g = graph(&[
+            ("src/lib.rs", "mod a; pub fn top() {}"),
+            ("src/a.rs", "mod b; pub fn helper() {}"),
+            ("src/a/b.rs", "use super::helper; use super::super::top;"),
+        ]);
+        let b = Path::new("src/a/b.rs");
+        assert_eq!(g.resolve(b, "helper").as_deref(), Some("crate::a::helper"));
+        assert_eq!(g.resolve(b, "top").as_deref(), Some("crate::top"));
+        assert_eq!(g.resolve(b, "self::x").as_deref(), Some("crate::a::b::x"));
+    }
+
+    #[test]
+    fn test_external_paths_are_unchanged() {
+        let g = graph(&[("src/lib.rs", "use std::fs; use tokio::fs as tfs;")]);
+        let lib = Path::new("src/lib.rs");
+        assert_eq!(g.resolve(lib, "fs::read").as_deref(), Some("std::fs::read"));
+        assert_eq!(
+            g.resolve(lib, "tfs::read").as_deref(),
+            Some("tokio::fs::read")
+        );
+        assert_eq!(
+            g.resolve(lib, "serde::Deserialize").as_deref(),
+            Some("serde::Deserialize")
+        );
+    }
+
+    #[test]
+    fn test_inline_module_and_path_attribute() {
+        let g = graph(&[
+            (
+                "src/lib.rs",
+                "mod outer { pub mod inner { pub fn f() {} } } #[path = \"odd_name.rs\"] mod renamed;",
+            ),
+            ("src/odd_name.rs", "pub fn g() {}"),
+        ]);
+        let lib = Path::new("src/lib.rs");
+        assert!(g.symbol(lib, "outer::inner::f").is_some());
+        assert_eq!(
+            g.module_of(Path::new("src/odd_name.rs")),
+            Some("crate::renamed")
+        );
+    }
+
+    #[test]
+    fn test_async_fn_symbols_are_marked() {
+        let g = graph(&[("src/main.rs", "async fn serve() {} fn load() {}")]);
+        let main = Path::new("src/main.rs");
+        assert!(g.symbol(main, "serve").unwrap().is_async);
+        assert!(!g.symbol(main, "load").unwrap().is_async);
+    }
+
+    #[test]
+    fn test_separate_crate_roots_do_not_share_symbols() {
+        let g = graph(&[
+            ("src/lib.rs", "pub fn shared() {}"),
+            ("src/bin/tool.rs", "fn main() {}"),
+        ]);
+        assert!(g.symbol(Path::new("src/lib.rs"), "shared").is_some());
+        assert!(g.symbol(Path::new("src/bin/tool.rs"), "shared").is_none());
+    }
+
+    #[test]
+    fn test_resolved_imports() {
+        let g = graph(&[
+            ("src/lib.rs", "mod compat; mod api;"),
+            ("src/compat.rs", "pub use std::fs;"),
+            ("src/api.rs", "use crate::compat::fs;"),
+        ]);
+        let imports = g.resolved_imports(Path::new("src/api.rs"));
+        assert_eq!(imports.get("fs").map(String::as_str), Some("std::fs"));
+    }
+
+    #[test]
+    fn test_calls_are_resolved_in_caller_module() {
+        let g = graph(&[
+            ("src/lib.rs", "mod config; mod util;"),
+            ("src/util.rs", "pub use std::fs::read_to_string as slurp;"),
+            (
+                "src/config.rs",
+                r#"
+                use crate::util::slurp;
+                use std::process::Command;
+                pub fn load() -> String { read_raw() }
+                fn read_raw() -> String {
+                    Command::new("ls").output();
+                    slurp("a").unwrap()
+                }
+                "#,
+            ),
+        ]);
+        let config = Path::new("src/config.rs");
+        let load = g.symbol(config, "load").unwrap();
+        assert_eq!(
+            g.calls(load).iter().map(|c| &c.callee).collect::<Vec<_>>(),
+            vec![&Callee::Path("crate::config::read_raw".to_string())]
+        );
+        let read_raw = g.symbol(config, "read_raw").unwrap();
+        let callees: Vec<&Callee> = g.calls(read_raw).iter().map(|c| &c.callee).collect();
+        assert!(callees.contains(&&Callee::Method {
+            name: "output".to_string(),
+            receiver: Some("std::process::Command::new".to_string()),
+        }));
+        assert!(callees.contains(&&Callee::Path("std::fs::read_to_string".to_string())));
+    }
+
+    #[test]
+    fn test_calls_resolve_self_and_skip_closures() {
+        let g = graph(&[(
+            "src/lib.rs",
+            r#"
+            struct Store;
+            impl Store {
+                fn get(&self) { self.load(); Self::parse(); }
+                fn load(&self) {}
+                fn parse() {}
+                fn offload(&self) { run(|| std::fs::read("x")); }
+                async fn fetch(&self) { self.load(); }
+            }
+            fn run<F>(_f: F) {}
+            "#,
+        )]);
+        let lib = Path::new("src/lib.rs");
+        let get = g.symbol(lib, "Store::get").unwrap();
+        let callees: Vec<&Callee> = g.calls(get).iter().map(|c| &c.callee).collect();
+        assert_eq!(
+            callees,
+            vec![
+                &Callee::Path("crate::Store::load".to_string()),
+                &Callee::Path("crate::Store::parse".to_string()),
+            ]
+        );
+        let offload = g.symbol(lib, "Store::offload").unwrap();
+        assert_eq!(
+            g.calls(offload).len(),
+            1,
+            "closure body is not a call of offload"
+        );
+        assert!(g.calls(g.symbol(lib, "Store::fetch").unwrap()).is_empty());
+    }
+}
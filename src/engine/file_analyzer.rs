@@ -7,19 +7,21 @@ use crate::discovery::MAX_FILE_SIZE;
 use crate::engine::context::AnalysisContext;
 use crate::engine::parser;
 use crate::error::{Error, Result};
-use crate::rules::{Diagnostic, Rule};
+use crate::rules::suppression_rules::{self, UNUSED_SUPPRESSION};
+use crate::rules::{registry, Diagnostic, Rule};
 use crate::suppression::SuppressionExtractor;
 use crate::Config;
 use std::any::Any;
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::Read;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 /// Extract a human-readable message from a panic payload.
 ///
 /// Panic payloads can be String, &str, or other types. This function
 /// attempts to extract a useful message from common panic payload types.
-fn extract_panic_message(payload: &Box<dyn Any + Send>) -> String {
+pub(crate) fn extract_panic_message(payload: &Box<dyn Any + Send>) -> String {
     // Try to extract &str
     if let Some(s) = payload.downcast_ref::<&str>() {
         return (*s).to_string();
@@ -57,26 +59,56 @@ pub fn read_file_secure(file_path: &Path) -> Result<String> {
     }
 
     // Check file size via fd metadata
-    if metadata.len() > MAX_FILE_SIZE {
+    check_file_size(file_path, metadata.len())?;
+
+    // Read from the same file descriptor
+    let mut source = String::with_capacity(metadata.len() as usize);
+    file.read_to_string(&mut source)
+        .map_err(|e| Error::io(file_path, e))?;
+
+    Ok(source)
+}
+
+/// Reject sources larger than [`MAX_FILE_SIZE`], on disk or in memory.
+pub(crate) fn check_file_size(file_path: &Path, len: u64) -> Result<()> {
+    if len > MAX_FILE_SIZE {
         return Err(Error::io(
             file_path,
             std::io::Error::new(
                 std::io::ErrorKind::InvalidInput,
                 format!(
                     "file too large: {} bytes (max: {} bytes)",
-                    metadata.len(),
-                    MAX_FILE_SIZE
+                    len, MAX_FILE_SIZE
                 ),
             ),
         ));
     }
+    Ok(())
+}
 
-    // Read from the same file descriptor
-    let mut source = String::with_capacity(metadata.len() as usize);
-    file.read_to_string(&mut source)
-        .map_err(|e| Error::io(file_path, e))?;
+/// A source file that has been read and parsed.
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub source: String,
+    pub ast: syn::File,
+}
 
-    Ok(source)
+/// Read and parse a file once so it can be reused across analysis passes.
+pub fn load_source_file(file_path: &Path) -> Result<SourceFile> {
+    // Read file with TOCTOU-safe handling
+    let source = read_file_secure(file_path)?;
+    let ast = parse_source(file_path, &source)?;
+
+    Ok(SourceFile {
+        path: file_path.to_path_buf(),
+        source,
+        ast,
+    })
+}
+
+/// Parse source read from `file_path`, attributing errors to that path.
+pub fn parse_source(file_path: &Path, source: &str) -> Result<syn::File> {
+    parser::parse_file(source).map_err(|e| Error::parse(file_path, e.to_string()))
 }
 
 /// Analyze a single file with a given set of rules.
@@ -89,6 +121,9 @@ pub fn read_file_secure(file_path: &Path) -> Result<String> {
 /// - Rule execution with panic catching
 /// - Diagnostic filtering
 ///
+/// The file is analyzed in isolation; use [`check_with_rules`] with a context
+/// carrying a [`crate::engine::CrateGraph`] for cross-file resolution.
+///
 /// # Arguments
 ///
 /// * `file_path` - Path to the Rust source file
@@ -106,23 +141,41 @@ pub fn analyze_file_with_rules<'a, I>(
 where
     I: Iterator<Item = &'a dyn Rule>,
 {
-    // Read file with TOCTOU-safe handling
-    let source = read_file_secure(file_path)?;
-
-    // Parse the source
-    let ast = parser::parse_file(&source).map_err(|e| Error::parse(file_path, e.to_string()))?;
+    let file = load_source_file(file_path)?;
 
     // Create analysis context
-    let ctx = AnalysisContext::new(file_path, &source, &ast, config);
+    let config = config.for_file(&file.path, None);
+    let ctx = AnalysisContext::new(&file.path, &file.source, &file.ast, &config);
+
+    Ok(check_with_rules(&ctx, rules))
+}
+
+/// Run rules against an already-built analysis context.
+///
+/// Applies the context's config (rule enablement and severity overrides) and
+/// the file's inline suppressions, and catches panics from individual rules.
+/// When `unused-suppression` is among the rules, the suppressions that
+/// suppressed nothing are reported once the other rules have run.
+pub fn check_with_rules<'a, I>(ctx: &AnalysisContext, rules: I) -> Vec<Diagnostic>
+where
+    I: Iterator<Item = &'a dyn Rule>,
+{
+    let config = ctx.config;
 
     // Extract suppressions for this file
-    let suppressions = SuppressionExtractor::new(&source, &ast);
+    let suppressions = SuppressionExtractor::new(ctx.source, ctx.ast);
+    // (suppression, rule) pairs that suppressed a diagnostic
+    let mut used = HashSet::new();
+    let mut known: HashSet<&str> = registry::rule_ids().collect();
+    let mut ran = HashSet::new();
+    let mut check_suppressions = false;
 
     // Run rules and collect diagnostics
     // cargo-perf-ignore: vec-no-capacity
     let mut diagnostics = Vec::new();
 
     for rule in rules {
+        known.insert(rule.id());
         // Check if rule is enabled via config
         if config
             .rule_severity(rule.id(), rule.default_severity())
@@ -130,6 +183,11 @@ where
         {
             continue;
         }
+        if rule.id() == UNUSED_SUPPRESSION {
+            ran.insert(rule.id());
+            check_suppressions = true;
+            continue;
+        }
 
         // Explicit `warn`/`deny` config overrides the rule's intrinsic severity.
         // Computed once per rule, outside the per-diagnostic loop.
@@ -137,7 +195,7 @@ where
 
         // Catch panics in rule execution to prevent one bad rule from crashing analysis
         let rule_diagnostics =
-            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| rule.check(&ctx))) {
+            match std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| rule.check(ctx))) {
                 Ok(diags) => diags,
                 Err(panic_payload) => {
                     // Extract panic message from the payload
@@ -145,25 +203,43 @@ where
                     eprintln!(
                         "Warning: Rule '{}' panicked while analyzing {}: {}",
                         rule.id(),
-                        file_path.display(),
+                        ctx.file_path.display(),
                         panic_msg
                     );
                     continue;
                 }
             };
+        // Only a rule that completed shows which of its suppressions are unused
+        ran.insert(rule.id());
 
         // Apply any configured severity override, then filter out suppressed diagnostics.
         for mut diag in rule_diagnostics {
             if let Some(severity) = severity_override {
                 diag.severity = severity;
             }
-            if !suppressions.is_suppressed(diag.rule_id, diag.line) {
+            let mut suppressed = false;
+            for suppression in suppressions.matching(diag.rule_id, diag.line) {
+                used.insert(suppression);
+                suppressed = true;
+            }
+            if !suppressed {
                 diagnostics.push(diag);
             }
         }
     }
 
-    Ok(diagnostics)
+    if check_suppressions {
+        let severity_override = config.severity_override(UNUSED_SUPPRESSION);
+        let found = suppression_rules::check_suppressions(ctx, &suppressions, &used, &ran, &known);
+        for mut diag in found {
+            if let Some(severity) = severity_override {
+                diag.severity = severity;
+            }
+            diagnostics.push(diag);
+        }
+    }
+
+    diagnostics
 }
 
 #[cfg(test)]
@@ -1,24 +1,46 @@
 //! Analysis engine - coordinates file discovery and rule execution.
 
+pub mod cache;
 mod context;
+pub mod crate_graph;
 pub mod file_analyzer;
 pub mod parser;
 
+pub use cache::AnalysisCache;
 pub use context::{AnalysisContext, LineIndex};
-pub use file_analyzer::{analyze_file_with_rules, read_file_secure};
+pub use crate_graph::{CallSite, Callee, CrateGraph, FileSummary, Symbol, SymbolKind};
+pub use file_analyzer::{
+    analyze_file_with_rules, check_with_rules, load_source_file, parse_source, read_file_secure,
+    SourceFile,
+};
 pub use parser::{parse_file, ParseError};
 
+use crate::diff::ChangedLines;
 use crate::discovery::{discover_rust_files, DiscoveryOptions};
 use crate::error::{Error, Result};
-use crate::rules::{registry, Diagnostic};
+use crate::rules::selection::RuleSelection;
+use crate::rules::{registry, Diagnostic, Rule};
+use crate::workspace::{Package, TargetInfo};
 use crate::Config;
-use rayon::prelude::*;
+use cache::CachedFile;
+use file_analyzer::extract_panic_message;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
-use std::sync::atomic::{AtomicUsize, Ordering};
-use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier, Mutex, OnceLock};
 
 pub struct Engine<'a> {
     config: &'a Config,
+    cache_dir: Option<PathBuf>,
+    packages: Option<Vec<Package>>,
+    rules: RuleSelection,
+    changed: Option<ChangedLines>,
+    /// Whether `cache` is kept between runs (see [`Engine::with_resident_cache`]).
+    resident: bool,
+    cache: Arc<Mutex<Option<AnalysisCache>>>,
+    cancel: Option<Arc<AtomicBool>>,
 }
 
 /// Progress information for streaming analysis.
@@ -30,11 +52,90 @@ pub struct AnalysisProgress {
     pub total_files: usize,
     /// Number of diagnostics found so far
     pub diagnostics_found: usize,
+    /// Number of analyzed files whose diagnostics were replayed from the cache
+    pub files_cached: usize,
 }
 
 impl<'a> Engine<'a> {
     pub fn new(config: &'a Config) -> Self {
-        Self { config }
+        Self {
+            config,
+            cache_dir: None,
+            packages: None,
+            rules: RuleSelection::all(),
+            changed: None,
+            resident: false,
+            cache: Arc::new(Mutex::new(None)),
+            cancel: None,
+        }
+    }
+
+    /// Use an incremental analysis cache stored in `dir` (see [`cache`]).
+    ///
+    /// Unchanged files are not re-parsed and replay their stored diagnostics.
+    /// Failing to write the cache is reported as a warning, not an error.
+    pub fn with_cache(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Analyze the build targets of workspace `packages` (see [`crate::workspace`])
+    /// instead of every `.rs` file under the analyzed path.
+    ///
+    /// Only files reachable from a target root are checked, and diagnostics
+    /// carry the [`TargetInfo`] of their target. Files are reported relative to
+    /// the analyzed path when they lie inside it. When the path is a directory
+    /// inside a package, the whole package is read for cross-file resolution
+    /// but only diagnostics under the path are returned.
+    pub fn with_packages(mut self, packages: Vec<Package>) -> Self {
+        self.packages = Some(packages);
+        self
+    }
+
+    /// Run only the rules in `selection` (see [`RuleSelection`]).
+    pub fn with_rules(mut self, selection: RuleSelection) -> Self {
+        self.rules = selection;
+        self
+    }
+
+    /// Keep the analysis cache in memory between [`Engine::analyze`] calls,
+    /// for a long-running process such as `cargo perf watch`.
+    ///
+    /// Each run then re-parses only files whose content changed and re-checks
+    /// only those and the files of crates whose items changed; everything else
+    /// is replayed. Combined with [`Engine::with_cache`], the cache is loaded
+    /// from disk once and saved after every run.
+    pub fn with_resident_cache(mut self) -> Self {
+        self.resident = true;
+        self
+    }
+
+    /// Keep the analysis cache in memory in `cache`, which other engines can
+    /// share, as with [`Engine::with_resident_cache`].
+    ///
+    /// This lets a long-running process whose config changes, such as the
+    /// LSP server, keep parsed summaries across engines. Diagnostics are only
+    /// replayed for the config and rules they were computed under.
+    pub fn with_shared_cache(mut self, cache: Arc<Mutex<Option<AnalysisCache>>>) -> Self {
+        self.resident = true;
+        self.cache = cache;
+        self
+    }
+
+    /// Check only files in `changed` and report only diagnostics on their
+    /// changed lines (see [`crate::diff`]).
+    ///
+    /// Unchanged files are still read so names they define resolve.
+    pub fn with_changed_lines(mut self, changed: ChangedLines) -> Self {
+        self.changed = Some(changed);
+        self
+    }
+
+    /// Stop analyzing once `flag` is set, for example when an editor cancels
+    /// a request. The run then fails with [`Error::Cancelled`].
+    pub fn with_cancellation(mut self, flag: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(flag);
+        self
     }
 
     pub fn analyze(&self, path: &Path) -> Result<Vec<Diagnostic>> {
@@ -71,63 +172,716 @@ impl<'a> Engine<'a> {
         F: Fn(AnalysisProgress) + Send + Sync,
     {
         // First, collect all valid file paths (sequential - fast)
-        let files = self.collect_files(path);
-        let total_files = files.len();
-
-        // Shared counters for progress tracking
-        let files_analyzed = AtomicUsize::new(0);
-        let diagnostics_found = AtomicUsize::new(0);
-        let errors: Mutex<Vec<(PathBuf, Error)>> = Mutex::new(Vec::new());
-
-        // Analyze files in parallel
-        let all_diagnostics: Vec<Diagnostic> = files
-            .par_iter()
-            .flat_map(|file_path| {
-                let result = match self.analyze_file(file_path) {
-                    Ok(diagnostics) => diagnostics,
-                    Err(e) => {
-                        // Collect errors but continue analyzing other files
-                        if let Ok(mut errs) = errors.lock() {
-                            errs.push((file_path.clone(), e));
-                        }
-                        Vec::new()
-                    }
-                };
+        let plan = match &self.packages {
+            Some(packages) => PackagePlan::new(path, packages),
+            None => PackagePlan {
+                files: self.collect_files(path),
+                ..PackagePlan::default()
+            },
+        };
 
-                // Update progress
-                let analyzed = files_analyzed.fetch_add(1, Ordering::Relaxed) + 1;
-                let found =
-                    diagnostics_found.fetch_add(result.len(), Ordering::Relaxed) + result.len();
+        // Use the static registry rules, narrowed to the selection
+        let rules = self
+            .rules
+            .select(registry::all_rules().iter().map(|r| r.as_ref()));
 
-                progress_callback(AnalysisProgress {
-                    files_analyzed: analyzed,
-                    total_files,
-                    diagnostics_found: found,
-                });
+        // Held for the whole run, so concurrent runs of a resident cache
+        // take turns instead of racing on it.
+        let mut resident = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let mut cache = self.open_cache(&mut resident, &rules);
 
-                result
-            })
+        // Analyzed path -> canonical path, for the files that changed
+        let changed_files: Option<HashMap<&PathBuf, PathBuf>> =
+            self.changed.as_ref().map(|changed| {
+                plan.files
+                    .iter()
+                    .map(|file| (file, file.canonicalize().unwrap_or_else(|_| file.clone())))
+                    .filter(|(_, canonical)| changed.contains_file(canonical))
+                    .collect()
+            });
+        let checked: Option<HashSet<PathBuf>> = changed_files
+            .as_ref()
+            .map(|files| files.keys().map(|&file| file.clone()).collect());
+
+        let options = FileOptions {
+            targets: self.packages.as_ref().map(|_| &plan.targets),
+            checked: checked.as_ref(),
+            overlay: None,
+            cancel: self.cancel.as_deref(),
+        };
+        let mut diagnostics = analyze_files(
+            &plan.files,
+            options,
+            self.config,
+            &rules,
+            cache.as_mut(),
+            progress_callback,
+        );
+        self.close_cache(&mut resident, cache);
+
+        // What was analyzed is cached, but the results are incomplete
+        if self
+            .cancel
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        {
+            return Err(Error::Cancelled);
+        }
+
+        diagnostics.retain(|d| !plan.context_only.contains(&d.file_path));
+        if let (Some(changed), Some(files)) = (&self.changed, &changed_files) {
+            diagnostics.retain(|d| {
+                files
+                    .get(&d.file_path)
+                    .is_some_and(|file| changed.touches(file, d.line, d.end_line.unwrap_or(d.line)))
+            });
+        }
+        Ok(diagnostics)
+    }
+
+    /// Analyze in-memory `source` as if it were the file at `path_hint`,
+    /// without touching disk.
+    ///
+    /// The same rules, inline suppressions and config apply as for a file on
+    /// disk, with `[[overrides]]` matched against `path_hint`. Names are
+    /// resolved within the source only; use [`Engine::analyze_sources`] to
+    /// resolve across several files. Diagnostics carry `path_hint` as their
+    /// path.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `source` does not parse or is larger than the file size limit.
+    pub fn analyze_source(
+        &self,
+        path_hint: impl AsRef<Path>,
+        source: &str,
+    ) -> Result<Vec<Diagnostic>> {
+        let path = path_hint.as_ref();
+        file_analyzer::check_file_size(path, source.len() as u64)?;
+        let ast = parse_source(path, source)?;
+        let graph = CrateGraph::build(&[FileSummary::from_ast(path, &ast)]);
+        let rules = self
+            .rules
+            .select(registry::all_rules().iter().map(|r| r.as_ref()));
+        let config = self.config.for_file(path, None);
+        let ctx = AnalysisContext::new(path, source, &ast, &config).with_crate_graph(&graph);
+        Ok(check_with_rules(&ctx, rules.iter().copied()))
+    }
+
+    /// Analyze a virtual file set of `(path, source)` pairs as one run,
+    /// without touching disk.
+    ///
+    /// Files resolve names across each other as files under one directory do
+    /// with [`Engine::analyze`]. Workspace packages, changed lines and the
+    /// cache are not used. Files that fail to parse are reported as warnings
+    /// and skipped.
+    pub fn analyze_sources<I, P, S>(&self, files: I) -> Result<Vec<Diagnostic>>
+    where
+        I: IntoIterator<Item = (P, S)>,
+        P: Into<PathBuf>,
+        S: Into<String>,
+    {
+        let overlay: HashMap<PathBuf, String> = files
+            .into_iter()
+            .map(|(path, source)| (path.into(), source.into()))
+            .collect();
+        let mut paths: Vec<PathBuf> = overlay.keys().cloned().collect();
+        paths.sort();
+        let rules = self
+            .rules
+            .select(registry::all_rules().iter().map(|r| r.as_ref()));
+        let options = FileOptions {
+            overlay: Some(&overlay),
+            ..FileOptions::default()
+        };
+        Ok(analyze_files(
+            &paths,
+            options,
+            self.config,
+            &rules,
+            None,
+            |_| {},
+        ))
+    }
+
+    /// Analyze `source` as the file at `path`, as part of the Rust files
+    /// under `root`, reading the files in `overlay` from memory instead of
+    /// disk.
+    ///
+    /// This is how an editor checks an unsaved buffer: names resolve across
+    /// the whole file set as with [`Engine::analyze`], with every other open
+    /// buffer passed in `overlay`, but only `path` is checked. `path` and the
+    /// `overlay` keys must be in the form discovery under `root` yields them.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `source` does not parse or is larger than the file size limit.
+    pub fn analyze_in_workspace(
+        &self,
+        root: &Path,
+        path: &Path,
+        source: &str,
+        mut overlay: HashMap<PathBuf, String>,
+    ) -> Result<Vec<Diagnostic>> {
+        // Errors in the buffer itself are the caller's to handle; the rest of
+        // the workspace only provides context
+        file_analyzer::check_file_size(path, source.len() as u64)?;
+        parse_source(path, source)?;
+
+        let mut plan = match &self.packages {
+            Some(packages) => PackagePlan::new(root, packages),
+            None => PackagePlan {
+                files: self.collect_files(root),
+                ..PackagePlan::default()
+            },
+        };
+        overlay.insert(path.to_path_buf(), source.to_string());
+        // Buffers not saved to disk yet are part of the file set too
+        let discovered: HashSet<PathBuf> = plan.files.iter().cloned().collect();
+        let mut unsaved: Vec<PathBuf> = overlay
+            .keys()
+            .filter(|file| !discovered.contains(*file))
+            .cloned()
             .collect();
+        unsaved.sort();
+        plan.files.extend(unsaved);
+
+        let rules = self
+            .rules
+            .select(registry::all_rules().iter().map(|r| r.as_ref()));
+        let mut resident = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let mut cache = self.open_cache(&mut resident, &rules);
+        let checked = HashSet::from([path.to_path_buf()]);
+        let options = FileOptions {
+            targets: self.packages.as_ref().map(|_| &plan.targets),
+            checked: Some(&checked),
+            overlay: Some(&overlay),
+            cancel: self.cancel.as_deref(),
+        };
+        let diagnostics = analyze_files(
+            &plan.files,
+            options,
+            self.config,
+            &rules,
+            cache.as_mut(),
+            |_| {},
+        );
+        self.close_cache(&mut resident, cache);
+        if self
+            .cancel
+            .as_ref()
+            .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        {
+            return Err(Error::Cancelled);
+        }
+        Ok(diagnostics)
+    }
+
+    /// Files under `path` that [`Engine::analyze`] reports diagnostics for,
+    /// before any changed-lines narrowing.
+    pub fn files(&self, path: &Path) -> Vec<PathBuf> {
+        match &self.packages {
+            Some(packages) => {
+                let plan = PackagePlan::new(path, packages);
+                plan.files
+                    .into_iter()
+                    .filter(|file| !plan.context_only.contains(file))
+                    .collect()
+            }
+            None => self.collect_files(path),
+        }
+    }
 
-        // Report errors at the end
-        if let Ok(errs) = errors.lock() {
-            for (path, error) in errs.iter() {
-                eprintln!("Warning: Failed to analyze {}: {}", path.display(), error);
+    /// The cache for a run: the resident one, keyed to this engine's settings,
+    /// or one loaded or created for the run.
+    fn open_cache(
+        &self,
+        resident: &mut Option<AnalysisCache>,
+        rules: &[&dyn Rule],
+    ) -> Option<AnalysisCache> {
+        match resident.take() {
+            Some(mut cache) => {
+                cache.set_settings(self.config, rules);
+                Some(cache)
             }
+            None => match &self.cache_dir {
+                Some(dir) => Some(AnalysisCache::load(dir, self.config, rules)),
+                None => self
+                    .resident
+                    .then(|| AnalysisCache::in_memory(self.config, rules)),
+            },
         }
+    }
 
-        Ok(all_diagnostics)
+    /// Save the cache of a run, and keep it if it is resident.
+    fn close_cache(&self, resident: &mut Option<AnalysisCache>, mut cache: Option<AnalysisCache>) {
+        if let Some(cache) = &mut cache {
+            if let Err(e) = cache.save() {
+                eprintln!("Warning: Failed to write analysis cache: {}", e);
+            }
+        }
+        if self.resident {
+            *resident = cache;
+        }
     }
 
     /// Collect all Rust files to analyze (sequential, fast).
     fn collect_files(&self, path: &Path) -> Vec<PathBuf> {
         discover_rust_files(path, &DiscoveryOptions::secure())
     }
+}
+
+/// Files and crate roots of the workspace packages being analyzed.
+#[derive(Default)]
+struct PackagePlan {
+    files: Vec<PathBuf>,
+    /// Target root file -> the target it is the root of.
+    targets: HashMap<PathBuf, TargetInfo>,
+    /// Files read only for cross-file resolution: they lie outside the
+    /// analyzed path, inside a package that contains it.
+    context_only: HashSet<PathBuf>,
+}
+
+impl PackagePlan {
+    fn new(path: &Path, packages: &[Package]) -> Self {
+        let base = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+        // Keep paths under the analyzed path in the form the user gave it,
+        // so reports and baselines look the same as with plain discovery.
+        let display = |file: &Path| match file.strip_prefix(&base) {
+            Ok(rel) if rel.as_os_str().is_empty() => path.to_path_buf(),
+            Ok(rel) => path.join(rel),
+            Err(_) => file.to_path_buf(),
+        };
+
+        let mut plan = Self::default();
+        let mut seen = HashSet::new();
+        for package in packages {
+            let root = package.root();
+            let scoped = base != root && base.starts_with(root);
+            let sources = discover_rust_files(root, &DiscoveryOptions::secure());
+            let target_roots = package.targets.iter().map(|t| t.src_path.clone());
+            for file in sources.into_iter().chain(target_roots) {
+                let file = file.canonicalize().unwrap_or(file);
+                if !seen.insert(file.clone()) {
+                    continue;
+                }
+                let shown = display(&file);
+                if scoped && !file.starts_with(&base) {
+                    plan.context_only.insert(shown.clone());
+                }
+                plan.files.push(shown);
+            }
+            for target in &package.targets {
+                plan.targets
+                    .insert(display(&target.src_path), package.target_info(target));
+            }
+        }
+        plan
+    }
+}
+
+/// Per-run settings of [`analyze_files`] beyond the files themselves.
+#[derive(Clone, Copy, Default)]
+pub(crate) struct FileOptions<'a> {
+    /// Crate roots and their targets. Files not reachable from any of them
+    /// are read but not checked, and every diagnostic is tagged with the
+    /// target of its file's crate.
+    pub targets: Option<&'a HashMap<PathBuf, TargetInfo>>,
+    /// Files to check; the others are only read for the crate graph.
+    pub checked: Option<&'a HashSet<PathBuf>>,
+    /// Contents to use instead of reading the file from disk.
+    pub overlay: Option<&'a HashMap<PathBuf, String>>,
+    /// Once set, workers stop taking new files.
+    pub cancel: Option<&'a AtomicBool>,
+}
+
+/// A file read by an analysis worker.
+struct LoadedFile<'f> {
+    idx: usize,
+    path: &'f PathBuf,
+    source: String,
+    /// `None` when the summary came from the cache and the file has not been
+    /// parsed (yet).
+    ast: Option<syn::File>,
+    /// Cache keys; only computed when a cache is in use.
+    content_hash: u64,
+    summary: Option<(FileSummary, u64)>,
+}
+
+/// The crate graph plus the cache digest of each file's crate.
+struct SharedGraph {
+    graph: CrateGraph,
+    crate_digests: HashMap<PathBuf, u64>,
+}
+
+/// Analyze a set of files as a single run.
+///
+/// Every file is read and parsed at most once, a [`CrateGraph`] is built over
+/// all of them, and then `rules` are run on each file with the graph attached
+/// so they can resolve names defined in other modules. Files that fail to read
+/// or parse are reported as warnings and skipped.
+///
+/// `syn` ASTs are not `Send`, so each worker thread keeps the ASTs it parsed
+/// and only hands a [`FileSummary`] to the graph builder. Workers meet at a
+/// barrier while the graph is built, then run rules on their own files. A
+/// panic while handling a file is reported and skips only that file, so every
+/// worker still reaches the barrier.
+///
+/// With a `cache`, unchanged files contribute their cached summary instead of
+/// being parsed, and their cached diagnostics are replayed when still valid
+/// (see [`cache`]). The cache is updated in memory; saving is up to the caller.
+///
+/// `options` narrow which files are crate roots and checked, and where their
+/// contents come from (see [`FileOptions`]).
+pub(crate) fn analyze_files<F>(
+    files: &[PathBuf],
+    options: FileOptions<'_>,
+    config: &Config,
+    rules: &[&dyn Rule],
+    mut cache: Option<&mut AnalysisCache>,
+    progress_callback: F,
+) -> Vec<Diagnostic>
+where
+    F: Fn(AnalysisProgress) + Send + Sync,
+{
+    let FileOptions {
+        targets,
+        checked,
+        overlay,
+        cancel,
+    } = options;
+    let cancelled = || cancel.is_some_and(|flag| flag.load(Ordering::Relaxed));
+    let total_files = files.len();
+    if total_files == 0 {
+        return Vec::new();
+    }
+
+    let workers = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1)
+        .min(total_files);
+
+    let cache_ref: Option<&AnalysisCache> = cache.as_deref();
+    let errors: Mutex<Vec<(PathBuf, Error)>> = Mutex::new(Vec::new());
+    let summaries: Mutex<Vec<(FileSummary, u64)>> = Mutex::new(Vec::with_capacity(total_files));
+    let shared: OnceLock<SharedGraph> = OnceLock::new();
+    let barrier = Barrier::new(workers);
+
+    // Shared counters for progress tracking
+    let files_analyzed = AtomicUsize::new(0);
+    let files_cached = AtomicUsize::new(0);
+    let diagnostics_found = AtomicUsize::new(0);
+    let report_progress = |found: usize| {
+        progress_callback(AnalysisProgress {
+            files_analyzed: files_analyzed.fetch_add(1, Ordering::Relaxed) + 1,
+            total_files,
+            diagnostics_found: found,
+            files_cached: files_cached.load(Ordering::Relaxed),
+        });
+    };
+    let record_error = |path: &PathBuf, e: Error| {
+        // Collect errors but continue analyzing other files
+        if let Ok(mut errs) = errors.lock() {
+            errs.push((path.clone(), e));
+        }
+        report_progress(diagnostics_found.load(Ordering::Relaxed));
+    };
+    let report_panic = |path: &PathBuf, payload: &Box<dyn Any + Send>| {
+        eprintln!(
+            "Warning: Analysis panicked on {}: {}",
+            path.display(),
+            extract_panic_message(payload)
+        );
+    };
+
+    type FileOutput = (usize, Vec<Diagnostic>, Option<(PathBuf, CachedFile)>);
+    let mut per_file: Vec<FileOutput> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|worker| {
+                let (summaries, shared, barrier) = (&summaries, &shared, &barrier);
+                let (record_error, report_progress) = (&record_error, &report_progress);
+                let (files_cached, diagnostics_found) = (&files_cached, &diagnostics_found);
+                scope.spawn(move || -> Vec<FileOutput> {
+                    // Every phase catches panics per file, so that each worker
+                    // reaches both barrier waits and the others don't hang
+
+                    // Phase 1: read this worker's share of the files and
+                    // summarize them, parsing only what the cache can't cover
+                    let mut loaded = Vec::new();
+                    for (idx, path) in files.iter().enumerate().skip(worker).step_by(workers) {
+                        if cancelled() {
+                            break;
+                        }
+                        let load = catch_unwind(AssertUnwindSafe(|| {
+                            match load_file(idx, path, overlay, cache_ref) {
+                                Ok((file, summary, summary_hash)) => {
+                                    if let Ok(mut all) = summaries.lock() {
+                                        all.push((summary, summary_hash));
+                                    }
+                                    Some(file)
+                                }
+                                Err(e) => {
+                                    record_error(path, e);
+                                    None
+                                }
+                            }
+                        }));
+                        match load {
+                            Ok(file) => loaded.extend(file),
+                            Err(payload) => report_panic(path, &payload),
+                        }
+                    }
+
+                    // Phase 2: one worker builds the crate graph while the rest wait
+                    if barrier.wait().is_leader() {
+                        let all = summaries
+                            .lock()
+                            .map(|mut s| std::mem::take(&mut *s))
+                            .unwrap_or_default();
+                        let built = catch_unwind(AssertUnwindSafe(|| {
+                            build_shared_graph(all, targets, cache_ref.is_some())
+                        }));
+                        // Without a graph, rules still run but resolve names
+                        // within each file only
+                        let graph = built.unwrap_or_else(|payload| {
+                            eprintln!(
+                                "Warning: Building the crate graph panicked: {}",
+                                extract_panic_message(&payload)
+                            );
+                            SharedGraph {
+                                graph: CrateGraph::default(),
+                                crate_digests: HashMap::new(),
+                            }
+                        });
+                        let _ = shared.set(graph);
+                    }
+                    barrier.wait();
+                    let Some(shared) = shared.get() else {
+                        return Vec::new();
+                    };
+
+                    // Phase 3: run rules on this worker's files with the graph attached
+                    let mut output = Vec::with_capacity(loaded.len());
+                    for mut file in loaded {
+                        if cancelled() {
+                            break;
+                        }
+                        let path = file.path;
+                        let check = catch_unwind(AssertUnwindSafe(|| -> Option<FileOutput> {
+                            if checked.is_some_and(|checked| !checked.contains(file.path)) {
+                                report_progress(diagnostics_found.load(Ordering::Relaxed));
+                                // Keep the summary of a file parsed for the
+                                // graph, so the next run need not parse it
+                                let parsed = file.ast.is_some();
+                                let entry = cache_ref.zip(file.summary.take()).filter(|_| parsed);
+                                return entry.map(|(cache, (summary, summary_hash))| {
+                                    let entry = CachedFile {
+                                        content_hash: file.content_hash,
+                                        summary,
+                                        summary_hash,
+                                        settings: cache.settings(),
+                                        crate_digest: 0,
+                                        diagnostics: None,
+                                    };
+                                    (file.idx, Vec::new(), Some((file.path.clone(), entry)))
+                                });
+                            }
+                            let target = match targets {
+                                Some(targets) => match shared.graph.crate_root(file.path) {
+                                    Some(root) => targets.get(root),
+                                    // Not part of any target: not compiled, not checked
+                                    None => {
+                                        report_progress(diagnostics_found.load(Ordering::Relaxed));
+                                        return None;
+                                    }
+                                },
+                                None => None,
+                            };
+                            let crate_digest =
+                                shared.crate_digests.get(file.path).copied().unwrap_or(0);
+                            let replayed = cache_ref.and_then(|cache| {
+                                cache.diagnostics(file.path, file.content_hash, crate_digest, rules)
+                            });
+                            let (mut result, entry) = match replayed {
+                                Some(result) => {
+                                    files_cached.fetch_add(1, Ordering::Relaxed);
+                                    (result, None)
+                                }
+                                None => {
+                                    let ast = match file.ast.take() {
+                                        Some(ast) => ast,
+                                        None => match parse_source(file.path, &file.source) {
+                                            Ok(ast) => ast,
+                                            Err(e) => {
+                                                record_error(file.path, e);
+                                                return None;
+                                            }
+                                        },
+                                    };
+                                    let file_config =
+                                        config.for_file(file.path, target.map(|t| t.kind));
+                                    let ctx = AnalysisContext::new(
+                                        file.path,
+                                        &file.source,
+                                        &ast,
+                                        &file_config,
+                                    )
+                                    .with_crate_graph(&shared.graph);
+                                    let result = check_with_rules(&ctx, rules.iter().copied());
+                                    let entry = cache_ref.zip(file.summary.take()).map(
+                                        |(cache, (summary, summary_hash))| {
+                                            let entry = CachedFile {
+                                                content_hash: file.content_hash,
+                                                summary,
+                                                summary_hash,
+                                                settings: cache.settings(),
+                                                crate_digest,
+                                                diagnostics: Some(
+                                                    result.iter().map(Into::into).collect(),
+                                                ),
+                                            };
+                                            (file.path.clone(), entry)
+                                        },
+                                    );
+                                    (result, entry)
+                                }
+                            };
+
+                            if let Some(target) = target {
+                                for diagnostic in &mut result {
+                                    diagnostic.target = Some(target.clone());
+                                }
+                            }
+
+                            // Update progress
+                            let found = diagnostics_found
+                                .fetch_add(result.len(), Ordering::Relaxed)
+                                + result.len();
+                            report_progress(found);
+
+                            Some((file.idx, result, entry))
+                        }));
+                        match check {
+                            Ok(checked) => output.extend(checked),
+                            Err(payload) => report_panic(path, &payload),
+                        }
+                    }
+                    output
+                })
+            })
+            .collect();
+
+        let mut output = Vec::new();
+        for handle in handles {
+            match handle.join() {
+                Ok(files) => output.extend(files),
+                Err(payload) => eprintln!(
+                    "Warning: An analysis worker panicked, some files were not analyzed: {}",
+                    extract_panic_message(&payload)
+                ),
+            }
+        }
+        output
+    });
+
+    // Report errors at the end
+    if let Ok(errs) = errors.lock() {
+        for (path, error) in errs.iter() {
+            eprintln!("Warning: Failed to analyze {}: {}", path.display(), error);
+        }
+    }
+
+    // Keep output in discovery order regardless of how work was split
+    per_file.sort_by_key(|(idx, _, _)| *idx);
+    let mut all_diagnostics = Vec::new();
+    for (_, diagnostics, entry) in per_file {
+        if let (Some(cache), Some((path, entry))) = (cache.as_deref_mut(), entry) {
+            cache.insert(path, entry);
+        }
+        all_diagnostics.extend(diagnostics);
+    }
+    all_diagnostics
+}
+
+/// Read a file and produce its summary, parsing it unless the cache already
+/// holds a summary for this exact content.
+fn load_file<'f>(
+    idx: usize,
+    path: &'f PathBuf,
+    overlay: Option<&HashMap<PathBuf, String>>,
+    cache: Option<&AnalysisCache>,
+) -> Result<(LoadedFile<'f>, FileSummary, u64)> {
+    let source = match overlay.and_then(|overlay| overlay.get(path)) {
+        Some(source) => {
+            file_analyzer::check_file_size(path, source.len() as u64)?;
+            source.clone()
+        }
+        None => read_file_secure(path)?,
+    };
+    let content_hash = cache.map(|_| cache::content_hash(&source)).unwrap_or(0);
+
+    let cached = cache.and_then(|cache| cache.file(path, content_hash));
+    let (ast, summary, summary_hash) = match cached {
+        Some(cached) => (None, cached.summary.clone(), cached.summary_hash),
+        None => {
+            let ast = parse_source(path, &source)?;
+            let summary = FileSummary::from_ast(path, &ast);
+            let summary_hash = cache.map(|_| cache::summary_hash(&summary)).unwrap_or(0);
+            (Some(ast), summary, summary_hash)
+        }
+    };
+
+    let file = LoadedFile {
+        idx,
+        path,
+        source,
+        ast,
+        content_hash,
+        summary: cache.map(|_| (summary.clone(), summary_hash)),
+    };
+    Ok((file, summary, summary_hash))
+}
+
+/// Build the crate graph and, when caching, each file's crate digest.
+fn build_shared_graph(
+    summaries: Vec<(FileSummary, u64)>,
+    targets: Option<&HashMap<PathBuf, TargetInfo>>,
+    with_digests: bool,
+) -> SharedGraph {
+    let (summaries, hashes): (Vec<FileSummary>, Vec<u64>) = summaries.into_iter().unzip();
+    let graph = match targets {
+        Some(targets) => {
+            let roots: Vec<PathBuf> = targets.keys().cloned().collect();
+            CrateGraph::build_with_roots(&summaries, &roots)
+        }
+        None => CrateGraph::build(&summaries),
+    };
+
+    let mut crate_digests = HashMap::new();
+    if with_digests {
+        let mut crates: HashMap<&Path, Vec<(&Path, u64)>> = HashMap::new();
+        for (summary, hash) in summaries.iter().zip(&hashes) {
+            if let Some(root) = graph.crate_root(&summary.path) {
+                crates
+                    .entry(root)
+                    .or_default()
+                    .push((summary.path.as_path(), *hash));
+            }
+        }
+        let digests: HashMap<&Path, u64> = crates
+            .into_iter()
+            .map(|(root, files)| (root, cache::crate_digest(files)))
+            .collect();
+        for summary in &summaries {
+            if let Some(digest) = graph.crate_root(&summary.path).and_then(|r| digests.get(r)) {
+                crate_digests.insert(summary.path.clone(), *digest);
+            }
+        }
+    }
 
-    fn analyze_file(&self, file_path: &Path) -> Result<Vec<Diagnostic>> {
-        // Use shared file analysis logic with static registry rules
-        let rules = registry::all_rules().iter().map(|r| r.as_ref());
-        analyze_file_with_rules(file_path, self.config, rules)
+    SharedGraph {
+        graph,
+        crate_digests,
     }
 }
 
@@ -197,4 +951,208 @@ mod tests {
         // Should succeed without trying to parse /etc/passwd
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_resident_cache_rechecks_only_changed_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let blocking = "async fn f() { std::thread::sleep(std::time::Duration::from_secs(1)); }\n";
+        std::fs::write(temp_dir.path().join("a.rs"), blocking).unwrap();
+        std::fs::write(temp_dir.path().join("b.rs"), "fn main() {}\n").unwrap();
+
+        let config = Config::default();
+        let engine = Engine::new(&config).with_resident_cache();
+        let run = || {
+            let cached = AtomicUsize::new(0);
+            let diagnostics = engine
+                .analyze_with_progress(temp_dir.path(), |p| {
+                    cached.fetch_max(p.files_cached, Ordering::Relaxed);
+                })
+                .unwrap();
+            (diagnostics.len(), cached.into_inner())
+        };
+
+        assert_eq!(run(), (1, 0));
+        assert_eq!(run(), (1, 2));
+        std::fs::write(temp_dir.path().join("b.rs"), blocking).unwrap();
+        assert_eq!(run(), (2, 1));
+    }
+
+    #[test]
+    fn test_cancellation_stops_the_run() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["a.rs", "b.rs", "c.rs"] {
+            std::fs::write(temp_dir.path().join(name), "fn main() {}\n").unwrap();
+        }
+
+        let config = Config::default();
+        let flag = Arc::new(AtomicBool::new(false));
+        let engine = Engine::new(&config).with_cancellation(Arc::clone(&flag));
+        assert!(engine.analyze(temp_dir.path()).is_ok());
+
+        // Cancelled from the first progress report on
+        let result = engine.analyze_with_progress(temp_dir.path(), |_| {
+            flag.store(true, Ordering::Relaxed);
+        });
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
+
+    #[test]
+    fn test_panic_in_a_worker_skips_only_its_file() {
+        let temp_dir = TempDir::new().unwrap();
+        std::fs::write(temp_dir.path().join("a.rs"), "fn (\n").unwrap();
+        for name in ["b.rs", "c.rs", "d.rs"] {
+            std::fs::write(temp_dir.path().join(name), BLOCKING).unwrap();
+        }
+
+        // The first report, for the file that fails to parse, panics while
+        // reading files, before any worker reaches the barrier
+        let config = Config::default();
+        let panicked = AtomicBool::new(false);
+        let diagnostics = Engine::new(&config)
+            .analyze_with_progress(temp_dir.path(), |_| {
+                if !panicked.swap(true, Ordering::Relaxed) {
+                    panic!("progress callback failed");
+                }
+            })
+            .unwrap();
+        assert_eq!(diagnostics.len(), 3, "{:?}", diagnostics);
+    }
+
+    const BLOCKING: &str =
+        "async fn f() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n";
+
+    #[test]
+    fn test_analyze_source_applies_config_and_suppressions() {
+        let config: Config = toml::from_str(
+            "[[overrides]]\npaths = [\"legacy/**\"]\nrules = { async-block-in-async = \"allow\" }\n",
+        )
+        .unwrap();
+        let engine = Engine::new(&config);
+
+        let diagnostics = engine.analyze_source("src/lib.rs", BLOCKING).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file_path, Path::new("src/lib.rs"));
+        assert_eq!(diagnostics[0].line, 2);
+
+        assert!(engine
+            .analyze_source("legacy/old.rs", BLOCKING)
+            .unwrap()
+            .is_empty());
+        let suppressed = BLOCKING.replace(
+            "    std::thread",
+            "    // cargo-perf-ignore: async-block-in-async\n    std::thread",
+        );
+        assert!(engine
+            .analyze_source("src/lib.rs", &suppressed)
+            .unwrap()
+            .is_empty());
+        assert!(engine.analyze_source("src/lib.rs", "fn (").is_err());
+    }
+
+    #[test]
+    fn test_analyze_sources_resolves_across_virtual_files() {
+        let config = Config::default();
+        let engine = Engine::new(&config);
+        let diagnostics = engine
+            .analyze_sources([
+                (
+                    "virtual/src/lib.rs",
+                    "mod util;\nasync fn handler() {\n    util::wait();\n}\n",
+                ),
+                (
+                    "virtual/src/util.rs",
+                    "pub fn wait() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n",
+                ),
+            ])
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].file_path, Path::new("virtual/src/lib.rs"));
+        assert_eq!(diagnostics[0].line, 3);
+    }
+
+    #[test]
+    fn test_analyze_in_workspace_resolves_against_files_on_disk() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        std::fs::create_dir(&src).unwrap();
+        std::fs::write(src.join("lib.rs"), "mod util;\nmod handler;\n").unwrap();
+        std::fs::write(
+            src.join("util.rs"),
+            "pub fn wait() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n",
+        )
+        .unwrap();
+        let lib = src.join("lib.rs");
+        let handler = src.join("handler.rs");
+        // The file on disk is stale; only the buffer calls the helper
+        std::fs::write(&handler, "async fn handler() {}\n").unwrap();
+        let config = Config::default();
+        let engine = Engine::new(&config);
+
+        let buffer = "async fn handler() {\n    crate::util::wait();\n}\n";
+        let diagnostics = engine
+            .analyze_in_workspace(temp_dir.path(), &handler, buffer, HashMap::new())
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].file_path, handler);
+        assert_eq!(diagnostics[0].line, 2);
+
+        // A suppression of the cross-file finding is used, not reported
+        let suppressed = buffer.replace(
+            "    crate::util",
+            "    // cargo-perf-ignore: async-block-in-async\n    crate::util",
+        );
+        assert!(engine
+            .analyze_in_workspace(temp_dir.path(), &handler, &suppressed, HashMap::new())
+            .unwrap()
+            .is_empty());
+
+        // Other open buffers replace their files on disk
+        let overlay = HashMap::from([(lib, "mod handler;\n".to_string())]);
+        assert!(engine
+            .analyze_in_workspace(temp_dir.path(), &handler, buffer, overlay)
+            .unwrap()
+            .is_empty());
+        assert!(engine
+            .analyze_in_workspace(temp_dir.path(), &handler, "fn (", HashMap::new())
+            .is_err());
+    }
+
+    #[test]
+    fn test_shared_cache_keeps_workspace_summaries() {
+        let temp_dir = TempDir::new().unwrap();
+        let util =
+            "pub fn wait() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n";
+        std::fs::write(temp_dir.path().join("lib.rs"), "mod util;\nmod handler;\n").unwrap();
+        std::fs::write(temp_dir.path().join("util.rs"), util).unwrap();
+        let handler = temp_dir.path().join("handler.rs");
+        let buffer = "async fn handler() {\n    crate::util::wait();\n}\n";
+
+        let shared = Arc::new(Mutex::new(None));
+        let config = Config::default();
+        let engine = Engine::new(&config).with_shared_cache(Arc::clone(&shared));
+        let diagnostics = engine
+            .analyze_in_workspace(temp_dir.path(), &handler, buffer, HashMap::new())
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1);
+
+        // Files read only for context are kept for the next engine
+        {
+            let cache = shared.lock().unwrap();
+            let cached = cache.as_ref().unwrap();
+            let util = cached.file(&temp_dir.path().join("util.rs"), cache::content_hash(util));
+            assert!(util.is_some_and(|file| file.diagnostics.is_none()));
+        }
+        let engine = Engine::new(&config).with_shared_cache(Arc::clone(&shared));
+        let diagnostics = engine
+            .analyze_in_workspace(temp_dir.path(), &handler, buffer, HashMap::new())
+            .unwrap();
+        assert_eq!(diagnostics.len(), 1);
+
+        let flag = Arc::new(AtomicBool::new(true));
+        let engine = Engine::new(&config)
+            .with_shared_cache(shared)
+            .with_cancellation(flag);
+        let result = engine.analyze_in_workspace(temp_dir.path(), &handler, buffer, HashMap::new());
+        assert!(matches!(result, Err(Error::Cancelled)));
+    }
 }
@@ -37,6 +37,17 @@ pub enum Error {
         message: String,
     },
 
+    /// Running git failed or it rejected the request.
+    #[error("git: {message}")]
+    Git {
+        /// git's error output, or why it could not be run.
+        message: String,
+    },
+
+    /// The analysis was cancelled before it finished.
+    #[error("Analysis cancelled")]
+    Cancelled,
+
     /// Generic IO error without path context.
     #[error("IO error: {0}")]
     IoGeneric(#[from] std::io::Error),
@@ -65,6 +76,13 @@ impl Error {
             message: message.into(),
         }
     }
+
+    /// Create a git error.
+    pub fn git(message: impl Into<String>) -> Self {
+        Self::Git {
+            message: message.into(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,288 @@
+//! Rule documentation rendered from [`Rule`] metadata.
+//!
+//! `cargo perf explain` and the generated `docs/rules.md` both come from
+//! here, so built-in and plugin rules are documented the same way and the
+//! reference cannot drift from the rules themselves.
+
+use crate::rules::{Category, Explanation, Rule, Severity};
+use colored::Colorize;
+use serde::Serialize;
+use std::fmt::Write;
+
+/// Everything known about a rule, in the shape `explain --format json` emits.
+#[derive(Debug, Serialize)]
+pub struct RuleDoc {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub severity: Severity,
+    pub category: &'static str,
+    pub confidence: &'static str,
+    pub tags: &'static [&'static str],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_url: Option<String>,
+    #[serde(flatten)]
+    pub explanation: Explanation,
+    /// Comment and attribute that silence this rule.
+    pub suppress_with: [String; 2],
+}
+
+impl RuleDoc {
+    pub fn new(rule: &dyn Rule) -> Self {
+        Self {
+            id: rule.id(),
+            name: rule.name(),
+            description: rule.description(),
+            severity: rule.default_severity(),
+            category: rule.category().as_str(),
+            confidence: rule.confidence().as_str(),
+            tags: rule.tags(),
+            doc_url: rule.doc_url(),
+            explanation: rule.explanation(),
+            suppress_with: suppress_with(rule.id()),
+        }
+    }
+}
+
+/// The line comment and attribute that suppress `rule_id`.
+pub fn suppress_with(rule_id: &str) -> [String; 2] {
+    [
+        format!("// cargo-perf-ignore: {}", rule_id),
+        format!("#[allow(cargo_perf::{})]", rule_id.replace('-', "_")),
+    ]
+}
+
+/// Terminal output for one rule, colored when color is enabled.
+pub fn console(rule: &dyn Rule) -> String {
+    let doc = RuleDoc::new(rule);
+    let explanation = &doc.explanation;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "{}", doc.name.bold().underline());
+    let _ = writeln!(out, "Rule ID: {}", doc.id.cyan());
+    let _ = writeln!(out, "Severity: {:?}", doc.severity);
+    let _ = writeln!(out, "Category: {}", doc.category);
+    let _ = writeln!(out, "Confidence: {}", doc.confidence);
+    if !doc.tags.is_empty() {
+        let _ = writeln!(out, "Tags: {}", doc.tags.join(", "));
+    }
+    if let Some(url) = &doc.doc_url {
+        let _ = writeln!(out, "Docs: {}", url);
+    }
+    let _ = writeln!(out, "\n{}\n", doc.description);
+
+    let _ = writeln!(out, "{}", "Why it matters:".yellow().bold());
+    let _ = writeln!(out, "  {}", explanation.rationale);
+    if let Some(bad) = explanation.bad_example {
+        let _ = writeln!(out, "\n{}", "Bad:".red().bold());
+        write_indented(&mut out, bad);
+    }
+    if let Some(good) = explanation.good_example {
+        let _ = writeln!(out, "\n{}", "Good:".green().bold());
+        write_indented(&mut out, good);
+    }
+    if let Some(impact) = explanation.impact {
+        let _ = writeln!(out, "\n{}", "Performance impact:".yellow().bold());
+        let _ = writeln!(out, "  {}", impact);
+    }
+    if explanation.autofix {
+        let _ = writeln!(out, "\n{}", "Auto-fix:".green().bold());
+        let _ = writeln!(out, "  Available with `cargo perf fix`.");
+    }
+    if !explanation.related.is_empty() {
+        let _ = writeln!(out, "\n{}", "Related rules:".yellow().bold());
+        let _ = writeln!(out, "  {}", explanation.related.join(", "));
+    }
+
+    let _ = writeln!(out, "\n{}", "Suppression:".yellow().bold());
+    if let Some(guidance) = explanation.suppression {
+        let _ = writeln!(out, "  {}", guidance);
+    }
+    for line in &doc.suppress_with {
+        let _ = writeln!(out, "  {}", line);
+    }
+    out
+}
+
+/// Markdown section for one rule, headed `### <rule-id>` so it can be
+/// linked as `#<rule-id>`.
+pub fn markdown(rule: &dyn Rule) -> String {
+    let doc = RuleDoc::new(rule);
+    let explanation = &doc.explanation;
+    let mut out = String::new();
+
+    let _ = writeln!(out, "### {}\n", doc.id);
+    let _ = writeln!(out, "{}\n", sentence(doc.description));
+    let _ = writeln!(out, "- Severity: {}", severity_name(doc.severity));
+    let _ = writeln!(out, "- Confidence: {}", doc.confidence);
+    if !doc.tags.is_empty() {
+        let _ = writeln!(out, "- Tags: {}", doc.tags.join(", "));
+    }
+    if explanation.autofix {
+        let _ = writeln!(out, "- Auto-fix: yes (`cargo perf fix`)");
+    }
+    let _ = writeln!(out, "\n{}", explanation.rationale);
+    if let Some(bad) = explanation.bad_example {
+        let _ = writeln!(out, "\nBad:\n\n```rust\n{}\n```", bad);
+    }
+    if let Some(good) = explanation.good_example {
+        let _ = writeln!(out, "\nGood:\n\n```rust\n{}\n```", good);
+    }
+    if let Some(impact) = explanation.impact {
+        let _ = writeln!(out, "\nPerformance impact: {}", impact);
+    }
+    if !explanation.related.is_empty() {
+        let links: Vec<String> = explanation
+            .related
+            .iter()
+            .map(|id| format!("[{0}](#{0})", id))
+            .collect();
+        let _ = writeln!(out, "\nRelated: {}", links.join(", "));
+    }
+
+    match explanation.suppression {
+        Some(guidance) => {
+            let _ = writeln!(out, "\nSuppression: {}", guidance);
+        }
+        None => {
+            let _ = writeln!(out, "\nSuppression:");
+        }
+    }
+    let _ = writeln!(out, "\n```rust\n{}\n```", doc.suppress_with.join("\n"));
+    out
+}
+
+/// A full rules reference: every rule grouped under its category.
+pub fn markdown_reference(rules: &[&dyn Rule]) -> String {
+    let mut out = String::from(
+        "# Rules reference\n\n\
+         Every rule cargo-perf ships, grouped by category. `cargo perf explain <rule-id>`\n\
+         shows the same information in the terminal. This file is generated by\n\
+         `cargo perf explain --all --format markdown`.\n",
+    );
+    for &category in Category::ALL {
+        let in_category: Vec<&dyn Rule> = rules
+            .iter()
+            .copied()
+            .filter(|rule| rule.category() == category)
+            .collect();
+        if in_category.is_empty() {
+            continue;
+        }
+        let _ = write!(out, "\n## {}\n", title_case(category.as_str()));
+        for rule in in_category {
+            out.push('\n');
+            out.push_str(&markdown(rule));
+        }
+    }
+    out
+}
+
+fn write_indented(out: &mut String, code: &str) {
+    for line in code.lines() {
+        if line.is_empty() {
+            out.push('\n');
+        } else {
+            let _ = writeln!(out, "  {}", line);
+        }
+    }
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Info => "info",
+    }
+}
+
+fn sentence(text: &str) -> String {
+    if text.ends_with('.') {
+        text.to_string()
+    } else {
+        format!("{}.", text)
+    }
+}
+
+fn title_case(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::registry;
+
+    fn builtin() -> Vec<&'static dyn Rule> {
+        registry::all_rules().iter().map(|r| r.as_ref()).collect()
+    }
+
+    #[test]
+    fn test_builtin_explanations_are_complete() {
+        for rule in builtin() {
+            let explanation = rule.explanation();
+            assert!(explanation.bad_example.is_some(), "{}", rule.id());
+            assert!(explanation.good_example.is_some(), "{}", rule.id());
+            for related in explanation.related {
+                assert!(registry::has_rule(related), "{} -> {}", rule.id(), related);
+            }
+        }
+    }
+
+    #[test]
+    fn test_autofix_matches_rules_with_fixes() {
+        let fixable: Vec<&str> = builtin()
+            .into_iter()
+            .filter(|rule| rule.explanation().autofix)
+            .map(|rule| rule.id())
+            .collect();
+        assert_eq!(
+            fixable,
+            vec![
+                "async-block-in-async",
+                "unbounded-channel",
+                "string-concat-loop",
+                "unused-suppression"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_console_sections() {
+        colored::control::set_override(false);
+        let text = console(registry::get_rule("regex-in-loop").unwrap());
+        assert!(text.contains("Why it matters:"));
+        assert!(text.contains("Bad:\n  for line in lines {"));
+        assert!(text.contains("LazyLock"));
+        assert!(text.contains("  #[allow(cargo_perf::regex_in_loop)]"));
+    }
+
+    #[test]
+    fn test_markdown_reference_groups_by_category() {
+        let reference = markdown_reference(&builtin());
+        let async_at = reference.find("## Async").unwrap();
+        let database_at = reference.find("## Database").unwrap();
+        let n_plus_one_at = reference.find("### n-plus-one-query").unwrap();
+        assert!(async_at < database_at && database_at < n_plus_one_at);
+        assert!(reference.contains("[lock-across-await](#lock-across-await)"));
+        assert!(!reference.contains("## Other"));
+    }
+
+    #[test]
+    fn test_json_flattens_explanation() {
+        let doc = RuleDoc::new(registry::get_rule("string-concat-loop").unwrap());
+        let value = serde_json::to_value(&doc).unwrap();
+        assert_eq!(value["id"], "string-concat-loop");
+        assert_eq!(value["category"], "allocation");
+        assert_eq!(value["autofix"], true);
+        assert!(value["rationale"].is_string());
+        assert_eq!(
+            value["suppress_with"][0],
+            "// cargo-perf-ignore: string-concat-loop"
+        );
+    }
+}
@@ -24,6 +24,20 @@
 //! }
 //! ```
 //!
+//! Sources that only exist in memory (unsaved buffers, diff blobs) are
+//! analyzed with [`Engine::analyze_source`], or [`Engine::analyze_sources`]
+//! for a set of files that refer to each other:
+//!
+//! ```no_run
+//! use cargo_perf::{Config, Engine};
+//!
+//! let config = Config::default();
+//! let engine = Engine::new(&config);
+//! let diagnostics = engine
+//!     .analyze_source("src/handler.rs", "async fn f() { std::thread::sleep(d); }")
+//!     .unwrap();
+//! ```
+//!
 //! ## Rules
 //!
 //! cargo-perf includes 12 rules organized into categories:
@@ -50,6 +64,9 @@
 //! ### Iterator Rules (Warnings)
 //! - `collect-then-iterate`: `.collect().iter()` anti-pattern
 //!
+//! ### Suppression Rules (Warnings)
+//! - `unused-suppression`: suppressions that suppressed nothing or name unknown rules
+//!
 //! ## Extending with Custom Rules
 //!
 //! Use the [`plugin`] module to add custom rules:
@@ -84,16 +101,25 @@
 //! #[allow(cargo_perf::clone_in_hot_loop)]
 //! fn my_function() { /* ... */ }
 //!
-//! // Comment-based (next line only)
+//! // Comment-based (next line, or its own line after code)
 //! // cargo-perf-ignore: clone-in-hot-loop
 //! let x = data.clone();
+//! let y = data.clone(); // cargo-perf-ignore: clone-in-hot-loop
+//!
+//! // In the whole file, before its first item, or between markers
+//! // cargo-perf-ignore-file: vec-no-capacity
+//! // cargo-perf-ignore-start: clone-in-hot-loop
+//! let z = data.clone();
+//! // cargo-perf-ignore-end
 //! ```
 
 pub mod baseline;
 pub mod config;
+pub mod diff;
 pub mod discovery;
 pub mod engine;
 pub mod error;
+pub mod explain;
 pub mod fix;
 #[cfg(feature = "lsp")]
 pub mod lsp;
@@ -101,6 +127,7 @@ pub mod plugin;
 pub mod reporter;
 pub mod rules;
 pub mod suppression;
+pub mod workspace;
 
 pub use baseline::Baseline;
 pub use config::Config;
@@ -0,0 +1,158 @@
+//! Open editor buffers, kept in sync through `didOpen`/`didChange`/`didClose`.
+//!
+//! Both full and incremental text sync are accepted: a change without a range
+//! replaces the whole text, and a ranged change is applied in place. Positions
+//! are in UTF-16 code units, as LSP specifies by default.
+
+use std::collections::HashMap;
+use tower_lsp::lsp_types::{Position, TextDocumentContentChangeEvent, Url};
+
+/// An open document as the editor currently has it.
+#[derive(Debug, Clone)]
+pub(crate) struct Document {
+    pub text: String,
+    pub version: i32,
+}
+
+/// Open documents by URI.
+#[derive(Debug, Default)]
+pub(crate) struct DocumentStore {
+    documents: HashMap<Url, Document>,
+}
+
+impl DocumentStore {
+    pub fn open(&mut self, uri: Url, text: String, version: i32) {
+        self.documents.insert(uri, Document { text, version });
+    }
+
+    /// Apply `changes` in order and record the new `version`. Returns `false`
+    /// if the document is not open.
+    pub fn change(
+        &mut self,
+        uri: &Url,
+        version: i32,
+        changes: Vec<TextDocumentContentChangeEvent>,
+    ) -> bool {
+        let Some(document) = self.documents.get_mut(uri) else {
+            return false;
+        };
+        for change in changes {
+            apply_change(&mut document.text, change);
+        }
+        document.version = version;
+        true
+    }
+
+    pub fn close(&mut self, uri: &Url) -> Option<Document> {
+        self.documents.remove(uri)
+    }
+
+    pub fn get(&self, uri: &Url) -> Option<&Document> {
+        self.documents.get(uri)
+    }
+
+    /// URIs of all open documents.
+    pub fn uris(&self) -> impl Iterator<Item = &Url> {
+        self.documents.keys()
+    }
+
+    /// Current version of an open document.
+    pub fn version(&self, uri: &Url) -> Option<i32> {
+        self.documents.get(uri).map(|document| document.version)
+    }
+}
+
+fn apply_change(text: &mut String, change: TextDocumentContentChangeEvent) {
+    match change.range {
+        None => *text = change.text,
+        Some(range) => {
+            let start = offset(text, range.start);
+            let end = offset(text, range.end).max(start);
+            text.replace_range(start..end, &change.text);
+        }
+    }
+}
+
+/// Byte offset of `position`, clamped to the end of its line and the text.
+fn offset(text: &str, position: Position) -> usize {
+    let mut line_start = 0;
+    for _ in 0..position.line {
+        match text[line_start..].find('\n') {
+            Some(newline) => line_start += newline + 1,
+            None => return text.len(),
+        }
+    }
+    let line = &text[line_start..];
+    let line = &line[..line.find('\n').unwrap_or(line.len())];
+    let mut units = 0;
+    for (idx, ch) in line.char_indices() {
+        if units >= position.character as usize {
+            return line_start + idx;
+        }
+        units += ch.len_utf16();
+    }
+    line_start + line.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tower_lsp::lsp_types::Range;
+
+    fn uri() -> Url {
+        Url::parse("file:///project/src/lib.rs").unwrap()
+    }
+
+    fn edit(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+        TextDocumentContentChangeEvent {
+            range: Some(Range {
+                start: Position::new(start.0, start.1),
+                end: Position::new(end.0, end.1),
+            }),
+            range_length: None,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_full_and_incremental_changes() {
+        let mut store = DocumentStore::default();
+        store.open(uri(), "fn a() {}\nfn b() {}\n".to_string(), 1);
+
+        assert!(store.change(&uri(), 2, vec![edit((1, 3), (1, 4), "renamed")]));
+        assert_eq!(
+            store.get(&uri()).unwrap().text,
+            "fn a() {}\nfn renamed() {}\n"
+        );
+
+        // Several edits apply in order, each against the previous result
+        let edits = vec![edit((0, 0), (0, 0), "// x\n"), edit((1, 3), (1, 4), "c")];
+        assert!(store.change(&uri(), 3, edits));
+        assert_eq!(
+            store.get(&uri()).unwrap().text,
+            "// x\nfn c() {}\nfn renamed() {}\n"
+        );
+
+        let full = TextDocumentContentChangeEvent {
+            range: None,
+            range_length: None,
+            text: "fn main() {}\n".to_string(),
+        };
+        assert!(store.change(&uri(), 4, vec![full]));
+        assert_eq!(store.get(&uri()).unwrap().text, "fn main() {}\n");
+        assert_eq!(store.version(&uri()), Some(4));
+
+        store.close(&uri());
+        assert!(!store.change(&uri(), 5, vec![]));
+    }
+
+    #[test]
+    fn test_positions_are_utf16() {
+        let text = "let s = \"😀é\"; x\n";
+        // 😀 is two UTF-16 units, é one: `x` is at unit 15
+        assert_eq!(&text[offset(text, Position::new(0, 15))..], "x\n");
+        // Past the end of a line clamps to the line end
+        assert_eq!(offset(text, Position::new(0, 99)), text.len() - 1);
+        assert_eq!(offset(text, Position::new(5, 0)), text.len());
+    }
+}
@@ -0,0 +1,87 @@
+//! Function spans, for the per-function findings code lens and for
+//! suppressing a finding in its function.
+
+use syn::visit::Visit;
+use syn::{ImplItemFn, ItemFn, TraitItemFn};
+
+/// Lines of a function with a body, 1-based and inclusive.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FunctionSpan {
+    /// Line of the `fn` keyword, where the lens is shown
+    pub line: usize,
+    pub end_line: usize,
+}
+
+/// Every function, method and default trait method in `source`, including
+/// nested ones, in source order. `None` if the source does not parse.
+pub(crate) fn functions(source: &str) -> Option<Vec<FunctionSpan>> {
+    let file = syn::parse_file(source).ok()?;
+    let mut collector = FunctionCollector::default();
+    collector.visit_file(&file);
+    Some(collector.functions)
+}
+
+#[derive(Default)]
+struct FunctionCollector {
+    functions: Vec<FunctionSpan>,
+}
+
+impl FunctionCollector {
+    fn push(&mut self, sig: &syn::Signature, block: &syn::Block) {
+        self.functions.push(FunctionSpan {
+            line: sig.fn_token.span.start().line,
+            end_line: block.brace_token.span.close().start().line,
+        });
+    }
+}
+
+impl<'ast> Visit<'ast> for FunctionCollector {
+    fn visit_item_fn(&mut self, node: &'ast ItemFn) {
+        self.push(&node.sig, &node.block);
+        syn::visit::visit_item_fn(self, node);
+    }
+
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        self.push(&node.sig, &node.block);
+        syn::visit::visit_impl_item_fn(self, node);
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
+        if let Some(block) = &node.default {
+            self.push(&node.sig, block);
+        }
+        syn::visit::visit_trait_item_fn(self, node);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_functions() {
+        let source = r#"
+fn free() {
+    fn nested() {}
+}
+
+impl Foo {
+    #[inline]
+    pub fn method(&self) {
+    }
+}
+
+trait Bar {
+    fn required(&self);
+    fn provided(&self) {}
+}
+"#;
+        let lines: Vec<(usize, usize)> = functions(source)
+            .unwrap()
+            .iter()
+            .map(|f| (f.line, f.end_line))
+            .collect();
+        assert_eq!(lines, vec![(2, 4), (3, 3), (8, 9), (14, 14)]);
+        assert_eq!(functions("fn broken( {"), None);
+    }
+}
@@ -10,9 +10,18 @@
 //!
 //! ## Supported Capabilities
 //!
-//! - Real-time diagnostics on file save
+//! - Real-time diagnostics as you type, on unsaved buffers
 //! - Diagnostic severity mapping (errors, warnings)
-//! - Code actions with auto-fix support
+//! - Code actions with auto-fix support, and to suppress a finding on its line
+//!   or in its function
+//! - Hover on a finding shows the rule's explanation, with bad and good examples
+//! - Code lens above each function summarizing its findings
+//! - Findings in the workspace baseline are hidden, or shown faded
+//! - `cargo-perf.toml` and baseline changes, and rule levels from editor
+//!   settings, are picked up without a restart
+//! - Pull diagnostics (`textDocument/diagnostic`, `workspace/diagnostic`) with
+//!   result IDs, for clients that support them; otherwise diagnostics are pushed
+//! - Cancellable `$/progress` reports while the workspace is scanned
 //!
 //! ## Editor Setup
 //!
@@ -39,19 +48,54 @@
 //! }
 //! require('lspconfig').cargo_perf.setup({})
 //! ```
+//!
+//! ## Initialization Options
+//!
+//! - `debounceMs`: delay after the last edit before a buffer is re-analyzed
+//!   (default: 300)
+//! - `codeLens`: show the per-function findings code lens (default: true)
+//! - `baselined`: how findings recorded in `.cargo-perf-baseline` are shown,
+//!   `"hide"` to drop them like `cargo perf check --baseline` does, or
+//!   `"hint"` to show them faded at hint severity (default: `"hide"`)
+//!
+//! ## Editor Settings
+//!
+//! `workspace/didChangeConfiguration` may set rule levels on top of the
+//! `[rules]` table in `cargo-perf.toml`:
+//!
+//! ```json
+//! { "cargo-perf": { "rules": { "clone-in-hot-loop": "allow" } } }
+//! ```
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
-use tokio::sync::RwLock;
-use tower_lsp::jsonrpc::Result;
+use serde::Deserialize;
+use tokio::sync::{watch, Mutex, RwLock};
+use tokio::task::AbortHandle;
+use tower_lsp::jsonrpc::{Error as RpcError, Result};
 use tower_lsp::lsp_types::*;
 use tower_lsp::{Client, LanguageServer, LspService, Server};
 
-use crate::engine::LineIndex;
-use crate::{analyze, Config, Diagnostic as PerfDiagnostic, Fix, Severity as PerfSeverity};
+mod documents;
+mod lens;
+mod progress;
+mod suppress;
+
+use documents::DocumentStore;
+use progress::Progress;
+
+use crate::baseline::{Baseline, BASELINE_FILENAME};
+use crate::config::RuleSeverity;
+use crate::engine::{AnalysisCache, LineIndex};
+use crate::explain;
+use crate::rules::registry;
+use crate::rules::suppression_rules::requires_reason;
+use crate::{Config, Diagnostic as PerfDiagnostic, Engine, Fix, Severity as PerfSeverity};
 
 /// Maximum file size to analyze (10 MB)
 const MAX_FILE_SIZE: u64 = 10 * 1024 * 1024;
@@ -65,23 +109,139 @@ const MAX_RATE_LIMIT_ENTRIES: usize = 1000;
 /// Age after which rate-limit entries are considered stale (5 minutes)
 const RATE_LIMIT_STALE_SECS: u64 = 300;
 
+/// Config file name, watched for changes
+const CONFIG_FILENAME: &str = "cargo-perf.toml";
+
+/// Section of the editor settings holding cargo-perf's settings
+const SETTINGS_SECTION: &str = "cargo-perf";
+
+/// Default delay after the last edit before a buffer is re-analyzed
+const DEFAULT_DEBOUNCE_MS: u64 = 300;
+
+/// Options a client may pass as `initializationOptions`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+struct ServerOptions {
+    /// Delay after the last edit before a buffer is re-analyzed
+    debounce_ms: u64,
+    /// Show the per-function findings code lens
+    code_lens: bool,
+    /// How findings recorded in the baseline are shown
+    baselined: BaselinedFindings,
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            debounce_ms: DEFAULT_DEBOUNCE_MS,
+            code_lens: true,
+            baselined: BaselinedFindings::default(),
+        }
+    }
+}
+
+/// How findings recorded in the baseline are shown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum BaselinedFindings {
+    /// Not shown, as `cargo perf check --baseline` does not report them
+    #[default]
+    Hide,
+    /// Shown at hint severity with the `Unnecessary` tag, which editors fade
+    Hint,
+}
+
+/// Settings from the editor, sent through `workspace/didChangeConfiguration`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+struct EditorSettings {
+    /// Rule levels applied on top of `[rules]` in `cargo-perf.toml`
+    rules: HashMap<String, RuleSeverity>,
+}
+
+impl EditorSettings {
+    /// Parse settings, either under a `cargo-perf` section or bare.
+    fn from_value(mut settings: serde_json::Value) -> serde_json::Result<Self> {
+        if let Some(section) = settings.get_mut(SETTINGS_SECTION) {
+            settings = section.take();
+        }
+        if settings.is_null() {
+            return Ok(Self::default());
+        }
+        serde_json::from_value(settings)
+    }
+
+    fn apply(&self, config: &mut Config) {
+        config
+            .rules
+            .extend(self.rules.iter().map(|(id, level)| (id.clone(), *level)));
+    }
+}
+
+/// Optional client features the server makes use of.
+#[derive(Debug, Clone, Copy, Default)]
+struct ClientSupport {
+    /// `workspace/codeLens/refresh`
+    code_lens_refresh: bool,
+    /// Registering `workspace/didChangeWatchedFiles` at runtime
+    watched_files: bool,
+    /// `textDocument/diagnostic` and `workspace/diagnostic`; diagnostics are
+    /// then pulled, not pushed
+    pull_diagnostics: bool,
+    /// `workspace/diagnostic/refresh`
+    diagnostic_refresh: bool,
+    /// Server-created `$/progress` tokens
+    work_done_progress: bool,
+}
+
+/// Sets a cancellation flag when dropped, so analysis for a request stops
+/// when the request is cancelled and its handler dropped.
+struct CancelOnDrop(Arc<AtomicBool>);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+}
+
 /// Stored diagnostic with its fix for code actions
 #[derive(Clone)]
 struct StoredDiagnostic {
     lsp_diagnostic: tower_lsp::lsp_types::Diagnostic,
     fix: Option<Fix>,
-    file_path: PathBuf,
 }
 
 /// The cargo-perf LSP server backend.
+#[derive(Clone)]
 pub struct Backend {
     client: Client,
+    /// Config from `cargo-perf.toml`, before editor settings are applied
     config: Arc<RwLock<Config>>,
+    settings: Arc<RwLock<EditorSettings>>,
+    /// Baseline of the workspace root, if it has one
+    baseline: Arc<RwLock<Option<Baseline>>>,
     root_path: Arc<RwLock<Option<PathBuf>>>,
     /// Track last analysis time per file for rate limiting
     last_analysis: Arc<RwLock<HashMap<String, Instant>>>,
     /// Store diagnostics with fixes for code actions
     stored_diagnostics: Arc<RwLock<HashMap<Url, Vec<StoredDiagnostic>>>>,
+    /// Open buffers, including unsaved edits
+    documents: Arc<RwLock<DocumentStore>>,
+    /// Debounced analyses waiting to run, per document
+    pending: Arc<Mutex<HashMap<Url, AbortHandle>>>,
+    options: Arc<RwLock<ServerOptions>>,
+    client_support: Arc<RwLock<ClientSupport>>,
+    /// Cancellation flags of running analyses, by progress token
+    cancellable: Arc<Mutex<HashMap<ProgressToken, Arc<AtomicBool>>>>,
+    /// Progress tokens created so far, for naming the next one
+    progress_tokens: Arc<AtomicU64>,
+    /// Bumped whenever files or config change, to wake `workspace/diagnostic`
+    workspace_generation: Arc<watch::Sender<u64>>,
+    /// Generation the last `workspace/diagnostic` report was computed for
+    reported_generation: Arc<Mutex<Option<u64>>>,
+    /// Summaries of the workspace files, kept between buffer analyses so
+    /// each edit only re-parses what changed
+    analysis_cache: Arc<std::sync::Mutex<Option<AnalysisCache>>>,
 }
 
 impl Backend {
@@ -90,9 +250,20 @@ impl Backend {
         Self {
             client,
             config: Arc::new(RwLock::new(Config::default())),
+            settings: Arc::new(RwLock::new(EditorSettings::default())),
+            baseline: Arc::new(RwLock::new(None)),
             root_path: Arc::new(RwLock::new(None)),
             last_analysis: Arc::new(RwLock::new(HashMap::new())),
             stored_diagnostics: Arc::new(RwLock::new(HashMap::new())),
+            documents: Arc::new(RwLock::new(DocumentStore::default())),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            options: Arc::new(RwLock::new(ServerOptions::default())),
+            client_support: Arc::new(RwLock::new(ClientSupport::default())),
+            cancellable: Arc::new(Mutex::new(HashMap::new())),
+            progress_tokens: Arc::new(AtomicU64::new(0)),
+            workspace_generation: Arc::new(watch::Sender::new(0)),
+            reported_generation: Arc::new(Mutex::new(None)),
+            analysis_cache: Arc::new(std::sync::Mutex::new(None)),
         }
     }
 
@@ -145,32 +316,34 @@ impl Backend {
         }
     }
 
-    /// Analyze a document and publish diagnostics.
-    async fn analyze_and_publish(&self, uri: &Url) {
-        let path = match uri.to_file_path() {
-            Ok(p) => p,
-            Err(_) => return,
-        };
+    /// Resolve a document URI to a canonical `.rs` path inside the workspace.
+    async fn document_path(&self, uri: &Url) -> Option<PathBuf> {
+        let path = uri.to_file_path().ok()?;
 
         // Only analyze .rs files
         if path.extension().is_some_and(|ext| ext != "rs") {
-            return;
+            return None;
         }
 
         // SECURITY: Validate path is within workspace (prevent path traversal)
-        let canonical_path = match self.validate_path_in_workspace(&path).await {
-            Some(p) => p,
-            None => {
-                self.client
-                    .log_message(MessageType::WARNING, "Path outside workspace, skipping")
-                    .await;
-                return;
-            }
+        let canonical_path = self.validate_path_in_workspace(&path).await;
+        if canonical_path.is_none() {
+            self.client
+                .log_message(MessageType::WARNING, "Path outside workspace, skipping")
+                .await;
+        }
+        canonical_path
+    }
+
+    /// Analyze a file on disk and publish diagnostics.
+    async fn analyze_and_publish(&self, uri: &Url) {
+        let Some(canonical_path) = self.document_path(uri).await else {
+            return;
         };
 
-        // SECURITY: Open file immediately after validation to reduce TOCTOU window
-        // Get metadata from open file handle, not path (prevents race condition)
-        let file = match std::fs::File::open(&canonical_path) {
+        // SECURITY: Open file immediately after validation and read the
+        // content from that handle, so the file analyzed is the one validated
+        let mut file = match std::fs::File::open(&canonical_path) {
             Ok(f) => f,
             Err(_) => return,
         };
@@ -192,9 +365,10 @@ impl Backend {
             }
             Err(_) => return,
         }
-        // Drop file handle before analyze (which will re-open)
-        // Note: This reduces but doesn't eliminate TOCTOU - full fix would require
-        // passing file content to analyze(), which is a larger refactor
+        let mut source = String::new();
+        if std::io::Read::read_to_string(&mut file, &mut source).is_err() {
+            return;
+        }
         drop(file);
 
         // Rate limiting: debounce rapid re-analysis requests
@@ -204,11 +378,129 @@ impl Backend {
         }
         self.record_analysis(uri_str).await;
 
-        let config = self.config.read().await.clone();
+        if let Some(diagnostics) = self.analyze_text(&canonical_path, source).await {
+            self.publish(uri, &canonical_path, diagnostics, None).await;
+            self.refresh_code_lenses().await;
+        }
+    }
 
-        // Run analysis on the file
-        let diagnostics = match analyze(&canonical_path, &config) {
-            Ok(diags) => diags,
+    /// Analyze an open document's buffer, whether or not it is saved.
+    ///
+    /// Diagnostics are only published if the document has not changed while
+    /// it was analyzed; otherwise the run for the newer version publishes.
+    async fn analyze_document(&self, uri: &Url) {
+        let Some((text, version)) = self
+            .documents
+            .read()
+            .await
+            .get(uri)
+            .map(|document| (document.text.clone(), document.version))
+        else {
+            return;
+        };
+        let Some(canonical_path) = self.document_path(uri).await else {
+            return;
+        };
+        if text.len() as u64 > MAX_FILE_SIZE {
+            return;
+        }
+
+        let Some(diagnostics) = self.analyze_text(&canonical_path, text).await else {
+            return;
+        };
+        if self.documents.read().await.version(uri) != Some(version) {
+            return;
+        }
+        self.publish(uri, &canonical_path, diagnostics, Some(version))
+            .await;
+        self.refresh_code_lenses().await;
+    }
+
+    /// Analyze `text` as the file at `path` off the async runtime.
+    ///
+    /// Names resolve across the workspace files, with the other open
+    /// documents read from their buffers, as a workspace analysis would.
+    /// Dropping the future, as a newer edit does, stops the analysis.
+    async fn analyze_text(&self, path: &Path, text: String) -> Option<Vec<PerfDiagnostic>> {
+        let config = self.effective_config().await;
+        let root = self.root_path.read().await.clone();
+        let buffers = self.open_buffers().await;
+        let path = path.to_path_buf();
+        let cache = Arc::clone(&self.analysis_cache);
+        let cancel = CancelOnDrop(Arc::new(AtomicBool::new(false)));
+        let flag = Arc::clone(&cancel.0);
+        let result = tokio::task::spawn_blocking(move || {
+            let engine = Engine::new(&config)
+                .with_shared_cache(cache)
+                .with_cancellation(flag);
+            let Some(root) = root.and_then(|root| root.canonicalize().ok()) else {
+                return engine.analyze_source(&path, &text);
+            };
+            // Keyed like the workspace files are discovered, under the
+            // canonical root
+            let overlay = buffers
+                .into_iter()
+                .filter_map(|(file, text)| Some((file.canonicalize().ok()?, text)))
+                .filter(|(file, _)| file.starts_with(&root) && *file != path)
+                .collect();
+            engine.analyze_in_workspace(&root, &path, &text, overlay)
+        })
+        .await
+        .ok()?;
+        self.analysis_result(result).await
+    }
+
+    /// Paths and buffers of the open Rust documents.
+    async fn open_buffers(&self) -> Vec<(PathBuf, String)> {
+        let documents = self.documents.read().await;
+        documents
+            .uris()
+            .filter_map(|uri| {
+                let path = uri.to_file_path().ok()?;
+                let text = &documents.get(uri)?.text;
+                let rust = path.extension().is_some_and(|ext| ext == "rs");
+                (rust && text.len() as u64 <= MAX_FILE_SIZE).then(|| (path, text.clone()))
+            })
+            .collect()
+    }
+
+    /// Analyze a document for a pull request, from its buffer if it is open,
+    /// and store the diagnostics. `None` if it could not be analyzed.
+    async fn pull_document(&self, uri: &Url) -> Option<Vec<tower_lsp::lsp_types::Diagnostic>> {
+        let path = self.document_path(uri).await?;
+        let text = self.document_text(uri).await?;
+        let diagnostics = self.analyze_text(&path, text).await?;
+        Some(self.store(uri, &path, diagnostics).await)
+    }
+
+    /// Analyze an open document after the configured debounce delay.
+    ///
+    /// A pending run for the same document is cancelled, so a burst of edits
+    /// is analyzed once, on its last version.
+    async fn schedule_analysis(&self, uri: Url) {
+        let delay = Duration::from_millis(self.options.read().await.debounce_ms);
+        let backend = self.clone();
+        let task_uri = uri.clone();
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            backend.analyze_document(&task_uri).await;
+        });
+        if let Some(previous) = self.pending.lock().await.insert(uri, task.abort_handle()) {
+            previous.abort();
+        }
+    }
+
+    /// Diagnostics from an analysis, or `None` after logging why there are none.
+    async fn analysis_result(
+        &self,
+        result: crate::Result<Vec<PerfDiagnostic>>,
+    ) -> Option<Vec<PerfDiagnostic>> {
+        match result {
+            Ok(diags) => Some(diags),
+            // Mid-edit syntax errors are rustc's to report
+            Err(crate::Error::Parse { .. }) => None,
+            // A newer analysis replaces it
+            Err(crate::Error::Cancelled) => None,
             Err(e) => {
                 self.client
                     .log_message(
@@ -218,9 +510,39 @@ impl Backend {
                     .await;
                 // Log detailed error separately (not exposed to client)
                 eprintln!("cargo-perf analysis error: {}", e);
-                return;
+                None
             }
-        };
+        }
+    }
+
+    /// Store diagnostics of `path` and push them, unless the client pulls them.
+    async fn publish(
+        &self,
+        uri: &Url,
+        path: &Path,
+        diagnostics: Vec<PerfDiagnostic>,
+        version: Option<i32>,
+    ) {
+        let lsp_diagnostics = self.store(uri, path, diagnostics).await;
+        if !self.client_support.read().await.pull_diagnostics {
+            self.client
+                .publish_diagnostics(uri.clone(), lsp_diagnostics, version)
+                .await;
+        }
+    }
+
+    /// Store diagnostics of `path` for code actions, hover and code lens, and
+    /// return them as LSP diagnostics.
+    ///
+    /// Baselined findings are dropped or marked, per the `baselined` option.
+    async fn store(
+        &self,
+        uri: &Url,
+        path: &Path,
+        diagnostics: Vec<PerfDiagnostic>,
+    ) -> Vec<tower_lsp::lsp_types::Diagnostic> {
+        let is_baselined = self.baseline_matcher(uri, path, &diagnostics).await;
+        let mode = self.options.read().await.baselined;
 
         // Convert to LSP diagnostics and store for code actions
         // cargo-perf-ignore: vec-no-capacity
@@ -228,22 +550,23 @@ impl Backend {
         // cargo-perf-ignore: vec-no-capacity
         let mut lsp_diagnostics = Vec::new();
 
-        for diag in diagnostics
-            .into_iter()
-            .filter(|d| d.file_path == canonical_path)
-        {
-            // Need clones: fix for storage, file_path for StoredDiagnostic, lsp_diag for both vectors
+        for diag in diagnostics.into_iter().filter(|d| d.file_path == path) {
+            let baselined = is_baselined(&diag);
+            if baselined && mode == BaselinedFindings::Hide {
+                continue;
+            }
+            // Need clones: fix for storage, lsp_diag for both vectors
             // cargo-perf-ignore: clone-in-hot-loop
             let fix = diag.fix.clone();
-            // cargo-perf-ignore: clone-in-hot-loop
-            let file_path = diag.file_path.clone();
-            let lsp_diag = perf_diag_to_lsp(diag);
+            let mut lsp_diag = perf_diag_to_lsp(diag);
+            if baselined {
+                mark_baselined(&mut lsp_diag);
+            }
 
             stored.push(StoredDiagnostic {
                 // cargo-perf-ignore: clone-in-hot-loop
                 lsp_diagnostic: lsp_diag.clone(),
                 fix,
-                file_path,
             });
             lsp_diagnostics.push(lsp_diag);
         }
@@ -253,79 +576,303 @@ impl Backend {
             let mut stored_map = self.stored_diagnostics.write().await;
             stored_map.insert(uri.clone(), stored);
         }
-
-        self.client
-            .publish_diagnostics(uri.clone(), lsp_diagnostics, None)
-            .await;
+        lsp_diagnostics
     }
 
-    /// Analyze all Rust files in the workspace.
-    async fn analyze_workspace(&self) {
+    /// A predicate telling which of `diagnostics` of `path` are in the
+    /// baseline, hashing their context from the document's current text.
+    async fn baseline_matcher(
+        &self,
+        uri: &Url,
+        path: &Path,
+        diagnostics: &[PerfDiagnostic],
+    ) -> impl Fn(&PerfDiagnostic) -> bool {
+        let baseline = self.baseline.read().await;
         let root = self.root_path.read().await.clone();
-        let root = match root {
-            Some(r) => r,
-            None => return,
+        let fingerprints = match (&*baseline, root, diagnostics.is_empty()) {
+            (Some(baseline), Some(root), false) => {
+                // Document paths are canonical, workspace scan paths are not
+                let root = match root.canonicalize() {
+                    Ok(canonical) if path.starts_with(&canonical) => canonical,
+                    _ => root,
+                };
+                let text = self.document_text(uri).await.unwrap_or_default();
+                diagnostics
+                    .iter()
+                    .zip(baseline.contains_in_source(diagnostics, &root, &text))
+                    .filter(|&(_, baselined)| baselined)
+                    .map(|(diag, _)| (diag.rule_id, diag.line, diag.column))
+                    .collect()
+            }
+            _ => HashSet::new(),
         };
+        move |diag| fingerprints.contains(&(diag.rule_id, diag.line, diag.column))
+    }
 
-        let config = self.config.read().await.clone();
+    /// Ask the client to re-request code lenses, whose counts follow the
+    /// published diagnostics.
+    async fn refresh_code_lenses(&self) {
+        let refresh = self.client_support.read().await.code_lens_refresh;
+        if refresh && self.options.read().await.code_lens {
+            let _ = self.client.code_lens_refresh().await;
+        }
+    }
 
-        let diagnostics = match analyze(&root, &config) {
-            Ok(diags) => diags,
+    /// Current text of a document: the open buffer, or the file on disk, read
+    /// off the async runtime.
+    async fn document_text(&self, uri: &Url) -> Option<String> {
+        if let Some(document) = self.documents.read().await.get(uri) {
+            return Some(document.text.clone());
+        }
+        let path = self.document_path(uri).await?;
+        tokio::task::spawn_blocking(move || {
+            // Check the size before reading, so an oversized file is never
+            // loaded into memory
+            let mut file = std::fs::File::open(path).ok()?;
+            if file.metadata().ok()?.len() > MAX_FILE_SIZE {
+                return None;
+            }
+            let mut text = String::new();
+            std::io::Read::read_to_string(&mut file, &mut text).ok()?;
+            Some(text)
+        })
+        .await
+        .ok()?
+    }
+
+    /// Config in effect: `cargo-perf.toml` with editor settings applied.
+    async fn effective_config(&self) -> Config {
+        let mut config = self.config.read().await.clone();
+        self.settings.read().await.apply(&mut config);
+        config
+    }
+
+    /// Reload `cargo-perf.toml` off the async runtime, keeping the current
+    /// config if it is invalid.
+    async fn reload_config(&self) {
+        let Some(root) = self.root_path.read().await.clone() else {
+            return;
+        };
+        // A panic while loading keeps the current config as well
+        let Ok(loaded) = tokio::task::spawn_blocking(move || Config::load_or_default(&root)).await
+        else {
+            return;
+        };
+        match loaded {
+            Ok(config) => *self.config.write().await = config,
             Err(e) => {
                 self.client
                     .log_message(
-                        MessageType::ERROR,
-                        format!("Workspace analysis failed: {}", e),
+                        MessageType::WARNING,
+                        format!(
+                            "Keeping previous config, {} is invalid: {}",
+                            CONFIG_FILENAME, e
+                        ),
                     )
                     .await;
-                return;
             }
+        }
+    }
+
+    /// Reload the workspace baseline off the async runtime, keeping the current
+    /// one if it is invalid.
+    async fn reload_baseline(&self) {
+        let Some(root) = self.root_path.read().await.clone() else {
+            return;
+        };
+        let Ok(loaded) = tokio::task::spawn_blocking(move || Baseline::load(&root)).await else {
+            return;
         };
+        match loaded {
+            Ok(baseline) => *self.baseline.write().await = Some(baseline),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                *self.baseline.write().await = None;
+            }
+            Err(e) => {
+                self.client
+                    .log_message(
+                        MessageType::WARNING,
+                        format!(
+                            "Keeping previous baseline, {} is invalid: {}",
+                            BASELINE_FILENAME, e
+                        ),
+                    )
+                    .await;
+            }
+        }
+    }
 
-        // Group diagnostics by file
-        let mut by_file: HashMap<PathBuf, Vec<PerfDiagnostic>> = HashMap::new();
-        for diag in diagnostics {
-            // cargo-perf-ignore: clone-in-hot-loop
-            let key = diag.file_path.clone();
-            by_file.entry(key).or_default().push(diag);
+    /// Re-analyze the workspace and every open buffer, after the config changed.
+    async fn reanalyze(&self) {
+        self.workspace_changed();
+        let support = *self.client_support.read().await;
+        if support.pull_diagnostics {
+            if support.diagnostic_refresh {
+                let _ = self.client.workspace_diagnostic_refresh().await;
+            }
+            return;
         }
+        self.analyze_workspace().await;
+        let open: Vec<Url> = self.documents.read().await.uris().cloned().collect();
+        for uri in open {
+            self.analyze_document(&uri).await;
+        }
+    }
 
-        // Publish and store diagnostics for each file
-        for (path, diags) in by_file {
-            if let Ok(uri) = Url::from_file_path(&path) {
-                // cargo-perf-ignore: vec-no-capacity
-                let mut stored = Vec::new();
-                // cargo-perf-ignore: vec-no-capacity
-                let mut lsp_diagnostics = Vec::new();
+    /// Watch `cargo-perf.toml` and the baseline through the client.
+    async fn register_file_watchers(&self) {
+        let watchers = [CONFIG_FILENAME, BASELINE_FILENAME]
+            .into_iter()
+            .map(|name| FileSystemWatcher {
+                glob_pattern: GlobPattern::String(format!("**/{}", name)),
+                kind: None,
+            })
+            .collect();
+        let registration = Registration {
+            id: "cargo-perf-watched-files".to_string(),
+            method: "workspace/didChangeWatchedFiles".to_string(),
+            register_options: serde_json::to_value(DidChangeWatchedFilesRegistrationOptions {
+                watchers,
+            })
+            .ok(),
+        };
+        if let Err(e) = self.client.register_capability(vec![registration]).await {
+            self.client
+                .log_message(
+                    MessageType::WARNING,
+                    format!("Cannot watch {}: {}", CONFIG_FILENAME, e),
+                )
+                .await;
+        }
+    }
 
-                for diag in diags {
-                    // cargo-perf-ignore: clone-in-hot-loop
-                    let fix = diag.fix.clone();
-                    // cargo-perf-ignore: clone-in-hot-loop
-                    let file_path = diag.file_path.clone();
-                    let lsp_diag = perf_diag_to_lsp(diag);
+    /// Note that files or config changed, waking a pending `workspace/diagnostic`.
+    fn workspace_changed(&self) {
+        self.workspace_generation
+            .send_modify(|generation| *generation += 1);
+    }
 
-                    stored.push(StoredDiagnostic {
-                        // cargo-perf-ignore: clone-in-hot-loop
-                        lsp_diagnostic: lsp_diag.clone(),
-                        fix,
-                        file_path,
-                    });
-                    lsp_diagnostics.push(lsp_diag);
-                }
+    /// Analyze every Rust file in the workspace off the async runtime.
+    ///
+    /// Progress is reported under `token`, or under a new token if the client
+    /// supports that. The analysis stops with [`crate::Error::Cancelled`] if
+    /// the client cancels the progress or the calling request.
+    async fn scan_workspace(
+        &self,
+        token: Option<ProgressToken>,
+    ) -> crate::Result<Vec<PerfDiagnostic>> {
+        let Some(root) = self.root_path.read().await.clone() else {
+            return Ok(Vec::new());
+        };
+        let config = self.effective_config().await;
 
-                // Store for code actions (outside inner loop - not mutex-in-loop)
-                {
-                    // cargo-perf-ignore: mutex-in-loop
-                    let mut stored_map = self.stored_diagnostics.write().await;
-                    // cargo-perf-ignore: clone-in-hot-loop
-                    stored_map.insert(uri.clone(), stored);
-                }
+        let token = match token {
+            Some(token) => Some(token),
+            None if self.client_support.read().await.work_done_progress => {
+                let id = self.progress_tokens.fetch_add(1, Ordering::Relaxed);
+                Progress::create(&self.client, format!("cargo-perf/workspace/{}", id)).await
+            }
+            None => None,
+        };
+        let cancel = CancelOnDrop(Arc::new(AtomicBool::new(false)));
+        let mut progress = match token {
+            Some(token) => {
+                self.cancellable
+                    .lock()
+                    .await
+                    .insert(token.clone(), Arc::clone(&cancel.0));
+                Some(Progress::begin(&self.client, token, "cargo-perf: analyzing workspace").await)
+            }
+            None => None,
+        };
+
+        // Workers report each analyzed file; forward that until they finish
+        // and drop the sender
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let flag = Arc::clone(&cancel.0);
+        let task = tokio::task::spawn_blocking(move || {
+            Engine::new(&config)
+                .with_cancellation(flag)
+                .analyze_with_progress(&root, |p| {
+                    let _ = sender.send((p.files_analyzed, p.total_files));
+                })
+        });
+        while let Some((done, total)) = receiver.recv().await {
+            if let Some(progress) = &mut progress {
+                progress.report(done, total).await;
+            }
+        }
+        let result = task.await.unwrap_or(Err(crate::Error::Cancelled));
+
+        if let Some(progress) = progress {
+            self.cancellable.lock().await.remove(&progress.token);
+            let message = match &result {
+                Ok(diagnostics) => match diagnostics.len() {
+                    1 => "1 finding".to_string(),
+                    n => format!("{} findings", n),
+                },
+                Err(crate::Error::Cancelled) => "Cancelled".to_string(),
+                Err(_) => "Failed".to_string(),
+            };
+            progress.end(message).await;
+        }
+        result
+    }
 
+    /// Analyze all Rust files in the workspace and push their diagnostics.
+    ///
+    /// Open documents are skipped, as their buffers may differ from disk.
+    async fn analyze_workspace(&self) {
+        let diagnostics = match self.scan_workspace(None).await {
+            Ok(diags) => diags,
+            Err(crate::Error::Cancelled) => return,
+            Err(e) => {
                 self.client
-                    .publish_diagnostics(uri, lsp_diagnostics, None)
+                    .log_message(
+                        MessageType::ERROR,
+                        format!("Workspace analysis failed: {}", e),
+                    )
                     .await;
+                return;
+            }
+        };
+
+        // Publish and store diagnostics for each file
+        let open: HashSet<Url> = self.documents.read().await.uris().cloned().collect();
+        let mut published = HashSet::new();
+        for (path, diags) in group_by_file(diagnostics) {
+            if let Ok(uri) = Url::from_file_path(&path) {
+                if open.contains(&uri) {
+                    continue;
+                }
+                self.publish(&uri, &path, diags, None).await;
+                published.insert(uri);
+            }
+        }
+
+        // Clear files whose findings all went away
+        let stale: Vec<Url> = {
+            let mut stored = self.stored_diagnostics.write().await;
+            let stale: Vec<Url> = stored
+                .keys()
+                .filter(|uri| !published.contains(*uri) && !open.contains(*uri))
+                .cloned()
+                .collect();
+            for uri in &stale {
+                stored.remove(uri);
             }
+            stale
+        };
+        for uri in stale {
+            self.client.publish_diagnostics(uri, vec![], None).await;
+        }
+        self.refresh_code_lenses().await;
+    }
+
+    /// Cancel the analysis reporting progress under `token`.
+    async fn work_done_progress_cancel(&self, params: WorkDoneProgressCancelParams) {
+        if let Some(flag) = self.cancellable.lock().await.get(&params.token) {
+            flag.store(true, Ordering::Relaxed);
         }
     }
 }
@@ -333,6 +880,20 @@ impl Backend {
 #[tower_lsp::async_trait]
 impl LanguageServer for Backend {
     async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        if let Some(options) = params.initialization_options {
+            match serde_json::from_value::<ServerOptions>(options) {
+                Ok(options) => *self.options.write().await = options,
+                Err(e) => {
+                    self.client
+                        .log_message(
+                            MessageType::WARNING,
+                            format!("Ignoring invalid initializationOptions: {}", e),
+                        )
+                        .await;
+                }
+            }
+        }
+
         // Store root path
         if let Some(root_uri) = params.root_uri {
             if let Ok(path) = root_uri.to_file_path() {
@@ -344,13 +905,41 @@ impl LanguageServer for Backend {
                 }
             }
         }
+        self.reload_baseline().await;
+
+        let capabilities = params.capabilities;
+        let workspace = capabilities.workspace.as_ref();
+        *self.client_support.write().await = ClientSupport {
+            code_lens_refresh: workspace
+                .and_then(|workspace| workspace.code_lens.as_ref())
+                .and_then(|code_lens| code_lens.refresh_support)
+                .unwrap_or(false),
+            watched_files: workspace
+                .and_then(|workspace| workspace.did_change_watched_files.as_ref())
+                .and_then(|watched| watched.dynamic_registration)
+                .unwrap_or(false),
+            pull_diagnostics: capabilities
+                .text_document
+                .as_ref()
+                .is_some_and(|text_document| text_document.diagnostic.is_some()),
+            diagnostic_refresh: workspace
+                .and_then(|workspace| workspace.diagnostic.as_ref())
+                .and_then(|diagnostic| diagnostic.refresh_support)
+                .unwrap_or(false),
+            work_done_progress: capabilities
+                .window
+                .as_ref()
+                .and_then(|window| window.work_done_progress)
+                .unwrap_or(false),
+        };
+        let code_lens = self.options.read().await.code_lens;
 
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
                 text_document_sync: Some(TextDocumentSyncCapability::Options(
                     TextDocumentSyncOptions {
                         open_close: Some(true),
-                        change: Some(TextDocumentSyncKind::FULL),
+                        change: Some(TextDocumentSyncKind::INCREMENTAL),
                         save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
                             include_text: Some(false),
                         })),
@@ -372,6 +961,10 @@ impl LanguageServer for Backend {
                         ..Default::default()
                     },
                 )),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                code_lens_provider: code_lens.then_some(CodeLensOptions {
+                    resolve_provider: Some(false),
+                }),
                 ..Default::default()
             },
             server_info: Some(ServerInfo {
@@ -386,41 +979,349 @@ impl LanguageServer for Backend {
             .log_message(MessageType::INFO, "cargo-perf LSP server initialized")
             .await;
 
-        // Initial workspace analysis
-        self.analyze_workspace().await;
+        if self.client_support.read().await.watched_files {
+            self.register_file_watchers().await;
+        }
+
+        // Initial workspace analysis; pulling clients ask for it instead
+        if !self.client_support.read().await.pull_diagnostics {
+            self.analyze_workspace().await;
+        }
     }
 
     async fn shutdown(&self) -> Result<()> {
         Ok(())
     }
 
+    async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
+        let settings = match EditorSettings::from_value(params.settings) {
+            Ok(settings) => settings,
+            Err(e) => {
+                self.client
+                    .log_message(
+                        MessageType::WARNING,
+                        format!("Ignoring invalid cargo-perf settings: {}", e),
+                    )
+                    .await;
+                return;
+            }
+        };
+        for rule_id in settings.rules.keys() {
+            if !registry::has_rule(rule_id) {
+                self.client
+                    .log_message(
+                        MessageType::WARNING,
+                        format!("Unknown rule '{}' in settings (will be ignored)", rule_id),
+                    )
+                    .await;
+            }
+        }
+
+        if *self.settings.read().await == settings {
+            return;
+        }
+        *self.settings.write().await = settings;
+        self.reanalyze().await;
+    }
+
+    async fn did_change_watched_files(&self, params: DidChangeWatchedFilesParams) {
+        let root = self.root_path.read().await.clone();
+        let relevant = params.changes.iter().any(|change| {
+            change
+                .uri
+                .to_file_path()
+                .is_ok_and(|path| is_watched_file(&path, root.as_deref()))
+        });
+        if relevant {
+            self.reload_config().await;
+            self.reload_baseline().await;
+            self.reanalyze().await;
+        } else {
+            self.workspace_changed();
+        }
+    }
+
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
-        self.analyze_and_publish(&params.text_document.uri).await;
+        let document = params.text_document;
+        self.documents
+            .write()
+            .await
+            .open(document.uri.clone(), document.text, document.version);
+        // Pulling clients request diagnostics when they need them
+        if !self.client_support.read().await.pull_diagnostics {
+            self.analyze_document(&document.uri).await;
+        }
     }
 
-    async fn did_change(&self, _params: DidChangeTextDocumentParams) {
-        // We analyze on save, not on every change (for performance)
+    async fn did_change(&self, params: DidChangeTextDocumentParams) {
+        let document = params.text_document;
+        let changed = self.documents.write().await.change(
+            &document.uri,
+            document.version,
+            params.content_changes,
+        );
+        if changed && !self.client_support.read().await.pull_diagnostics {
+            self.schedule_analysis(document.uri).await;
+        }
     }
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
-        self.analyze_and_publish(&params.text_document.uri).await;
+        self.workspace_changed();
+        if self.client_support.read().await.pull_diagnostics {
+            return;
+        }
+        let uri = params.text_document.uri;
+        if self.documents.read().await.get(&uri).is_some() {
+            // The buffer is what was saved; analyze it now rather than after
+            // the debounce
+            if let Some(pending) = self.pending.lock().await.remove(&uri) {
+                pending.abort();
+            }
+            self.analyze_document(&uri).await;
+        } else {
+            self.analyze_and_publish(&uri).await;
+        }
     }
 
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
         // Clear diagnostics and stored data when file is closed
+        if let Some(pending) = self.pending.lock().await.remove(&params.text_document.uri) {
+            pending.abort();
+        }
+        self.documents
+            .write()
+            .await
+            .close(&params.text_document.uri);
         {
             let mut stored = self.stored_diagnostics.write().await;
             stored.remove(&params.text_document.uri);
         }
-        self.client
-            .publish_diagnostics(params.text_document.uri, vec![], None)
-            .await;
+        if self.client_support.read().await.pull_diagnostics {
+            // The workspace report covers the file again
+            self.workspace_changed();
+        } else {
+            self.client
+                .publish_diagnostics(params.text_document.uri, vec![], None)
+                .await;
+        }
+    }
+
+    async fn diagnostic(
+        &self,
+        params: DocumentDiagnosticParams,
+    ) -> Result<DocumentDiagnosticReportResult> {
+        let uri = params.text_document.uri;
+        let previous = params.previous_result_id;
+        let report = match self.pull_document(&uri).await {
+            Some(items) => diagnostic_report(items, previous.as_deref()),
+            // Keep what the client has while the file does not parse
+            None => match previous {
+                Some(result_id) => {
+                    DocumentDiagnosticReportKind::Unchanged(UnchangedDocumentDiagnosticReport {
+                        result_id,
+                    })
+                }
+                None => diagnostic_report(Vec::new(), None),
+            },
+        };
+        if matches!(report, DocumentDiagnosticReportKind::Full(_)) {
+            self.refresh_code_lenses().await;
+        }
+
+        let report = match report {
+            DocumentDiagnosticReportKind::Full(full) => {
+                DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+                    related_documents: None,
+                    full_document_diagnostic_report: full,
+                })
+            }
+            DocumentDiagnosticReportKind::Unchanged(unchanged) => {
+                DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                    related_documents: None,
+                    unchanged_document_diagnostic_report: unchanged,
+                })
+            }
+        };
+        Ok(DocumentDiagnosticReportResult::Report(report))
+    }
+
+    async fn workspace_diagnostic(
+        &self,
+        params: WorkspaceDiagnosticParams,
+    ) -> Result<WorkspaceDiagnosticReportResult> {
+        let previous: HashMap<Url, String> = params
+            .previous_result_ids
+            .into_iter()
+            .map(|id| (id.uri, id.value))
+            .collect();
+
+        // Clients ask again as soon as they get a report: hold a repeated
+        // request until something changed, rather than re-scanning
+        let mut generation = self.workspace_generation.subscribe();
+        let reported = *self.reported_generation.lock().await;
+        if reported == Some(*generation.borrow_and_update()) {
+            let _ = generation.changed().await;
+        }
+        let scanned = *generation.borrow_and_update();
+
+        let token = params.work_done_progress_params.work_done_token;
+        let diagnostics = match self.scan_workspace(token).await {
+            Ok(diagnostics) => diagnostics,
+            Err(crate::Error::Cancelled) => return Err(RpcError::request_cancelled()),
+            Err(e) => {
+                eprintln!("cargo-perf analysis error: {}", e);
+                let mut error = RpcError::internal_error();
+                error.message = "Workspace analysis failed".into();
+                return Err(error);
+            }
+        };
+
+        // Open documents are pulled one by one, from their buffers
+        let open: HashSet<Url> = self.documents.read().await.uris().cloned().collect();
+        // cargo-perf-ignore: vec-no-capacity
+        let mut items = Vec::new();
+        let mut reported_uris = HashSet::new();
+        for (path, diags) in group_by_file(diagnostics) {
+            let Ok(uri) = Url::from_file_path(&path) else {
+                continue;
+            };
+            if open.contains(&uri) {
+                continue;
+            }
+            let lsp_diagnostics = self.store(&uri, &path, diags).await;
+            let report = diagnostic_report(lsp_diagnostics, previous.get(&uri).map(String::as_str));
+            reported_uris.insert(uri.clone());
+            items.push(workspace_report(uri, report));
+        }
+
+        // Files reported before that have no findings now
+        let cleared: Vec<(Url, String)> = previous
+            .into_iter()
+            .filter(|(uri, _)| !reported_uris.contains(uri) && !open.contains(uri))
+            .collect();
+        {
+            let mut stored = self.stored_diagnostics.write().await;
+            for (uri, _) in &cleared {
+                stored.remove(uri);
+            }
+        }
+        for (uri, result_id) in cleared {
+            items.push(workspace_report(
+                uri,
+                diagnostic_report(Vec::new(), Some(&result_id)),
+            ));
+        }
+
+        *self.reported_generation.lock().await = Some(scanned);
+        self.refresh_code_lenses().await;
+        Ok(WorkspaceDiagnosticReportResult::Report(
+            WorkspaceDiagnosticReport { items },
+        ))
+    }
+
+    async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
+        let position = params.text_document_position_params.position;
+        let stored = self.stored_diagnostics.read().await;
+        let Some(file_diagnostics) =
+            stored.get(&params.text_document_position_params.text_document.uri)
+        else {
+            return Ok(None);
+        };
+
+        // One explanation per rule flagged at this position
+        let mut range: Option<Range> = None;
+        let mut rule_ids: Vec<&str> = Vec::new();
+        for stored_diag in file_diagnostics {
+            let diag = &stored_diag.lsp_diagnostic;
+            if !range_contains(&diag.range, position) {
+                continue;
+            }
+            let Some(NumberOrString::String(rule_id)) = &diag.code else {
+                continue;
+            };
+            if !rule_ids.contains(&rule_id.as_str()) {
+                rule_ids.push(rule_id);
+                range.get_or_insert(diag.range);
+            }
+        }
+
+        let sections: Vec<String> = rule_ids
+            .into_iter()
+            .filter_map(registry::get_rule)
+            .map(explain::markdown)
+            .collect();
+        if sections.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: sections.join("\n---\n\n"),
+            }),
+            range,
+        }))
+    }
+
+    async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
+        if !self.options.read().await.code_lens {
+            return Ok(None);
+        }
+        let uri = &params.text_document.uri;
+        let Some(text) = self.document_text(uri).await else {
+            return Ok(None);
+        };
+        let Some(functions) = lens::functions(&text) else {
+            return Ok(None);
+        };
+
+        let stored = self.stored_diagnostics.read().await;
+        let Some(file_diagnostics) = stored.get(uri) else {
+            return Ok(None);
+        };
+        let lenses: Vec<CodeLens> = functions
+            .into_iter()
+            .filter_map(|function| {
+                // cargo-perf-ignore: vec-no-capacity
+                let mut severities = Vec::new();
+                for stored_diag in file_diagnostics {
+                    let line = stored_diag.lsp_diagnostic.range.start.line as usize + 1;
+                    if (function.line..=function.end_line).contains(&line) {
+                        severities.push(stored_diag.lsp_diagnostic.severity);
+                    }
+                }
+                let title = findings_summary(&severities)?;
+                let line = function.line.saturating_sub(1) as u32;
+                Some(CodeLens {
+                    range: Range::new(Position::new(line, 0), Position::new(line, 0)),
+                    command: Some(Command {
+                        title,
+                        command: String::new(),
+                        arguments: None,
+                    }),
+                    data: None,
+                })
+            })
+            .collect();
+        Ok(Some(lenses))
     }
 
     async fn code_action(&self, params: CodeActionParams) -> Result<Option<CodeActionResponse>> {
         let uri = &params.text_document.uri;
         let request_range = params.range;
 
+        // The buffer the diagnostics were computed on, or the file if it is
+        // not open
+        let Some(source) = self.document_text(uri).await else {
+            return Ok(None);
+        };
+        let line_index = LineIndex::new(&source);
+        let functions = lens::functions(&source).unwrap_or_default();
+        // Suppressions without a reason would be findings themselves
+        let require_reason = match uri.to_file_path() {
+            Ok(path) => requires_reason(&self.effective_config().await.for_file(&path, None)),
+            Err(_) => false,
+        };
+
         // Get stored diagnostics for this file
         let stored = self.stored_diagnostics.read().await;
         let file_diagnostics = match stored.get(uri) {
@@ -428,85 +1329,62 @@ impl LanguageServer for Backend {
             None => return Ok(None),
         };
 
-        // Find diagnostics that overlap with the requested range and have fixes
+        // Offer the fix, if any, and suppressions for each diagnostic that
+        // overlaps with the requested range
         // cargo-perf-ignore: vec-no-capacity
         let mut actions = Vec::new();
 
         for stored_diag in file_diagnostics {
-            // Check if this diagnostic overlaps with the requested range
-            if !ranges_overlap(&stored_diag.lsp_diagnostic.range, &request_range) {
+            let diagnostic = &stored_diag.lsp_diagnostic;
+            if !ranges_overlap(&diagnostic.range, &request_range) {
                 continue;
             }
 
-            // Only create code action if there's a fix
-            let fix = match &stored_diag.fix {
-                Some(f) => f,
-                None => continue,
-            };
-
-            // Read file to build LineIndex for byte-to-position conversion
-            let source = match std::fs::read_to_string(&stored_diag.file_path) {
-                Ok(s) => s,
-                Err(e) => {
-                    eprintln!(
-                        "Warning: Cannot read file for code action: {} ({})",
-                        stored_diag.file_path.display(),
-                        e
-                    );
-                    continue;
-                }
-            };
-            let line_index = LineIndex::new(&source);
-
-            // Build workspace edit from fix replacements
-            // cargo-perf-ignore: vec-no-capacity
-            let mut text_edits = Vec::new();
-            for replacement in &fix.replacements {
-                let (start_line, start_col) = line_index.line_col(replacement.start_byte);
-                let (end_line, end_col) = line_index.line_col(replacement.end_byte);
-
-                text_edits.push(TextEdit {
-                    range: Range {
-                        start: Position {
-                            line: start_line.saturating_sub(1) as u32,
-                            character: start_col.saturating_sub(1) as u32,
-                        },
-                        end: Position {
-                            line: end_line.saturating_sub(1) as u32,
-                            character: end_col.saturating_sub(1) as u32,
+            if let Some(fix) = &stored_diag.fix {
+                // Build workspace edit from fix replacements
+                // cargo-perf-ignore: vec-no-capacity
+                let mut text_edits = Vec::new();
+                for replacement in &fix.replacements {
+                    let (start_line, start_col) = line_index.line_col(replacement.start_byte);
+                    let (end_line, end_col) = line_index.line_col(replacement.end_byte);
+
+                    text_edits.push(TextEdit {
+                        range: Range {
+                            start: Position {
+                                line: start_line.saturating_sub(1) as u32,
+                                character: start_col.saturating_sub(1) as u32,
+                            },
+                            end: Position {
+                                line: end_line.saturating_sub(1) as u32,
+                                character: end_col.saturating_sub(1) as u32,
+                            },
                         },
-                    },
+                        // cargo-perf-ignore: clone-in-hot-loop
+                        new_text: replacement.new_text.clone(),
+                    });
+                }
+
+                if !text_edits.is_empty() {
                     // cargo-perf-ignore: clone-in-hot-loop
-                    new_text: replacement.new_text.clone(),
-                });
+                    let title = fix.description.clone();
+                    actions.push(quick_fix(uri, title, diagnostic, text_edits, true));
+                }
             }
 
-            if text_edits.is_empty() {
+            let Some(NumberOrString::String(rule_id)) = &diagnostic.code else {
                 continue;
-            }
-
-            let mut changes = HashMap::new();
-            // cargo-perf-ignore: clone-in-hot-loop
-            changes.insert(uri.clone(), text_edits);
-
-            let code_action = CodeAction {
-                // cargo-perf-ignore: clone-in-hot-loop
-                title: fix.description.clone(),
-                kind: Some(CodeActionKind::QUICKFIX),
-                // cargo-perf-ignore: clone-in-hot-loop
-                diagnostics: Some(vec![stored_diag.lsp_diagnostic.clone()]),
-                edit: Some(WorkspaceEdit {
-                    changes: Some(changes),
-                    document_changes: None,
-                    change_annotations: None,
-                }),
-                command: None,
-                is_preferred: Some(true),
-                disabled: None,
-                data: None,
             };
-
-            actions.push(CodeActionOrCommand::CodeAction(code_action));
+            let line = diagnostic.range.start.line as usize;
+            if let Some(edit) = suppress::line_suppression(&source, line, rule_id, require_reason) {
+                let title = format!("Suppress `{}` on this line", rule_id);
+                actions.push(quick_fix(uri, title, diagnostic, vec![edit], false));
+            }
+            if let Some(edit) =
+                suppress::function_suppression(&source, &functions, line, rule_id, require_reason)
+            {
+                let title = format!("Suppress `{}` in this function", rule_id);
+                actions.push(quick_fix(uri, title, diagnostic, vec![edit], false));
+            }
         }
 
         if actions.is_empty() {
@@ -526,6 +1404,145 @@ fn ranges_overlap(a: &Range, b: &Range) -> bool {
         || (b.end.line == a.start.line && b.end.character < a.start.character))
 }
 
+/// Diagnostics grouped by file.
+fn group_by_file(diagnostics: Vec<PerfDiagnostic>) -> HashMap<PathBuf, Vec<PerfDiagnostic>> {
+    let mut by_file: HashMap<PathBuf, Vec<PerfDiagnostic>> = HashMap::new();
+    for diag in diagnostics {
+        // cargo-perf-ignore: clone-in-hot-loop
+        let key = diag.file_path.clone();
+        by_file.entry(key).or_default().push(diag);
+    }
+    by_file
+}
+
+/// Result ID of a set of diagnostics: equal diagnostics give equal IDs, so a
+/// client that already has them is told they are unchanged.
+fn result_id(diagnostics: &[tower_lsp::lsp_types::Diagnostic]) -> String {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    serde_json::to_string(diagnostics)
+        .unwrap_or_default()
+        .hash(&mut hasher);
+    format!("{:016x}", hasher.finish())
+}
+
+/// A full report of `diagnostics`, or an unchanged one if the client's
+/// `previous` result ID matches them.
+fn diagnostic_report(
+    diagnostics: Vec<tower_lsp::lsp_types::Diagnostic>,
+    previous: Option<&str>,
+) -> DocumentDiagnosticReportKind {
+    let result_id = result_id(&diagnostics);
+    if previous == Some(result_id.as_str()) {
+        DocumentDiagnosticReportKind::Unchanged(UnchangedDocumentDiagnosticReport { result_id })
+    } else {
+        DocumentDiagnosticReportKind::Full(FullDocumentDiagnosticReport {
+            result_id: Some(result_id),
+            items: diagnostics,
+        })
+    }
+}
+
+fn workspace_report(
+    uri: Url,
+    report: DocumentDiagnosticReportKind,
+) -> WorkspaceDocumentDiagnosticReport {
+    match report {
+        DocumentDiagnosticReportKind::Full(full) => {
+            WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                uri,
+                version: None,
+                full_document_diagnostic_report: full,
+            })
+        }
+        DocumentDiagnosticReportKind::Unchanged(unchanged) => {
+            WorkspaceDocumentDiagnosticReport::Unchanged(
+                WorkspaceUnchangedDocumentDiagnosticReport {
+                    uri,
+                    version: None,
+                    unchanged_document_diagnostic_report: unchanged,
+                },
+            )
+        }
+    }
+}
+
+/// Whether a change to `path` affects analysis: the config or baseline at the
+/// workspace root.
+fn is_watched_file(path: &Path, root: Option<&Path>) -> bool {
+    let in_root = match root {
+        Some(root) => path.parent() == Some(root),
+        None => true,
+    };
+    in_root
+        && path
+            .file_name()
+            .is_some_and(|name| name == CONFIG_FILENAME || name == BASELINE_FILENAME)
+}
+
+/// A quick-fix code action applying `edits` to `uri` for `diagnostic`.
+fn quick_fix(
+    uri: &Url,
+    title: String,
+    diagnostic: &tower_lsp::lsp_types::Diagnostic,
+    edits: Vec<TextEdit>,
+    preferred: bool,
+) -> CodeActionOrCommand {
+    let mut changes = HashMap::new();
+    changes.insert(uri.clone(), edits);
+
+    CodeActionOrCommand::CodeAction(CodeAction {
+        title,
+        kind: Some(CodeActionKind::QUICKFIX),
+        diagnostics: Some(vec![diagnostic.clone()]),
+        edit: Some(WorkspaceEdit {
+            changes: Some(changes),
+            document_changes: None,
+            change_annotations: None,
+        }),
+        command: None,
+        is_preferred: Some(preferred),
+        disabled: None,
+        data: None,
+    })
+}
+
+/// Check if a range contains a position, both ends inclusive.
+fn range_contains(range: &Range, position: Position) -> bool {
+    range.start <= position && position <= range.end
+}
+
+/// Code lens title for a function's findings, or `None` if there are none.
+fn findings_summary(severities: &[Option<DiagnosticSeverity>]) -> Option<String> {
+    if severities.is_empty() {
+        return None;
+    }
+    let count = |severity| severities.iter().filter(|&&s| s == Some(severity)).count();
+    let parts: Vec<String> = [
+        (count(DiagnosticSeverity::ERROR), "error"),
+        (count(DiagnosticSeverity::WARNING), "warning"),
+        (count(DiagnosticSeverity::INFORMATION), "info"),
+        (count(DiagnosticSeverity::HINT), "baselined"),
+    ]
+    .into_iter()
+    .filter(|&(n, _)| n > 0)
+    .map(|(n, name)| format!("{} {}", n, name))
+    .collect();
+    let total = severities.len();
+    Some(format!(
+        "cargo-perf: {} finding{} ({})",
+        total,
+        if total == 1 { "" } else { "s" },
+        parts.join(", ")
+    ))
+}
+
+/// Lower a diagnostic of a baselined finding to a faded hint.
+fn mark_baselined(diagnostic: &mut tower_lsp::lsp_types::Diagnostic) {
+    diagnostic.severity = Some(DiagnosticSeverity::HINT);
+    diagnostic.tags = Some(vec![DiagnosticTag::UNNECESSARY]);
+    diagnostic.message.push_str(" (baselined)");
+}
+
 /// Convert a cargo-perf diagnostic to an LSP diagnostic.
 fn perf_diag_to_lsp(diag: PerfDiagnostic) -> tower_lsp::lsp_types::Diagnostic {
     let severity = match diag.severity {
@@ -561,7 +1578,10 @@ fn perf_diag_to_lsp(diag: PerfDiagnostic) -> tower_lsp::lsp_types::Diagnostic {
         range,
         severity: Some(severity),
         code: Some(NumberOrString::String(diag.rule_id.to_string())),
-        code_description: None,
+        code_description: registry::get_rule(diag.rule_id)
+            .and_then(|rule| rule.doc_url())
+            .and_then(|url| Url::parse(&url).ok())
+            .map(|href| CodeDescription { href }),
         source: Some("cargo-perf".to_string()),
         message: diag.message,
         related_information: if related_info.is_empty() {
@@ -581,7 +1601,12 @@ pub async fn run_server() {
     let stdin = tokio::io::stdin();
     let stdout = tokio::io::stdout();
 
-    let (service, socket) = LspService::new(Backend::new);
+    let (service, socket) = LspService::build(Backend::new)
+        .custom_method(
+            "window/workDoneProgress/cancel",
+            Backend::work_done_progress_cancel,
+        )
+        .finish();
     Server::new(stdin, stdout, socket).serve(service).await;
 }
 
@@ -602,6 +1627,7 @@ mod tests {
             end_column: None,
             suggestion: None,
             fix: None,
+            target: None,
         };
 
         let lsp_diag = perf_diag_to_lsp(diag);
@@ -622,6 +1648,7 @@ mod tests {
             end_column: Some(20),
             suggestion: None,
             fix: None,
+            target: None,
         };
 
         let lsp_diag = perf_diag_to_lsp(diag);
@@ -631,6 +1658,148 @@ mod tests {
         assert_eq!(lsp_diag.range.end.character, 20);
     }
 
+    #[test]
+    fn test_server_options() {
+        let options: ServerOptions =
+            serde_json::from_value(serde_json::json!({ "debounceMs": 50 })).unwrap();
+        assert_eq!(options.debounce_ms, 50);
+        assert_eq!(options.baselined, BaselinedFindings::Hide);
+
+        let options: ServerOptions =
+            serde_json::from_value(serde_json::json!({ "baselined": "hint" })).unwrap();
+        assert_eq!(options.baselined, BaselinedFindings::Hint);
+
+        // Unknown and missing options fall back to defaults
+        let options: ServerOptions =
+            serde_json::from_value(serde_json::json!({ "other": true })).unwrap();
+        assert_eq!(options, ServerOptions::default());
+    }
+
+    #[test]
+    fn test_editor_settings() {
+        let settings = EditorSettings::from_value(serde_json::json!({
+            "cargo-perf": { "rules": { "clone-in-hot-loop": "allow" } },
+            "rust-analyzer": {}
+        }))
+        .unwrap();
+        assert_eq!(
+            settings.rules.get("clone-in-hot-loop"),
+            Some(&RuleSeverity::Allow)
+        );
+
+        // Bare settings and no settings at all are accepted too
+        let bare = EditorSettings::from_value(
+            serde_json::json!({ "rules": { "clone-in-hot-loop": "allow" } }),
+        )
+        .unwrap();
+        assert_eq!(bare, settings);
+        let none = EditorSettings::from_value(serde_json::Value::Null).unwrap();
+        assert_eq!(none, EditorSettings::default());
+        assert!(
+            EditorSettings::from_value(serde_json::json!({ "rules": { "x": "loud" } })).is_err()
+        );
+
+        // Editor settings win over cargo-perf.toml
+        let mut config: Config =
+            toml::from_str("[rules]\nclone-in-hot-loop = \"deny\"\nregex-in-loop = \"warn\"")
+                .unwrap();
+        settings.apply(&mut config);
+        assert_eq!(config.rules["clone-in-hot-loop"], RuleSeverity::Allow);
+        assert_eq!(config.rules["regex-in-loop"], RuleSeverity::Warn);
+    }
+
+    #[test]
+    fn test_is_watched_file() {
+        let root = Path::new("/project");
+        assert!(is_watched_file(
+            Path::new("/project/cargo-perf.toml"),
+            Some(root)
+        ));
+        assert!(is_watched_file(
+            Path::new("/project/.cargo-perf-baseline"),
+            Some(root)
+        ));
+        assert!(!is_watched_file(
+            Path::new("/project/sub/cargo-perf.toml"),
+            Some(root)
+        ));
+        assert!(!is_watched_file(
+            Path::new("/project/Cargo.toml"),
+            Some(root)
+        ));
+    }
+
+    #[test]
+    fn test_diagnostic_report_result_ids() {
+        let diag = perf_diag_to_lsp(PerfDiagnostic {
+            rule_id: "test-rule",
+            severity: PerfSeverity::Warning,
+            message: "Test".to_string(),
+            file_path: PathBuf::from("/test.rs"),
+            line: 3,
+            column: 1,
+            end_line: None,
+            end_column: None,
+            suggestion: None,
+            fix: None,
+            target: None,
+        });
+
+        let DocumentDiagnosticReportKind::Full(full) = diagnostic_report(vec![diag.clone()], None)
+        else {
+            panic!("first report must be full");
+        };
+        let result_id = full.result_id.unwrap();
+        assert_eq!(full.items.len(), 1);
+
+        // Same diagnostics, same ID: nothing is resent
+        assert!(matches!(
+            diagnostic_report(vec![diag], Some(&result_id)),
+            DocumentDiagnosticReportKind::Unchanged(u) if u.result_id == result_id
+        ));
+        // Findings that went away are reported as an empty full report
+        assert!(matches!(
+            diagnostic_report(Vec::new(), Some(&result_id)),
+            DocumentDiagnosticReportKind::Full(f) if f.items.is_empty()
+        ));
+    }
+
+    #[test]
+    fn test_range_contains() {
+        let range = Range::new(Position::new(2, 4), Position::new(3, 1));
+        assert!(range_contains(&range, Position::new(2, 4)));
+        assert!(range_contains(&range, Position::new(2, 80)));
+        assert!(range_contains(&range, Position::new(3, 1)));
+        assert!(!range_contains(&range, Position::new(2, 3)));
+        assert!(!range_contains(&range, Position::new(3, 2)));
+    }
+
+    #[test]
+    fn test_findings_summary() {
+        assert_eq!(findings_summary(&[]), None);
+        assert_eq!(
+            findings_summary(&[Some(DiagnosticSeverity::WARNING)]).as_deref(),
+            Some("cargo-perf: 1 finding (1 warning)")
+        );
+        let severities = [
+            Some(DiagnosticSeverity::WARNING),
+            Some(DiagnosticSeverity::ERROR),
+            Some(DiagnosticSeverity::WARNING),
+        ];
+        assert_eq!(
+            findings_summary(&severities).as_deref(),
+            Some("cargo-perf: 3 findings (1 error, 2 warning)")
+        );
+        assert_eq!(
+            findings_summary(&[
+                Some(DiagnosticSeverity::WARNING),
+                Some(DiagnosticSeverity::HINT)
+            ])
+            .as_deref(),
+            Some("cargo-perf: 2 findings (1 warning, 1 baselined)")
+        );
+    }
+
     #[test]
     fn test_ranges_overlap() {
         let range_a = Range {
@@ -0,0 +1,82 @@
+//! `$/progress` work-done reports for long-running analyses.
+
+use tower_lsp::lsp_types::notification::Progress as ProgressNotification;
+use tower_lsp::lsp_types::request::WorkDoneProgressCreate;
+use tower_lsp::lsp_types::{
+    NumberOrString, ProgressParams, ProgressParamsValue, ProgressToken, WorkDoneProgress,
+    WorkDoneProgressBegin, WorkDoneProgressCreateParams, WorkDoneProgressEnd,
+    WorkDoneProgressReport,
+};
+use tower_lsp::Client;
+
+/// An in-progress work-done report, shown by the client until ended.
+pub(crate) struct Progress {
+    client: Client,
+    pub token: ProgressToken,
+    percentage: Option<u32>,
+}
+
+impl Progress {
+    /// Ask the client to create a progress token named `name`. Returns `None`
+    /// if the client declines.
+    pub async fn create(client: &Client, name: String) -> Option<ProgressToken> {
+        let token = NumberOrString::String(name);
+        client
+            .send_request::<WorkDoneProgressCreate>(WorkDoneProgressCreateParams {
+                token: token.clone(),
+            })
+            .await
+            .ok()?;
+        Some(token)
+    }
+
+    /// Start a cancellable report under `token`.
+    pub async fn begin(client: &Client, token: ProgressToken, title: &str) -> Self {
+        let progress = Self {
+            client: client.clone(),
+            token,
+            percentage: None,
+        };
+        progress
+            .send(WorkDoneProgress::Begin(WorkDoneProgressBegin {
+                title: title.to_string(),
+                cancellable: Some(true),
+                message: None,
+                percentage: Some(0),
+            }))
+            .await;
+        progress
+    }
+
+    /// Report `done` of `total` files, skipping reports that would not move
+    /// the percentage.
+    pub async fn report(&mut self, done: usize, total: usize) {
+        let percentage = (done * 100 / total.max(1)) as u32;
+        if self.percentage == Some(percentage) {
+            return;
+        }
+        self.percentage = Some(percentage);
+        self.send(WorkDoneProgress::Report(WorkDoneProgressReport {
+            cancellable: Some(true),
+            message: Some(format!("{}/{} files", done, total)),
+            percentage: Some(percentage),
+        }))
+        .await;
+    }
+
+    pub async fn end(self, message: String) {
+        self.send(WorkDoneProgress::End(WorkDoneProgressEnd {
+            message: Some(message),
+        }))
+        .await;
+    }
+
+    async fn send(&self, progress: WorkDoneProgress) {
+        self.client
+            .send_notification::<ProgressNotification>(ProgressParams {
+                token: self.token.clone(),
+                value: ProgressParamsValue::WorkDone(progress),
+            })
+            .await;
+    }
+}
@@ -0,0 +1,160 @@
+//! Edits that suppress a diagnostic, in the forms `SuppressionExtractor` reads.
+//!
+//! Lines are 0-based, as in LSP positions.
+
+use tower_lsp::lsp_types::{Position, Range, TextEdit};
+
+use super::lens::FunctionSpan;
+use crate::explain::suppress_with;
+use crate::suppression::parse_rule_list;
+
+const IGNORE_COMMENT: &str = "// cargo-perf-ignore:";
+
+/// Suppress `rule_id` on `line` with a `// cargo-perf-ignore:` comment above
+/// it, indented like the line. An existing rule list on the line above is
+/// extended instead, ahead of any reason. With `require_reason`, a new
+/// comment ends in a `-- reason: ` for the user to fill in.
+pub(crate) fn line_suppression(
+    source: &str,
+    line: usize,
+    rule_id: &str,
+    require_reason: bool,
+) -> Option<TextEdit> {
+    let text = source.lines().nth(line)?;
+
+    if let Some(above) = line.checked_sub(1).and_then(|l| source.lines().nth(l)) {
+        if let Some(list) = above.trim_start().strip_prefix(IGNORE_COMMENT) {
+            let rules = parse_rule_list(list);
+            if rules.iter().any(|(_, rule)| *rule == rule_id) {
+                return None;
+            }
+            let all = rules.iter().any(|(_, rule)| *rule == "all");
+            if let (false, Some((last, _))) = (all, rules.last()) {
+                let list_start = above.len() - list.len();
+                let end =
+                    Position::new(line as u32 - 1, utf16_len(&above[..list_start + last.end]));
+                return Some(TextEdit {
+                    range: Range::new(end, end),
+                    new_text: format!(", {}", rule_id),
+                });
+            }
+        }
+    }
+
+    let [comment, _] = suppress_with(rule_id);
+    let comment = if require_reason {
+        format!("{} -- reason: ", comment)
+    } else {
+        comment
+    };
+    Some(insert_above(line, text, &comment))
+}
+
+/// Suppress `rule_id` in the innermost function of `functions` containing
+/// `line`, with an `#[allow(cargo_perf::...)]` attribute above its `fn` line.
+/// With `require_reason`, the attribute has an empty `reason` to fill in.
+pub(crate) fn function_suppression(
+    source: &str,
+    functions: &[FunctionSpan],
+    line: usize,
+    rule_id: &str,
+    require_reason: bool,
+) -> Option<TextEdit> {
+    // Spans are 1-based; nested functions come after their parent
+    let function = functions
+        .iter()
+        .filter(|f| (f.line..=f.end_line).contains(&(line + 1)))
+        .max_by_key(|f| f.line)?;
+    let fn_line = function.line - 1;
+    let text = source.lines().nth(fn_line)?;
+
+    let [_, attribute] = suppress_with(rule_id);
+    let attribute = if require_reason {
+        attribute.replace(")]", ", reason = \"\")]")
+    } else {
+        attribute
+    };
+    Some(insert_above(fn_line, text, &attribute))
+}
+
+fn insert_above(line: usize, text: &str, inserted: &str) -> TextEdit {
+    let indent = &text[..text.len() - text.trim_start().len()];
+    let start = Position::new(line as u32, 0);
+    TextEdit {
+        range: Range::new(start, start),
+        new_text: format!("{}{}\n", indent, inserted),
+    }
+}
+
+fn utf16_len(text: &str) -> u32 {
+    text.chars().map(|c| c.len_utf16() as u32).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lsp::lens;
+
+    const SOURCE: &str = "\
+impl Foo {
+    fn run(&self) {
+        for x in xs {
+            let y = x.clone();
+        }
+    }
+}
+";
+
+    #[test]
+    fn test_line_suppression() {
+        let edit = line_suppression(SOURCE, 3, "clone-in-hot-loop", false).unwrap();
+        assert_eq!(edit.range.start, Position::new(3, 0));
+        assert_eq!(
+            edit.new_text,
+            "            // cargo-perf-ignore: clone-in-hot-loop\n"
+        );
+
+        // An existing comment gains the rule instead of a second comment
+        let source = "fn f() {\n    // cargo-perf-ignore: a, b\n    g();\n}\n";
+        let edit = line_suppression(source, 2, "c", false).unwrap();
+        assert_eq!(edit.range.start, Position::new(1, 30));
+        assert_eq!(edit.new_text, ", c");
+        assert_eq!(line_suppression(source, 2, "b", false), None);
+
+        // Rules go before the reason, which does not count as a rule
+        let source = "fn f() {\n    // cargo-perf-ignore: a -- reason: b, c\n    g();\n}\n";
+        let edit = line_suppression(source, 2, "c", false).unwrap();
+        assert_eq!(edit.range.start, Position::new(1, 27));
+        assert_eq!(edit.new_text, ", c");
+        assert_eq!(line_suppression(source, 2, "a", false), None);
+
+        let edit = line_suppression(SOURCE, 3, "clone-in-hot-loop", true).unwrap();
+        assert_eq!(
+            edit.new_text,
+            "            // cargo-perf-ignore: clone-in-hot-loop -- reason: \n"
+        );
+    }
+
+    #[test]
+    fn test_function_suppression() {
+        let functions = lens::functions(SOURCE).unwrap();
+        let edit = function_suppression(SOURCE, &functions, 3, "clone-in-hot-loop", false).unwrap();
+        assert_eq!(edit.range.start, Position::new(1, 0));
+        assert_eq!(
+            edit.new_text,
+            "    #[allow(cargo_perf::clone_in_hot_loop)]\n"
+        );
+
+        let edit = function_suppression(SOURCE, &functions, 3, "clone-in-hot-loop", true).unwrap();
+        assert_eq!(
+            edit.new_text,
+            "    #[allow(cargo_perf::clone_in_hot_loop, reason = \"\")]\n"
+        );
+
+        // Outside any function
+        assert_eq!(
+            function_suppression(SOURCE, &functions, 0, "x", false),
+            None
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use anyhow::Result;
-use cargo_perf::{analyze, Config};
-use clap::{Parser, Subcommand};
+use cargo_perf::diff::{ChangedLines, DiffBase};
+use cargo_perf::rules::selection::RuleSelection;
+use cargo_perf::rules::{Category, Confidence};
+use cargo_perf::workspace::{PackageSelection, Workspace};
+use cargo_perf::{Config, Engine};
+use clap::{Parser, Subcommand, ValueEnum};
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::time::Instant;
@@ -14,12 +19,24 @@ struct Cli {
     command: Option<Commands>,
 
     /// Path to analyze (defaults to current directory)
-    #[arg(short, long, default_value = ".")]
+    #[arg(long, default_value = ".")]
     path: PathBuf,
 
-    /// Output format
-    #[arg(short, long, default_value = "console")]
-    format: OutputFormat,
+    /// Package(s) to analyze (repeatable); defaults to the packages under the path
+    #[arg(short = 'p', long = "package", global = true)]
+    packages: Vec<String>,
+
+    /// Analyze every package in the workspace
+    #[arg(long, global = true, conflicts_with = "packages")]
+    workspace: bool,
+
+    /// Output format [default: `output.format` in cargo-perf.toml, else console]
+    #[arg(short, long, global = true)]
+    format: Option<OutputFormat>,
+
+    /// When to color output [default: `output.color` in cargo-perf.toml, else auto]
+    #[arg(long, global = true)]
+    color: Option<ColorChoice>,
 
     /// Minimum severity to report
     #[arg(long, default_value = "info")]
@@ -29,17 +46,26 @@ struct Cli {
     #[arg(long)]
     fail_on: Option<cargo_perf::Severity>,
 
-    /// Specific rules to run (comma-separated)
-    #[arg(long)]
+    /// Rules to run: IDs, groups (async, database, memory, iterator,
+    /// allocation, suppression) or globs such as `*-no-capacity` (comma-separated)
+    #[arg(long, global = true)]
     rules: Option<String>,
 
-    /// Strict mode: only run high-confidence rules (async-block-in-async, lock-across-await)
+    /// Rules to skip, in the same form as --rules
+    #[arg(long, global = true)]
+    exclude_rules: Option<String>,
+
+    /// Strict mode: only run high-confidence rules (see `cargo perf rules`)
     #[arg(long)]
     strict: bool,
 
     /// Show timing information for performance debugging
     #[arg(long)]
     timing: bool,
+
+    /// Disable the incremental analysis cache (target/cargo-perf/)
+    #[arg(long, global = true)]
+    no_cache: bool,
 }
 
 #[derive(Subcommand)]
@@ -61,6 +87,19 @@ enum Commands {
         /// Use baseline file to filter known issues
         #[arg(long)]
         baseline: bool,
+
+        /// Fail if the baseline has stale entries for issues that were fixed,
+        /// so they are pruned and cannot come back unnoticed
+        #[arg(long, requires = "baseline", conflicts_with_all = ["changed_since", "staged"])]
+        ratchet: bool,
+
+        /// Only report issues on lines changed since this git revision
+        #[arg(long, value_name = "REV", conflicts_with = "staged")]
+        changed_since: Option<String>,
+
+        /// Only report issues on lines staged in git
+        #[arg(long)]
+        staged: bool,
     },
     /// Create or update baseline file with current diagnostics
     Baseline {
@@ -71,6 +110,36 @@ enum Commands {
         /// Update existing baseline instead of replacing
         #[arg(long)]
         update: bool,
+
+        /// Remove entries that no longer match any finding
+        #[arg(long)]
+        prune: bool,
+
+        /// Convert an existing baseline to structural fingerprints, keeping
+        /// only entries that still match a finding
+        #[arg(long, conflicts_with_all = ["update", "prune"])]
+        migrate: bool,
+
+        /// List the baseline's entries grouped by owner, without analyzing
+        #[arg(long, conflicts_with_all = ["update", "prune", "migrate"])]
+        report: bool,
+
+        /// Owner or team to record on the entries this run adds
+        #[arg(long, conflicts_with_all = ["migrate", "report"])]
+        owner: Option<String>,
+
+        /// Why the issues this run adds are accepted
+        #[arg(long, conflicts_with_all = ["migrate", "report"])]
+        justification: Option<String>,
+
+        /// Tracking ticket to record on the entries this run adds
+        #[arg(long, conflicts_with_all = ["migrate", "report"])]
+        ticket: Option<String>,
+
+        /// Last day (YYYY-MM-DD) the entries this run adds apply; afterwards
+        /// `check --baseline` reports their issues again
+        #[arg(long, value_name = "DATE", value_parser = parse_date, conflicts_with_all = ["migrate", "report"])]
+        expires: Option<String>,
     },
     /// Apply auto-fixes for detected issues
     Fix {
@@ -81,19 +150,31 @@ enum Commands {
         /// Only show what would be fixed without making changes
         #[arg(long)]
         dry_run: bool,
-
-        /// Specific rules to apply fixes for (comma-separated)
-        #[arg(long)]
-        rules: Option<String>,
     },
     /// Initialize cargo-perf.toml config
     Init,
     /// List available rules
     Rules,
-    /// Explain a specific rule in detail
+    /// Explain a rule in detail, or generate a reference for all rules
     Explain {
         /// Rule ID to explain (e.g., "regex-in-loop")
-        rule_id: String,
+        #[arg(required_unless_present = "all", conflicts_with = "all")]
+        rule_id: Option<String>,
+
+        /// Explain every rule, grouped by category
+        #[arg(long)]
+        all: bool,
+    },
+    /// Re-run analysis whenever a `.rs` file or cargo-perf.toml changes
+    #[cfg(feature = "watch")]
+    Watch {
+        /// Path to watch and analyze
+        #[arg(default_value = ".")]
+        path: PathBuf,
+
+        /// Strict mode: only run high-confidence rules
+        #[arg(long)]
+        strict: bool,
     },
     /// Start LSP server for IDE integration (requires 'lsp' feature)
     #[cfg(feature = "lsp")]
@@ -106,6 +187,16 @@ enum OutputFormat {
     Console,
     Json,
     Sarif,
+    /// Markdown (`explain` only)
+    Markdown,
+}
+
+#[derive(Clone, Copy, Default, clap::ValueEnum)]
+enum ColorChoice {
+    #[default]
+    Auto,
+    Always,
+    Never,
 }
 
 fn main() -> ExitCode {
@@ -130,44 +221,128 @@ fn run() -> Result<()> {
 
     let config = Config::load_or_default(&cli.path)?;
 
+    // CLI flags override the [output] settings in cargo-perf.toml
+    let format = match cli.format {
+        Some(format) => format,
+        None => config_setting("output.format", &config.output.format)?,
+    };
+    let color = match cli.color {
+        Some(color) => color,
+        None => config_setting("output.color", &config.output.color)?,
+    };
+    match color {
+        ColorChoice::Auto => {}
+        ColorChoice::Always => colored::control::set_override(true),
+        ColorChoice::Never => colored::control::set_override(false),
+    }
+
+    let mut rules = RuleSelection::all();
+    if let Some(spec) = &cli.rules {
+        rules = rules.include(spec)?;
+    }
+    if let Some(spec) = &cli.exclude_rules {
+        rules = rules.exclude(spec)?;
+    }
+    let selection = if cli.workspace {
+        PackageSelection::Workspace
+    } else if !cli.packages.is_empty() {
+        PackageSelection::Packages(cli.packages.clone())
+    } else {
+        PackageSelection::Default
+    };
+
     match cli.command {
         Some(Commands::Check {
             path,
             strict,
             timing,
             baseline,
+            ratchet,
+            changed_since,
+            staged,
         }) => run_check(CheckOptions {
             path: &path,
             config: &config,
-            format: cli.format,
+            selection: &selection,
+            rules: &rules,
+            format,
             min_severity: cli.min_severity,
             fail_on: cli.fail_on,
             strict: strict || cli.strict,
             show_timing: timing || cli.timing,
             use_baseline: baseline,
+            ratchet,
+            use_cache: !cli.no_cache,
+            diff_base: match changed_since {
+                Some(rev) => Some(DiffBase::Since(rev)),
+                None => staged.then_some(DiffBase::Staged),
+            },
         }),
         None => {
             // Default to check with cli.path
             run_check(CheckOptions {
                 path: &cli.path,
                 config: &config,
-                format: cli.format,
+                selection: &selection,
+                rules: &rules,
+                format,
                 min_severity: cli.min_severity,
                 fail_on: cli.fail_on,
                 strict: cli.strict,
                 show_timing: cli.timing,
                 use_baseline: false,
+                ratchet: false,
+                use_cache: !cli.no_cache,
+                diff_base: None,
             })
         }
-        Some(Commands::Baseline { path, update }) => run_baseline(&path, &config, update),
-        Some(Commands::Fix {
+        Some(Commands::Baseline {
             path,
-            dry_run,
-            rules,
-        }) => run_fix(&path, &config, dry_run, rules.as_deref()),
+            update,
+            prune,
+            migrate,
+            report,
+            owner,
+            justification,
+            ticket,
+            expires,
+        }) if !report => run_baseline(
+            &path,
+            &config,
+            &selection,
+            &rules,
+            BaselineMode {
+                update,
+                prune,
+                migrate,
+                metadata: cargo_perf::baseline::EntryMetadata {
+                    owner,
+                    justification,
+                    ticket,
+                    expires,
+                },
+            },
+        ),
+        Some(Commands::Baseline { path, .. }) => run_baseline_report(&path, format),
+        Some(Commands::Fix { path, dry_run }) => {
+            run_fix(&path, &config, &selection, &rules, dry_run)
+        }
         Some(Commands::Init) => run_init(&cli.path),
         Some(Commands::Rules) => run_list_rules(),
-        Some(Commands::Explain { rule_id }) => run_explain(&rule_id),
+        // `--all` conflicts with a rule ID, so no ID means all rules
+        Some(Commands::Explain { rule_id, .. }) => {
+            run_explain(rule_id.as_deref(), cli.format.unwrap_or_default())
+        }
+        #[cfg(feature = "watch")]
+        Some(Commands::Watch { path, strict }) => run_watch(WatchOptions {
+            path: &path,
+            config_dir: &cli.path,
+            selection: &selection,
+            rules: &rules,
+            min_severity: cli.min_severity,
+            strict: strict || cli.strict,
+            use_cache: !cli.no_cache,
+        }),
         #[cfg(feature = "lsp")]
         Some(Commands::Lsp) => run_lsp(),
     }
@@ -183,57 +358,265 @@ fn run_lsp() -> Result<()> {
     Ok(())
 }
 
-/// High-confidence rules for strict mode
-const STRICT_RULES: &[&str] = &["async-block-in-async", "lock-across-await"];
+/// Options for the watch command
+#[cfg(feature = "watch")]
+struct WatchOptions<'a> {
+    path: &'a Path,
+    /// Where cargo-perf.toml is loaded from, and reloaded when it changes
+    config_dir: &'a Path,
+    selection: &'a PackageSelection,
+    rules: &'a RuleSelection,
+    min_severity: cargo_perf::Severity,
+    strict: bool,
+    use_cache: bool,
+}
+
+/// What a batch of file system events touched.
+#[cfg(feature = "watch")]
+#[derive(PartialEq)]
+enum WatchChange {
+    /// Only Rust sources: the resident engine re-checks what they affect.
+    Sources,
+    /// cargo-perf.toml or a Cargo.toml: reload the config and workspace.
+    Project,
+}
+
+#[cfg(feature = "watch")]
+fn run_watch(opts: WatchOptions<'_>) -> Result<()> {
+    use notify::{RecursiveMode, Watcher};
+
+    let root = opts.path.canonicalize()?;
+    let config_dir = opts.config_dir.canonicalize()?;
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx)?;
+    watcher.watch(&root, RecursiveMode::Recursive)?;
+    if !config_dir.starts_with(&root) {
+        watcher.watch(&config_dir, RecursiveMode::NonRecursive)?;
+    }
+
+    let rules = if opts.strict {
+        opts.rules.clone().with_min_confidence(Confidence::High)
+    } else {
+        opts.rules.clone()
+    };
+    let interactive = std::io::IsTerminal::is_terminal(&std::io::stdout());
+    loop {
+        // A broken config edit is reported and waits for the next save
+        let config = match Config::load_or_default(&config_dir) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Error: {e}");
+                wait_for_change(&rx, &root)?;
+                continue;
+            }
+        };
+        let mut engine =
+            workspace_engine(&config, opts.path, opts.selection, &rules)?.with_resident_cache();
+        if let Some(dir) = opts
+            .use_cache
+            .then(|| cargo_perf::engine::cache::default_cache_dir(opts.path))
+            .flatten()
+        {
+            engine = engine.with_cache(dir);
+        }
+
+        loop {
+            let start = Instant::now();
+            let result = engine.analyze(opts.path);
+            if interactive {
+                // Clear the screen so each report replaces the last
+                print!("\x1B[2J\x1B[H");
+            }
+            match result {
+                Ok(diagnostics) => {
+                    let diagnostics: Vec<_> = diagnostics
+                        .into_iter()
+                        .filter(|d| d.severity >= opts.min_severity)
+                        .collect();
+                    cargo_perf::reporter::console::report_compact(&diagnostics);
+                }
+                Err(e) => eprintln!("Error: {e}"),
+            }
+            eprintln!(
+                "Analyzed in {:.2?}; watching {} for changes (Ctrl-C to stop)",
+                start.elapsed(),
+                opts.path.display()
+            );
+            if wait_for_change(&rx, &root)? == WatchChange::Project {
+                break;
+            }
+        }
+    }
+}
+
+/// Block until a relevant file changes, then collect the burst of events a
+/// save produces so it triggers one run.
+#[cfg(feature = "watch")]
+fn wait_for_change(
+    rx: &std::sync::mpsc::Receiver<notify::Result<notify::Event>>,
+    root: &Path,
+) -> Result<WatchChange> {
+    use std::sync::mpsc::RecvTimeoutError;
+    use std::time::Duration;
+
+    let mut change = None;
+    loop {
+        let event = match change {
+            None => rx.recv()?,
+            Some(_) => match rx.recv_timeout(Duration::from_millis(100)) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(e) => return Err(e.into()),
+            },
+        };
+        let event = match event {
+            Ok(event) if !event.kind.is_access() => event,
+            Ok(_) => continue,
+            Err(e) => {
+                eprintln!("Warning: {e}");
+                continue;
+            }
+        };
+        for path in &event.paths {
+            // Build output and hidden directories are never analyzed
+            let skipped = path
+                .strip_prefix(root)
+                .ok()
+                .and_then(Path::parent)
+                .is_some_and(|dir| {
+                    dir.components().any(|c| {
+                        let name = c.as_os_str().to_string_lossy();
+                        name == "target" || name.starts_with('.')
+                    })
+                });
+            if skipped {
+                continue;
+            }
+            let name = path.file_name().unwrap_or_default();
+            if name == "cargo-perf.toml" || name == "Cargo.toml" {
+                change = Some(WatchChange::Project);
+            } else if path.extension().is_some_and(|ext| ext == "rs") {
+                change = change.or(Some(WatchChange::Sources));
+            }
+        }
+    }
+    Ok(change.unwrap_or(WatchChange::Sources))
+}
 
 /// Options for the check command
 struct CheckOptions<'a> {
     path: &'a Path,
     config: &'a Config,
+    selection: &'a PackageSelection,
+    rules: &'a RuleSelection,
     format: OutputFormat,
     min_severity: cargo_perf::Severity,
     fail_on: Option<cargo_perf::Severity>,
     strict: bool,
     show_timing: bool,
     use_baseline: bool,
+    /// Fail on stale baseline entries
+    ratchet: bool,
+    use_cache: bool,
+    /// Report only lines changed relative to this
+    diff_base: Option<DiffBase>,
 }
 
 fn run_check(opts: CheckOptions<'_>) -> Result<()> {
     use cargo_perf::Baseline;
 
+    if matches!(opts.format, OutputFormat::Markdown) {
+        anyhow::bail!("markdown output is only supported by `explain`");
+    }
+
     let start = Instant::now();
-    let diagnostics = analyze(opts.path, opts.config)?;
+    // Strict mode only runs high-confidence rules
+    let rules = if opts.strict {
+        opts.rules.clone().with_min_confidence(Confidence::High)
+    } else {
+        opts.rules.clone()
+    };
+    let mut engine = workspace_engine(opts.config, opts.path, opts.selection, &rules)?;
+    if let Some(dir) = opts
+        .use_cache
+        .then(|| cargo_perf::engine::cache::default_cache_dir(opts.path))
+        .flatten()
+    {
+        engine = engine.with_cache(dir);
+    }
+    let changed_files = match &opts.diff_base {
+        Some(base) => {
+            let changed = ChangedLines::from_git(opts.path, base)?;
+            let count = changed.len();
+            engine = engine.with_changed_lines(changed);
+            Some(count)
+        }
+        None => None,
+    };
+    // Progress reports arrive from several threads; keep the highest counts.
+    let file_counts = std::sync::Mutex::new((0, 0));
+    let diagnostics = engine.analyze_with_progress(opts.path, |progress| {
+        if let Ok(mut counts) = file_counts.lock() {
+            counts.0 = counts.0.max(progress.total_files);
+            counts.1 = counts.1.max(progress.files_cached);
+        }
+    })?;
     let analysis_time = start.elapsed();
-
-    // Filter by minimum severity and strict mode
-    let mut diagnostics: Vec<_> = diagnostics
-        .into_iter()
-        .filter(|d| d.severity >= opts.min_severity)
-        .filter(|d| !opts.strict || STRICT_RULES.contains(&d.rule_id))
-        .collect();
-
-    // Filter by baseline if requested
-    let baseline_count = if opts.use_baseline {
+    let (total_files, cached_files) = file_counts.into_inner().unwrap_or_default();
+
+    // Filter by baseline if requested, before the severity filter so that
+    // entries of lower-severity findings do not look stale
+    let mut baseline_count = 0;
+    let mut stale = Vec::new();
+    let mut expired = Vec::new();
+    let diagnostics = if opts.use_baseline {
         match Baseline::load(opts.path) {
             Ok(baseline) => {
-                let before = diagnostics.len();
-                diagnostics = baseline.filter(diagnostics, opts.path);
-                before - diagnostics.len()
+                if baseline.needs_migration() {
+                    eprintln!(
+                        "Note: The baseline uses line fingerprints, which break when code is \
+                         reformatted. Run `cargo perf baseline --migrate` to upgrade it."
+                    );
+                }
+                let partition = baseline.partition(diagnostics, opts.path);
+                expired = partition.expired.into_iter().cloned().collect();
+                baseline_count = partition
+                    .baselined
+                    .iter()
+                    .filter(|d| d.severity >= opts.min_severity)
+                    .count();
+                // Narrowed to changed lines, nearly every entry would look stale
+                if opts.diff_base.is_none() {
+                    let scope = StaleScope::new(&engine, opts.path, &rules);
+                    stale = partition
+                        .stale
+                        .into_iter()
+                        .filter(|entry| scope.contains(entry))
+                        .cloned()
+                        .collect();
+                }
+                partition.new
             }
             Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
                 eprintln!(
                     "Warning: No baseline file found. Run `cargo perf baseline` to create one."
                 );
-                0
+                diagnostics
             }
             Err(e) => {
                 anyhow::bail!("Failed to load baseline: {}", e);
             }
         }
     } else {
-        0
+        diagnostics
     };
 
+    // Filter by minimum severity
+    let diagnostics: Vec<_> = diagnostics
+        .into_iter()
+        .filter(|d| d.severity >= opts.min_severity)
+        .collect();
+
     // Report
     match opts.format {
         OutputFormat::Console => {
@@ -245,6 +628,14 @@ fn run_check(opts: CheckOptions<'_>) -> Result<()> {
         OutputFormat::Sarif => {
             cargo_perf::reporter::sarif::report(&diagnostics)?;
         }
+        OutputFormat::Markdown => unreachable!("rejected before analysis"),
+    }
+
+    if !expired.is_empty() {
+        report_expired(&expired);
+    }
+    if !stale.is_empty() {
+        report_stale(&stale);
     }
 
     // Show timing information
@@ -253,12 +644,28 @@ fn run_check(opts: CheckOptions<'_>) -> Result<()> {
         eprintln!();
         eprintln!("{}", "Timing:".bold());
         eprintln!("  Analysis time: {:?}", analysis_time);
+        eprintln!(
+            "  Files:         {} ({} from cache)",
+            total_files, cached_files
+        );
+        if let Some(count) = changed_files {
+            eprintln!("  Changed files: {}", count);
+        }
         eprintln!("  Diagnostics:   {}", diagnostics.len());
         if opts.use_baseline && baseline_count > 0 {
             eprintln!("  Baselined:     {} (filtered)", baseline_count);
         }
     }
 
+    if opts.ratchet && !stale.is_empty() {
+        anyhow::bail!(
+            "Baseline has {} stale {} for fixed issues; run `cargo perf baseline --prune` to \
+             lock in the fixes",
+            stale.len(),
+            if stale.len() == 1 { "entry" } else { "entries" }
+        );
+    }
+
     // Check fail condition
     if let Some(fail_severity) = opts.fail_on {
         if diagnostics.iter().any(|d| d.severity >= fail_severity) {
@@ -276,54 +683,384 @@ fn run_check(opts: CheckOptions<'_>) -> Result<()> {
     Ok(())
 }
 
-fn run_baseline(path: &Path, config: &Config, update: bool) -> Result<()> {
+/// Parse a cargo-perf.toml setting into the value type of its CLI flag.
+fn config_setting<T: ValueEnum>(key: &str, value: &str) -> Result<T> {
+    T::from_str(value, true).map_err(|_| {
+        let expected: Vec<String> = T::value_variants()
+            .iter()
+            .filter_map(|v| v.to_possible_value())
+            .map(|v| v.get_name().to_string())
+            .collect();
+        anyhow::anyhow!(
+            "Invalid `{}` in cargo-perf.toml: \"{}\" (expected one of: {})",
+            key,
+            value,
+            expected.join(", ")
+        )
+    })
+}
+
+/// Engine for `path`: inside a Cargo project, analyze the build targets of the
+/// selected workspace packages instead of every `.rs` file under the path.
+fn workspace_engine<'a>(
+    config: &'a Config,
+    path: &Path,
+    selection: &PackageSelection,
+    rules: &RuleSelection,
+) -> Result<Engine<'a>> {
+    let engine = Engine::new(config).with_rules(rules.clone());
+    // A single file is analyzed as given unless packages were named
+    if *selection == PackageSelection::Default && !path.is_dir() {
+        return Ok(engine);
+    }
+    let Some(workspace) = Workspace::load(path) else {
+        if *selection != PackageSelection::Default {
+            anyhow::bail!(
+                "`--package` and `--workspace` require a Cargo project at {}",
+                path.display()
+            );
+        }
+        return Ok(engine);
+    };
+    let packages: Vec<_> = workspace
+        .select(selection, path)?
+        .into_iter()
+        .cloned()
+        .collect();
+    if packages.is_empty() {
+        // A directory inside the workspace but in no package
+        return Ok(engine);
+    }
+    Ok(engine.with_packages(packages))
+}
+
+/// Stale baseline entries listed before the rest are only counted
+const MAX_STALE_LISTED: usize = 20;
+
+fn report_stale(stale: &[cargo_perf::baseline::BaselineEntry]) {
+    use colored::Colorize;
+
+    eprintln!();
+    eprintln!(
+        "{} {} baseline {} no longer {} any finding:",
+        "Stale:".yellow().bold(),
+        stale.len(),
+        if stale.len() == 1 { "entry" } else { "entries" },
+        if stale.len() == 1 { "matches" } else { "match" }
+    );
+    for entry in stale.iter().take(MAX_STALE_LISTED) {
+        eprintln!("  - {}", entry.description);
+    }
+    if stale.len() > MAX_STALE_LISTED {
+        eprintln!("  ... and {} more", stale.len() - MAX_STALE_LISTED);
+    }
+    eprintln!("Run `cargo perf baseline --prune` to remove them.");
+}
+
+fn report_expired(expired: &[cargo_perf::baseline::BaselineEntry]) {
+    use colored::Colorize;
+
+    eprintln!();
+    eprintln!(
+        "{} {} baseline {} expired; {} reported again:",
+        "Expired:".yellow().bold(),
+        expired.len(),
+        if expired.len() == 1 {
+            "entry has"
+        } else {
+            "entries have"
+        },
+        if expired.len() == 1 {
+            "its finding is"
+        } else {
+            "their findings are"
+        }
+    );
+    for entry in expired.iter().take(MAX_STALE_LISTED) {
+        eprintln!(
+            "  - {} (expired {}{})",
+            entry.description,
+            entry.expires.as_deref().unwrap_or_default(),
+            entry
+                .owner
+                .as_deref()
+                .map(|owner| format!(", owner {owner}"))
+                .unwrap_or_default()
+        );
+    }
+    if expired.len() > MAX_STALE_LISTED {
+        eprintln!("  ... and {} more", expired.len() - MAX_STALE_LISTED);
+    }
+    eprintln!("Fix them, or extend `expires` in the baseline to accept them for longer.");
+}
+
+/// The stale baseline entries a run can vouch for: those whose rule ran and
+/// whose file was analyzed or no longer exists. Other entries may only lie
+/// outside the selected rules or packages.
+struct StaleScope<'a> {
+    root: &'a Path,
+    rules: &'a RuleSelection,
+    files: HashSet<PathBuf>,
+}
+
+impl<'a> StaleScope<'a> {
+    fn new(engine: &Engine<'_>, root: &'a Path, rules: &'a RuleSelection) -> Self {
+        Self {
+            root,
+            rules,
+            files: engine.files(root).into_iter().collect(),
+        }
+    }
+
+    fn contains(&self, entry: &cargo_perf::baseline::BaselineEntry) -> bool {
+        use cargo_perf::rules::registry;
+
+        // Entries of rules that no longer exist are stale too
+        let rule_ran = registry::get_rule(&entry.fingerprint.rule_id)
+            .is_none_or(|rule| self.rules.is_selected(rule));
+        let file = self.root.join(&entry.fingerprint.file_path);
+        rule_ran && (self.files.contains(&file) || !file.exists())
+    }
+}
+
+/// What `cargo perf baseline` does with an existing baseline.
+struct BaselineMode {
+    /// Add new findings to it instead of replacing it
+    update: bool,
+    /// Remove its entries that no longer match a finding
+    prune: bool,
+    /// Only convert it to structural fingerprints
+    migrate: bool,
+    /// Owner, justification, ticket and expiry date for added entries
+    metadata: cargo_perf::baseline::EntryMetadata,
+}
+
+fn run_baseline(
+    path: &Path,
+    config: &Config,
+    selection: &PackageSelection,
+    rules: &RuleSelection,
+    mode: BaselineMode,
+) -> Result<()> {
     use cargo_perf::baseline::BASELINE_FILENAME;
     use cargo_perf::Baseline;
     use colored::Colorize;
 
     // Run analysis
-    let diagnostics = analyze(path, config)?;
+    let engine = workspace_engine(config, path, selection, rules)?;
+    let diagnostics = engine.analyze(path)?;
+
+    if mode.migrate {
+        let baseline = match Baseline::load(path) {
+            Ok(b) => b,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                anyhow::bail!("No baseline file found. Run `cargo perf baseline` to create one.");
+            }
+            Err(e) => anyhow::bail!("Failed to load baseline: {}", e),
+        };
+        if !baseline.needs_migration() {
+            println!("Baseline is already up to date.");
+            return Ok(());
+        }
 
-    if diagnostics.is_empty() {
+        let before = baseline.len();
+        let scope = StaleScope::new(&engine, path, rules);
+        let baseline = baseline
+            .migrate(&diagnostics, path, |entry| scope.contains(entry))
+            .map_err(|e| {
+                anyhow::anyhow!(
+                    "Cannot migrate the baseline: {}. Run `cargo perf baseline --migrate` over \
+                     the whole workspace with all rules.",
+                    e
+                )
+            })?;
+        baseline.save(path)?;
+
+        println!("{}", "Baseline migrated:".green().bold());
+        println!("  File:    {}", path.join(BASELINE_FILENAME).display());
+        println!("  Total:   {} entries", baseline.len());
+        println!(
+            "  Dropped: {} entries matching no current finding",
+            before - baseline.len()
+        );
+        return Ok(());
+    }
+
+    // Pruning an existing baseline may leave it empty
+    if diagnostics.is_empty() && !mode.prune {
         println!("No diagnostics to baseline.");
         return Ok(());
     }
 
-    // Load existing baseline if updating
-    let mut baseline = if update {
+    // Load existing baseline if updating or pruning
+    let mut baseline = if mode.update || mode.prune {
         match Baseline::load(path) {
+            // Entries this run cannot match would be lost in the conversion
+            Ok(b) if b.needs_migration() => {
+                anyhow::bail!(
+                    "The baseline uses old fingerprints. Run `cargo perf baseline --migrate` first."
+                );
+            }
             Ok(b) => {
                 println!("Updating existing baseline with {} entries...", b.len());
                 b
             }
+            Err(_) if !mode.update => {
+                anyhow::bail!("No baseline file found. Run `cargo perf baseline` to create one.");
+            }
             Err(_) => Baseline::new(),
         }
     } else {
         Baseline::new()
     };
 
-    // Add diagnostics
+    // Remove entries of fixed issues
+    let removed = if mode.prune {
+        let scope = StaleScope::new(&engine, path, rules);
+        let stale: HashSet<cargo_perf::baseline::Fingerprint> = baseline
+            .partition(diagnostics.clone(), path)
+            .stale
+            .into_iter()
+            .filter(|entry| scope.contains(entry))
+            .map(|entry| entry.fingerprint.clone())
+            .collect();
+        baseline.retain(|entry| !stale.contains(&entry.fingerprint))
+    } else {
+        0
+    };
+
+    // Add diagnostics, unless only pruning
     let before = baseline.len();
-    for diag in &diagnostics {
-        baseline.add(diag, path);
+    if mode.update || !mode.prune {
+        baseline.add_all(&diagnostics, path);
     }
     let added = baseline.len() - before;
+    baseline.annotate_from(before, &mode.metadata);
 
     // Save
     baseline.save(path)?;
 
-    println!("{}", "Baseline created:".green().bold());
+    let title = if mode.prune && !mode.update {
+        "Baseline pruned:"
+    } else {
+        "Baseline created:"
+    };
+    println!("{}", title.green().bold());
     println!("  File:    {}", path.join(BASELINE_FILENAME).display());
     println!("  Total:   {} entries", baseline.len());
-    if update {
+    if mode.update {
         println!("  Added:   {} new entries", added);
     }
+    if mode.prune {
+        println!("  Removed: {} stale entries", removed);
+    }
     println!();
     println!("Use `cargo perf check --baseline` to filter these issues.");
 
     Ok(())
 }
 
+/// Parse a `--expires` date.
+fn parse_date(value: &str) -> Result<String, String> {
+    if cargo_perf::baseline::is_valid_date(value) {
+        Ok(value.to_string())
+    } else {
+        Err("expected a date in YYYY-MM-DD form".to_string())
+    }
+}
+
+/// Entries of one owner in the JSON baseline report
+#[derive(serde::Serialize)]
+struct OwnerDebt<'a> {
+    owner: Option<&'a str>,
+    entries: Vec<&'a cargo_perf::baseline::BaselineEntry>,
+}
+
+fn run_baseline_report(path: &Path, format: OutputFormat) -> Result<()> {
+    use cargo_perf::Baseline;
+    use colored::Colorize;
+
+    let baseline = match Baseline::load(path) {
+        Ok(b) => b,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            anyhow::bail!("No baseline file found. Run `cargo perf baseline` to create one.");
+        }
+        Err(e) => anyhow::bail!("Failed to load baseline: {}", e),
+    };
+    let groups = baseline.by_owner();
+
+    match format {
+        OutputFormat::Console => {}
+        OutputFormat::Json => {
+            let owners: Vec<_> = groups
+                .into_iter()
+                .map(|(owner, entries)| OwnerDebt { owner, entries })
+                .collect();
+            println!("{}", serde_json::to_string_pretty(&owners)?);
+            return Ok(());
+        }
+        OutputFormat::Sarif | OutputFormat::Markdown => {
+            anyhow::bail!("`baseline --report` supports console and JSON output");
+        }
+    }
+
+    if baseline.is_empty() {
+        println!("The baseline is empty.");
+        return Ok(());
+    }
+    for (owner, entries) in &groups {
+        let expired = entries.iter().filter(|e| e.is_expired()).count();
+        println!(
+            "{} ({} {}{})",
+            owner.unwrap_or("(no owner)").bold(),
+            entries.len(),
+            if entries.len() == 1 {
+                "entry"
+            } else {
+                "entries"
+            },
+            if expired > 0 {
+                format!(", {} expired", expired)
+            } else {
+                String::new()
+            }
+        );
+        for entry in entries {
+            println!("  - {}", entry.description);
+            let mut details = Vec::new();
+            if let Some(ticket) = &entry.ticket {
+                details.push(format!("ticket {ticket}"));
+            }
+            match &entry.expires {
+                Some(date) if entry.is_expired() => {
+                    details.push(format!("{} {date}", "expired".red()))
+                }
+                Some(date) => details.push(format!("expires {date}")),
+                None => {}
+            }
+            if let Some(justification) = &entry.justification {
+                details.push(justification.clone());
+            }
+            if !details.is_empty() {
+                println!("      {}", details.join("; "));
+            }
+        }
+        println!();
+    }
+    println!(
+        "{} entries, {} without an owner, {} without an expiry date",
+        baseline.len(),
+        groups.get(&None).map_or(0, Vec::len),
+        baseline
+            .entries
+            .iter()
+            .filter(|e| e.expires.is_none())
+            .count()
+    );
+
+    Ok(())
+}
+
 fn run_init(path: &Path) -> Result<()> {
     let config_path = path.join("cargo-perf.toml");
     if config_path.exists() {
@@ -354,350 +1091,91 @@ url = "https://raw.githubusercontent.com/cschuman/cargo-perf/main/cargo-perf.sch
 
 fn run_list_rules() -> Result<()> {
     use cargo_perf::rules::registry;
+    use colored::Colorize;
 
-    println!("Available rules:\n");
-    for rule in registry::all_rules() {
-        println!(
-            "  {:<30} [{:?}] {}",
-            rule.id(),
-            rule.default_severity(),
-            rule.description()
-        );
+    println!("Available rules:");
+    for category in Category::ALL {
+        let rules: Vec<_> = registry::all_rules()
+            .iter()
+            .filter(|r| r.category() == *category)
+            .collect();
+        if rules.is_empty() {
+            continue;
+        }
+        println!("\n{}", category.as_str().bold());
+        for rule in rules {
+            println!(
+                "  {:<30} [{:?}] {:<6} {}",
+                rule.id(),
+                rule.default_severity(),
+                rule.confidence(),
+                rule.description()
+            );
+        }
     }
+    println!("\nCategory names work as groups in --rules / --exclude-rules.");
+    println!("--strict runs only high-confidence rules.");
     println!("\nUse `cargo perf explain <rule-id>` for detailed information.");
     Ok(())
 }
 
-fn run_explain(rule_id: &str) -> Result<()> {
+fn run_explain(rule_id: Option<&str>, format: OutputFormat) -> Result<()> {
+    use cargo_perf::explain::{self, RuleDoc};
     use cargo_perf::rules::registry;
+    use cargo_perf::Rule;
     use colored::Colorize;
 
-    // Find the rule
-    let rule = registry::all_rules().iter().find(|r| r.id() == rule_id);
-
-    let rule = match rule {
-        Some(r) => r,
-        None => {
-            eprintln!("{} Unknown rule: {}", "error:".red().bold(), rule_id);
-            eprintln!("\nAvailable rules:");
-            for r in registry::all_rules() {
-                eprintln!("  {}", r.id());
+    let rules: Vec<&dyn Rule> = match rule_id {
+        None => registry::all_rules().iter().map(|r| r.as_ref()).collect(),
+        Some(rule_id) => match registry::get_rule(rule_id) {
+            Some(rule) => vec![rule],
+            None => {
+                eprintln!("{} Unknown rule: {}", "error:".red().bold(), rule_id);
+                eprintln!("\nAvailable rules:");
+                for r in registry::all_rules() {
+                    eprintln!("  {}", r.id());
+                }
+                anyhow::bail!("Unknown rule: {}", rule_id);
             }
-            anyhow::bail!("Unknown rule: {}", rule_id);
-        }
+        },
     };
 
-    // Print header
-    println!("{}", rule.name().bold().underline());
-    println!("Rule ID: {}", rule.id().cyan());
-    println!("Severity: {:?}", rule.default_severity());
-    println!();
-    println!("{}", rule.description());
-    println!();
-
-    // Print detailed explanation based on rule ID
-    print_rule_explanation(rule.id());
-
-    Ok(())
-}
-
-fn print_rule_explanation(rule_id: &str) {
-    use colored::Colorize;
-
-    match rule_id {
-        "async-block-in-async" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Blocking calls in async functions block the entire async runtime thread.");
-            println!("  This can cause all other async tasks to stall, destroying concurrency.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  async fn fetch_data() {{");
-            println!("      let data = std::fs::read_to_string(\"file.txt\"); // BLOCKS!");
-            println!("      std::thread::sleep(Duration::from_secs(1));      // BLOCKS!");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  async fn fetch_data() {{");
-            println!("      let data = tokio::fs::read_to_string(\"file.txt\").await;");
-            println!("      tokio::time::sleep(Duration::from_secs(1)).await;");
-            println!("  }}");
-            println!();
-            println!("{}", "Performance impact:".yellow().bold());
-            println!("  Can reduce async throughput by 10-100x depending on workload.");
-        }
-
-        "lock-across-await" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Holding a MutexGuard across an .await point can cause deadlocks.");
-            println!("  The task may be suspended while holding the lock, blocking other tasks.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  async fn update() {{");
-            println!("      let guard = mutex.lock().unwrap();");
-            println!("      do_async_work().await;  // DEADLOCK RISK!");
-            println!("      *guard = new_value;");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  async fn update() {{");
-            println!("      {{");
-            println!("          let guard = mutex.lock().unwrap();");
-            println!("          *guard = new_value;");
-            println!("      }} // guard dropped before await");
-            println!("      do_async_work().await;");
-            println!("  }}");
-            println!();
-            println!("{}", "Performance impact:".yellow().bold());
-            println!("  Can cause complete system hangs in production.");
-        }
-
-        "n-plus-one-query" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Executing database queries inside loops causes N+1 query problems.");
-            println!("  For N items, you execute N+1 queries instead of 1-2 batch queries.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  for user_id in user_ids {{");
-            println!(
-                "      let user = sqlx::query!(\"SELECT * FROM users WHERE id = ?\", user_id)"
-            );
-            println!("          .fetch_one(&pool).await?;");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!(
-                "  let users = sqlx::query!(\"SELECT * FROM users WHERE id IN (?))\", &user_ids)"
-            );
-            println!("      .fetch_all(&pool).await?;");
-            println!();
-            println!("{}", "Performance impact:".yellow().bold());
-            println!("  100 items = 101 queries vs 1 query. Can be 50-100x slower.");
-        }
-
-        "regex-in-loop" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Regex::new() compiles the regex pattern on every call.");
-            println!("  Compilation is expensive and should be done once.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  for line in lines {{");
-            println!("      let re = Regex::new(r\"\\d+\").unwrap();");
-            println!("      if re.is_match(line) {{ ... }}");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  static RE: LazyLock<Regex> = LazyLock::new(|| {{");
-            println!("      Regex::new(r\"\\d+\").unwrap()");
-            println!("  }});");
-            println!("  for line in lines {{");
-            println!("      if RE.is_match(line) {{ ... }}");
-            println!("  }}");
-            println!();
-            println!("{}", "Performance impact:".yellow().bold());
-            println!(
-                "  Benchmark: {} faster with pre-compiled regex.",
-                "737x".green().bold()
-            );
+    match format {
+        OutputFormat::Console => {
+            let sections: Vec<String> = rules.iter().map(|rule| explain::console(*rule)).collect();
+            print!("{}", sections.join("\n"));
         }
-
-        "clone-in-hot-loop" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Cloning heap-allocated types (String, Vec, etc.) in loops");
-            println!("  causes repeated memory allocations and copies.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  for item in items {{");
-            println!("      let owned = expensive_string.clone();");
-            println!("      process(owned);");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  for item in items {{");
-            println!("      process(&expensive_string);  // borrow instead");
-            println!("  }}");
-            println!("  // Or clone once before the loop if ownership needed");
-            println!();
-            println!("{}", "Performance impact:".yellow().bold());
-            println!(
-                "  Benchmark: {} faster avoiding clone in loop.",
-                "48x".green().bold()
-            );
+        OutputFormat::Markdown if rule_id.is_none() => {
+            print!("{}", explain::markdown_reference(&rules));
         }
-
-        "collect-then-iterate" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Calling .collect() followed by .iter() creates an unnecessary");
-            println!("  intermediate collection. Continue the iterator chain instead.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  items.iter()");
-            println!("      .filter(|x| x.is_valid())");
-            println!("      .collect::<Vec<_>>()");
-            println!("      .iter()  // unnecessary!");
-            println!("      .map(|x| x.process())");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  items.iter()");
-            println!("      .filter(|x| x.is_valid())");
-            println!("      .map(|x| x.process())");
-            println!();
-            println!("{}", "Performance impact:".yellow().bold());
-            println!(
-                "  Benchmark: {} faster without intermediate collection.",
-                "2.3x".green().bold()
-            );
-            println!();
-            println!("{}", "Auto-fix available:".cyan().bold());
-            println!("  This rule supports automatic fixing via `cargo perf fix`.");
-        }
-
-        "vec-no-capacity" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Vec::new() starts with zero capacity. Each push beyond capacity");
-            println!("  triggers reallocation. Pre-allocating avoids repeated allocations.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  let mut results = Vec::new();");
-            println!("  for i in 0..1000 {{");
-            println!("      results.push(compute(i));");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  let mut results = Vec::with_capacity(1000);");
-            println!("  for i in 0..1000 {{");
-            println!("      results.push(compute(i));");
-            println!("  }}");
-            println!();
-            println!("{}", "Performance impact:".yellow().bold());
-            println!(
-                "  Benchmark: {} faster with pre-allocated capacity.",
-                "1.8x".green().bold()
-            );
+        OutputFormat::Markdown => print!("{}", explain::markdown(rules[0])),
+        OutputFormat::Json => {
+            let docs: Vec<RuleDoc> = rules.iter().map(|rule| RuleDoc::new(*rule)).collect();
+            let json = match rule_id {
+                Some(_) => serde_json::to_string_pretty(&docs[0])?,
+                None => serde_json::to_string_pretty(&docs)?,
+            };
+            println!("{}", json);
         }
-
-        "format-in-loop" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  format!() allocates a new String on every call.");
-            println!("  In loops, this causes repeated heap allocations.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  for item in items {{");
-            println!("      let msg = format!(\"Processing: {{}}\", item);");
-            println!("      log(msg);");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  let mut buf = String::new();");
-            println!("  for item in items {{");
-            println!("      buf.clear();");
-            println!("      write!(&mut buf, \"Processing: {{}}\", item)?;");
-            println!("      log(&buf);");
-            println!("  }}");
-        }
-
-        "string-concat-loop" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  The + operator on Strings allocates a new String each time.");
-            println!("  Use push_str() to append in place without allocation.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  let mut result = String::new();");
-            println!("  for word in words {{");
-            println!("      result = result + word;  // allocates each time!");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  let mut result = String::new();");
-            println!("  for word in words {{");
-            println!("      result.push_str(word);  // appends in place");
-            println!("  }}");
-            println!();
-            println!("{}", "Auto-fix available:".cyan().bold());
-            println!("  This rule supports automatic fixing via `cargo perf fix`.");
-        }
-
-        "mutex-in-loop" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Acquiring a lock inside a loop causes repeated lock/unlock overhead.");
-            println!("  Acquire once before the loop when possible.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  for item in items {{");
-            println!("      let mut guard = data.lock().unwrap();");
-            println!("      guard.push(item);");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  let mut guard = data.lock().unwrap();");
-            println!("  for item in items {{");
-            println!("      guard.push(item);");
-            println!("  }}");
-        }
-
-        "unbounded-channel" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Unbounded channels can grow without limit, exhausting memory");
-            println!("  if producers outpace consumers.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  let (tx, rx) = std::sync::mpsc::channel();  // unbounded!");
-            println!("  let (tx, rx) = tokio::sync::mpsc::unbounded_channel();");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  let (tx, rx) = std::sync::mpsc::sync_channel(100);  // bounded");
-            println!("  let (tx, rx) = tokio::sync::mpsc::channel(100);");
-            println!();
-            println!("{}", "Performance impact:".yellow().bold());
-            println!("  Prevents OOM crashes under load.");
-        }
-
-        "unbounded-spawn" => {
-            println!("{}", "Why it matters:".yellow().bold());
-            println!("  Spawning tasks in a loop without limits can exhaust memory");
-            println!("  and overwhelm the runtime with too many concurrent tasks.");
-            println!();
-            println!("{}", "Bad:".red().bold());
-            println!("  for url in urls {{");
-            println!("      tokio::spawn(fetch(url));  // thousands of concurrent tasks!");
-            println!("  }}");
-            println!();
-            println!("{}", "Good:".green().bold());
-            println!("  use futures::stream::StreamExt;");
-            println!("  futures::stream::iter(urls)");
-            println!("      .map(|url| fetch(url))");
-            println!("      .buffer_unordered(10)  // max 10 concurrent");
-            println!("      .collect::<Vec<_>>().await;");
-        }
-
-        _ => {
-            println!("No detailed explanation available for this rule.");
-            println!("Run `cargo perf rules` to see all available rules.");
+        OutputFormat::Sarif => {
+            anyhow::bail!("`explain` supports console, json and markdown output")
         }
     }
-
-    println!();
-    println!("{}", "Suppression:".yellow().bold());
-    println!("  // cargo-perf-ignore: {}", rule_id);
-    println!("  #[allow(cargo_perf::{})]", rule_id);
+    Ok(())
 }
 
-fn run_fix(path: &Path, config: &Config, dry_run: bool, rules_filter: Option<&str>) -> Result<()> {
+fn run_fix(
+    path: &Path,
+    config: &Config,
+    selection: &PackageSelection,
+    rules: &RuleSelection,
+    dry_run: bool,
+) -> Result<()> {
     use cargo_perf::fix::apply_fixes;
     use colored::Colorize;
 
     // Run analysis
-    let diagnostics = analyze(path, config)?;
-
-    // Filter by rules if specified
-    let diagnostics: Vec<_> = if let Some(filter) = rules_filter {
-        let allowed_rules: Vec<&str> = filter.split(',').map(|s| s.trim()).collect();
-        diagnostics
-            .into_iter()
-            .filter(|d| allowed_rules.contains(&d.rule_id))
-            .collect()
-    } else {
-        diagnostics
-    };
+    let diagnostics = workspace_engine(config, path, selection, rules)?.analyze(path)?;
 
     // Count fixable diagnostics
     let fixable: Vec<_> = diagnostics.iter().filter(|d| d.fix.is_some()).collect();
@@ -59,14 +59,13 @@
 //! ```
 
 use crate::discovery::{discover_rust_files, DiscoveryOptions};
-use crate::engine::{analyze_file_with_rules, AnalysisContext};
+use crate::engine::{analyze_files, AnalysisContext, FileOptions};
 use crate::error::Error;
+use crate::rules::selection::RuleSelection;
 use crate::rules::{Diagnostic, Rule};
 use crate::Config;
-use rayon::prelude::*;
 use std::collections::HashMap;
 use std::path::Path;
-use std::sync::Mutex;
 
 /// A registry for managing both built-in and custom rules.
 ///
@@ -90,6 +89,8 @@ pub struct PluginRegistry {
     custom_rules: Vec<Box<dyn Rule>>,
     /// Index for O(1) lookup of custom rules by ID.
     custom_index: HashMap<String, usize>,
+    /// Which of the rules [`analyze_with_plugins`] runs.
+    selection: RuleSelection,
 }
 
 impl Default for PluginRegistry {
@@ -105,6 +106,7 @@ impl PluginRegistry {
             include_builtins: false,
             custom_rules: Vec::new(),
             custom_index: HashMap::new(),
+            selection: RuleSelection::all(),
         }
     }
 
@@ -166,6 +168,20 @@ impl PluginRegistry {
         }
     }
 
+    /// Restrict the rules [`analyze_with_plugins`] runs.
+    ///
+    /// Selection uses rule metadata, so custom rules that override
+    /// [`Rule::category`] and [`Rule::confidence`] take part in groups and
+    /// strict mode like built-in rules.
+    pub fn set_selection(&mut self, selection: RuleSelection) {
+        self.selection = selection;
+    }
+
+    /// The registered rules that the current selection runs.
+    pub fn selected_rules(&self) -> Vec<&dyn Rule> {
+        self.selection.select(self.rules())
+    }
+
     /// Get all registered rules as trait object references.
     ///
     /// Returns an iterator over all rules (built-in + custom).
@@ -311,6 +327,12 @@ impl PluginRegistryBuilder {
         self
     }
 
+    /// Restrict the rules that run (see [`PluginRegistry::set_selection`]).
+    pub fn with_selection(mut self, selection: RuleSelection) -> Self {
+        self.registry.set_selection(selection);
+        self
+    }
+
     /// Build the registry.
     pub fn build(self) -> PluginRegistry {
         self.registry
@@ -344,36 +366,16 @@ pub fn analyze_with_plugins(
     // Use secure discovery (same as Engine) to prevent symlink attacks
     let files = discover_rust_files(path, &DiscoveryOptions::secure());
 
-    // Track errors but don't fail the entire analysis
-    let errors: Mutex<Vec<(std::path::PathBuf, Error)>> = Mutex::new(Vec::new());
-
-    // Analyze files in parallel using shared file analysis logic
-    let all_diagnostics: Vec<Diagnostic> = files
-        .par_iter()
-        .flat_map(|file_path| {
-            // Use shared analysis function with plugin registry rules
-            let rules = registry.rules().into_iter();
-            match analyze_file_with_rules(file_path, config, rules) {
-                Ok(diagnostics) => diagnostics,
-                Err(e) => {
-                    // Log errors but continue analyzing other files
-                    if let Ok(mut errs) = errors.lock() {
-                        errs.push((file_path.clone(), e));
-                    }
-                    Vec::new()
-                }
-            }
-        })
-        .collect();
-
-    // Report errors at the end (same as Engine)
-    if let Ok(errs) = errors.lock() {
-        for (path, error) in errs.iter() {
-            eprintln!("Warning: Failed to analyze {}: {}", path.display(), error);
-        }
-    }
-
-    Ok(all_diagnostics)
+    // Share the Engine's parse-once / crate-graph pipeline with the plugin rule set
+    let rules = registry.selected_rules();
+    Ok(analyze_files(
+        &files,
+        FileOptions::default(),
+        config,
+        &rules,
+        None,
+        |_| {},
+    ))
 }
 
 /// A helper macro for defining custom rules more concisely.
@@ -465,6 +467,57 @@ mod tests {
         }
     }
 
+    struct ConfidentRule;
+
+    impl Rule for ConfidentRule {
+        fn id(&self) -> &'static str {
+            "confident-rule"
+        }
+
+        fn name(&self) -> &'static str {
+            "Confident Rule"
+        }
+
+        fn description(&self) -> &'static str {
+            "A high-confidence test rule"
+        }
+
+        fn default_severity(&self) -> Severity {
+            Severity::Error
+        }
+
+        fn confidence(&self) -> crate::rules::Confidence {
+            crate::rules::Confidence::High
+        }
+
+        fn check(&self, _ctx: &AnalysisContext) -> Vec<Diagnostic> {
+            Vec::new()
+        }
+    }
+
+    #[test]
+    fn test_strict_selection_includes_confident_custom_rules() {
+        let registry = PluginRegistryBuilder::new()
+            .with_builtin_rules()
+            .with_rule(Box::new(TestRule))
+            .with_rule(Box::new(ConfidentRule))
+            .with_selection(
+                RuleSelection::all().with_min_confidence(crate::rules::Confidence::High),
+            )
+            .build();
+
+        let ids: Vec<&str> = registry.selected_rules().iter().map(|r| r.id()).collect();
+        assert_eq!(
+            ids,
+            vec![
+                "async-block-in-async",
+                "lock-across-await",
+                "unused-suppression",
+                "confident-rule"
+            ]
+        );
+    }
+
     #[test]
     fn test_registry_add_rule() {
         let mut registry = PluginRegistry::new();
@@ -519,6 +572,52 @@ mod tests {
         assert!(registry.has_rule("test-rule"));
     }
 
+    struct PanickingRule;
+
+    impl Rule for PanickingRule {
+        fn id(&self) -> &'static str {
+            "panicking-rule"
+        }
+
+        fn name(&self) -> &'static str {
+            "Panicking Rule"
+        }
+
+        fn description(&self) -> &'static str {
+            "A rule that always panics"
+        }
+
+        fn default_severity(&self) -> Severity {
+            Severity::Warning
+        }
+
+        fn check(&self, _ctx: &AnalysisContext) -> Vec<Diagnostic> {
+            panic!("rule bug")
+        }
+    }
+
+    #[test]
+    fn test_panicking_rule_suppressions_are_not_unused() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(
+            tmp.path().join("lib.rs"),
+            "fn f() {\n    // cargo-perf-ignore: panicking-rule\n    let x = 1;\n}\n",
+        )
+        .unwrap();
+        let registry = PluginRegistryBuilder::new()
+            .with_builtin_rules()
+            .with_rule(Box::new(PanickingRule))
+            .build();
+
+        let diagnostics = analyze_with_plugins(tmp.path(), &Config::default(), &registry).unwrap();
+        assert!(
+            diagnostics
+                .iter()
+                .all(|d| d.rule_id != "unused-suppression"),
+            "{diagnostics:?}"
+        );
+    }
+
     #[test]
     fn test_rule_ids() {
         let mut registry = PluginRegistry::new();
@@ -70,6 +70,52 @@ fn print_diagnostic(d: &Diagnostic) {
     println!();
 }
 
+/// One line per finding, `path:line:col: severity: message [rule]`, with
+/// duplicates at the same place collapsed. Used by `cargo perf watch`.
+pub fn report_compact(diagnostics: &[Diagnostic]) {
+    let unique = deduplicated(diagnostics);
+    if unique.is_empty() {
+        println!("{}", "No performance issues found.".green());
+        return;
+    }
+    for d in &unique {
+        println!("{}", compact_line(d));
+    }
+    let count = |severity| unique.iter().filter(|d| d.severity == severity).count();
+    println!(
+        "{} error(s), {} warning(s), {} info(s)",
+        count(Severity::Error),
+        count(Severity::Warning),
+        count(Severity::Info)
+    );
+}
+
+/// Diagnostics sorted by location, one per rule and location.
+fn deduplicated(diagnostics: &[Diagnostic]) -> Vec<&Diagnostic> {
+    let key = |d: &Diagnostic| (d.file_path.clone(), d.line, d.column, d.rule_id);
+    let mut sorted: Vec<&Diagnostic> = diagnostics.iter().collect();
+    sorted.sort_by_cached_key(|d| key(d));
+    sorted.dedup_by(|a, b| key(a) == key(b));
+    sorted
+}
+
+fn compact_line(d: &Diagnostic) -> String {
+    let severity = match d.severity {
+        Severity::Error => "error".red().bold(),
+        Severity::Warning => "warning".yellow().bold(),
+        Severity::Info => "info".blue().bold(),
+    };
+    format!(
+        "{}:{}:{}: {}: {} {}",
+        d.file_path.display(),
+        d.line,
+        d.column,
+        severity,
+        d.message,
+        format!("[{}]", d.rule_id).dimmed()
+    )
+}
+
 /// Format a diagnostic as a plain text string (no colors) for testing.
 #[cfg(test)]
 fn format_diagnostic_plain(d: &Diagnostic) -> String {
@@ -134,6 +180,7 @@ mod tests {
             end_column: None,
             suggestion: suggestion.map(|s| s.to_string()),
             fix: None,
+            target: None,
         }
     }
 
@@ -154,6 +201,29 @@ mod tests {
         assert_eq!(infos, 3);
     }
 
+    #[test]
+    fn test_compact_lines_are_sorted_and_deduplicated() {
+        colored::control::set_override(false);
+        let mut later = make_diagnostic("w1", Severity::Warning, None);
+        later.line = 20;
+        let diagnostics = vec![
+            later,
+            make_diagnostic("e1", Severity::Error, None),
+            make_diagnostic("e1", Severity::Error, None),
+        ];
+        let lines: Vec<String> = deduplicated(&diagnostics)
+            .into_iter()
+            .map(compact_line)
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                "test.rs:10:5: error: Test message for e1 [e1]",
+                "test.rs:20:5: warning: Test message for w1 [w1]",
+            ]
+        );
+    }
+
     #[test]
     fn test_count_empty() {
         let (errors, warnings, infos) = count_by_severity(&[]);
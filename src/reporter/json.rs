@@ -30,6 +30,7 @@ mod tests {
             end_column: None,
             suggestion: Some("Test suggestion".to_string()),
             fix: None,
+            target: None,
         }
     }
 
@@ -65,6 +66,7 @@ mod tests {
             end_column: None,
             suggestion: None,
             fix: None,
+            target: None,
         };
         let diag2 = Diagnostic {
             rule_id: "rule-b",
@@ -77,6 +79,7 @@ mod tests {
             end_column: None,
             suggestion: None,
             fix: None,
+            target: None,
         };
 
         let result = format(&[diag1, diag2]).unwrap();
@@ -44,6 +44,21 @@ struct SarifRule {
     name: String,
     #[serde(rename = "shortDescription")]
     short_description: SarifMessage,
+    #[serde(rename = "fullDescription", skip_serializing_if = "Option::is_none")]
+    full_description: Option<SarifMessage>,
+    #[serde(rename = "helpUri", skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<SarifRuleProperties>,
+}
+
+/// Rule metadata in the property names GitHub code scanning understands.
+#[derive(Serialize)]
+struct SarifRuleProperties {
+    category: &'static str,
+    /// `high`, `medium` or `low`.
+    precision: &'static str,
+    tags: Vec<&'static str>,
 }
 
 #[derive(Serialize)]
@@ -53,6 +68,18 @@ struct SarifResult {
     level: &'static str,
     message: SarifMessage,
     locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    properties: Option<SarifResultProperties>,
+}
+
+/// Property bag carrying the package and target of a result.
+#[derive(Serialize)]
+struct SarifResultProperties {
+    package: String,
+    #[serde(rename = "targetKind")]
+    target_kind: String,
+    #[serde(rename = "targetName")]
+    target_name: String,
 }
 
 #[derive(Serialize)]
@@ -94,20 +121,36 @@ impl SarifReport {
         let seen_rules: std::collections::HashSet<&str> =
             diagnostics.iter().map(|d| d.rule_id).collect();
 
-        // Build rules list outside the loop using registry for descriptions
+        // Build rules list outside the loop using registry metadata
         let rules: Vec<SarifRule> = seen_rules
             .into_iter()
-            .map(|rule_id| {
-                let description = registry::get_rule(rule_id)
-                    .map(|r| r.description())
-                    .unwrap_or(rule_id);
-                SarifRule {
+            .map(|rule_id| match registry::get_rule(rule_id) {
+                Some(rule) => SarifRule {
+                    id: rule_id.to_string(),
+                    name: rule.name().to_string(),
+                    short_description: SarifMessage {
+                        text: rule.description().to_string(),
+                    },
+                    full_description: Some(SarifMessage {
+                        text: rule.explanation().rationale.to_string(),
+                    }),
+                    help_uri: rule.doc_url(),
+                    properties: Some(SarifRuleProperties {
+                        category: rule.category().as_str(),
+                        precision: rule.confidence().as_str(),
+                        tags: rule.tags().to_vec(),
+                    }),
+                },
+                None => SarifRule {
                     id: rule_id.to_string(),
                     name: rule_id.to_string(),
                     short_description: SarifMessage {
-                        text: description.to_string(),
+                        text: rule_id.to_string(),
                     },
-                }
+                    full_description: None,
+                    help_uri: None,
+                    properties: None,
+                },
             })
             .collect();
 
@@ -134,6 +177,11 @@ impl SarifReport {
                         },
                     },
                 }],
+                properties: d.target.as_ref().map(|t| SarifResultProperties {
+                    package: t.package.clone(),
+                    target_kind: t.kind.to_string(),
+                    target_name: t.name.clone(),
+                }),
             })
             .collect();
 
@@ -178,6 +226,7 @@ mod tests {
             end_column: None,
             suggestion: None,
             fix: None,
+            target: None,
         }
     }
 
@@ -221,6 +270,7 @@ mod tests {
             end_column: None,
             suggestion: None,
             fix: None,
+            target: None,
         };
 
         let result = format(&[diag]).unwrap();
@@ -254,4 +304,39 @@ mod tests {
         assert!(parsed.get("$schema").is_some());
         assert!(parsed.get("runs").is_some());
     }
+
+    #[test]
+    fn test_sarif_target_properties() {
+        use crate::workspace::{TargetInfo, TargetKind};
+
+        let mut diag = test_diagnostic("test-rule", Severity::Error);
+        diag.target = Some(TargetInfo {
+            package: "core".to_string(),
+            kind: TargetKind::Bin,
+            name: "server".to_string(),
+        });
+        let untagged = test_diagnostic("test-rule", Severity::Error);
+
+        let result = format(&[diag, untagged]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let results = &parsed["runs"][0]["results"];
+        assert_eq!(results[0]["properties"]["package"], "core");
+        assert_eq!(results[0]["properties"]["targetKind"], "bin");
+        assert!(results[1].get("properties").is_none());
+    }
+
+    #[test]
+    fn test_sarif_rule_metadata() {
+        let diag = test_diagnostic("async-block-in-async", Severity::Error);
+        let result = format(&[diag]).unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&result).unwrap();
+        let rule = &parsed["runs"][0]["tool"]["driver"]["rules"][0];
+        assert_eq!(rule["properties"]["category"], "async");
+        assert_eq!(rule["properties"]["precision"], "high");
+        assert!(rule["helpUri"]
+            .as_str()
+            .unwrap()
+            .ends_with("#async-block-in-async"));
+        assert!(rule["fullDescription"]["text"].is_string());
+    }
 }
@@ -1,7 +1,10 @@
 //! Rules for detecting allocation anti-patterns.
 
 use super::visitor::VisitorState;
-use super::{Diagnostic, Fix, Replacement, Rule, Severity, MAX_FIX_TEXT_SIZE};
+use super::{
+    builtin_doc_url, Category, Confidence, Diagnostic, Explanation, Fix, Replacement, Rule,
+    Severity, MAX_FIX_TEXT_SIZE,
+};
 use crate::engine::AnalysisContext;
 use std::collections::HashSet;
 use syn::spanned::Spanned;
@@ -28,6 +31,53 @@ impl Rule for VecNoCapacityRule {
         Severity::Warning
     }
 
+    fn category(&self) -> Category {
+        Category::Allocation
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["allocation", "loops"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale: "`Vec::new()` starts with zero capacity, so pushing in a loop reallocates \
+                repeatedly as the vector grows. Pre-allocating with `Vec::with_capacity` \
+                avoids the reallocations.",
+            bad_example: Some(
+                r#"let mut results = Vec::new();
+for i in 0..1000 {
+    results.push(compute(i));
+}"#,
+            ),
+            good_example: Some(
+                r#"let mut results = Vec::with_capacity(1000);
+for i in 0..1000 {
+    results.push(compute(i));
+}"#,
+            ),
+            impact: Some("Benchmark: 1.8x faster with pre-allocated capacity."),
+            related: &[
+                "hashmap-no-capacity",
+                "string-no-capacity",
+                "collect-then-iterate",
+            ],
+            suppression: Some(
+                "Suppress when the number of elements is unknown and usually \
+                small.",
+            ),
+            autofix: false,
+        }
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
         let mut visitor = VecNoCapacityVisitor {
             ctx,
@@ -150,6 +200,7 @@ impl<'ast> Visit<'ast> for VecNoCapacityVisitor<'_> {
                             end_column: None,
                             suggestion: Some("Pre-allocate with `Vec::with_capacity(expected_size)`".to_string()),
                             fix: None,
+                            target: None,
                         });
 
                         // Remove from tracking to avoid duplicate warnings
@@ -204,6 +255,49 @@ impl Rule for HashMapNoCapacityRule {
         Severity::Warning
     }
 
+    fn category(&self) -> Category {
+        Category::Allocation
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["allocation", "loops"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale: "`HashMap::new()` starts empty, so inserting in a loop triggers repeated \
+                rehashing as the map grows. Pre-allocating with `HashMap::with_capacity` \
+                avoids it.",
+            bad_example: Some(
+                r#"let mut index = HashMap::new();
+for user in &users {
+    index.insert(user.id, user);
+}"#,
+            ),
+            good_example: Some(
+                r#"let mut index = HashMap::with_capacity(users.len());
+for user in &users {
+    index.insert(user.id, user);
+}"#,
+            ),
+            impact: Some("Avoids repeated rehashing as the map grows."),
+            related: &["vec-no-capacity", "string-no-capacity"],
+            suppression: Some(
+                "Suppress when the number of entries is unknown and usually \
+                small.",
+            ),
+            autofix: false,
+        }
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
         let mut visitor = HashMapNoCapacityVisitor {
             ctx,
@@ -322,6 +416,7 @@ impl<'ast> Visit<'ast> for HashMapNoCapacityVisitor<'_> {
                             end_column: None,
                             suggestion: Some("Pre-allocate with `HashMap::with_capacity(expected_size)`".to_string()),
                             fix: None,
+                            target: None,
                         });
 
                         // Remove from tracking to avoid duplicate warnings
@@ -376,6 +471,47 @@ impl Rule for StringNoCapacityRule {
         Severity::Warning
     }
 
+    fn category(&self) -> Category {
+        Category::Allocation
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["allocation", "loops", "strings"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale:
+                "`String::new()` starts with zero capacity, so appending in a loop reallocates \
+                repeatedly. Pre-allocating with `String::with_capacity` avoids the \
+                reallocations.",
+            bad_example: Some(
+                r#"let mut out = String::new();
+for word in &words {
+    out.push_str(word);
+}"#,
+            ),
+            good_example: Some(
+                r#"let mut out = String::with_capacity(words.iter().map(|w| w.len()).sum());
+for word in &words {
+    out.push_str(word);
+}"#,
+            ),
+            impact: Some("Avoids repeated reallocation as the string grows."),
+            related: &["vec-no-capacity", "string-concat-loop", "format-in-loop"],
+            suppression: Some("Suppress when the final length is unknown and usually small."),
+            autofix: false,
+        }
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
         let mut visitor = StringNoCapacityVisitor {
             ctx,
@@ -497,6 +633,7 @@ impl<'ast> Visit<'ast> for StringNoCapacityVisitor<'_> {
                             end_column: None,
                             suggestion: Some("Pre-allocate with `String::with_capacity(expected_size)`".to_string()),
                             fix: None,
+                            target: None,
                         });
 
                         // Remove from tracking to avoid duplicate warnings
@@ -551,6 +688,49 @@ impl Rule for FormatInLoopRule {
         Severity::Warning
     }
 
+    fn category(&self) -> Category {
+        Category::Allocation
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["allocation", "loops", "strings"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale: "`format!()` allocates a new `String` on every call, so using it in a loop \
+                allocates on every iteration. Reuse a buffer with `write!` instead.",
+            bad_example: Some(
+                r#"for item in items {
+    let msg = format!("Processing: {}", item);
+    log(msg);
+}"#,
+            ),
+            good_example: Some(
+                r#"use std::fmt::Write;
+
+let mut buf = String::new();
+for item in items {
+    buf.clear();
+    write!(&mut buf, "Processing: {}", item)?;
+    log(&buf);
+}"#,
+            ),
+            impact: Some("Removes one heap allocation per iteration."),
+            related: &["string-concat-loop", "string-no-capacity"],
+            suppression: None,
+            autofix: false,
+        }
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
         let mut visitor = FormatInLoopVisitor {
             ctx,
@@ -635,6 +815,7 @@ impl<'ast> Visit<'ast> for FormatInLoopVisitor<'_> {
                     end_column: None,
                     suggestion: Some("Consider using `write!()` to a reusable buffer or moving format outside loop".to_string()),
                     fix: None,
+                    target: None,
                 });
             }
         }
@@ -662,6 +843,45 @@ impl Rule for StringConcatLoopRule {
         Severity::Warning
     }
 
+    fn category(&self) -> Category {
+        Category::Allocation
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["allocation", "loops", "strings"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale: "The `+` operator on strings in a loop can reallocate on each iteration. \
+                Appending with `push_str()` into a pre-sized buffer avoids it.",
+            bad_example: Some(
+                r#"let mut result = String::new();
+for word in words {
+    result = result + word; // may reallocate each time
+}"#,
+            ),
+            good_example: Some(
+                r#"let mut result = String::new();
+for word in words {
+    result.push_str(word); // appends in place
+}"#,
+            ),
+            impact: Some("Avoids a reallocation and copy per iteration."),
+            related: &["string-no-capacity", "format-in-loop"],
+            suppression: None,
+            autofix: true,
+        }
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
         let mut visitor = StringConcatVisitor {
             ctx,
@@ -745,6 +965,7 @@ impl<'ast> Visit<'ast> for StringConcatVisitor<'_> {
                         end_column: None,
                         suggestion: Some("Use `String::push_str()` or `write!()` to a buffer instead".to_string()),
                         fix,
+                        target: None,
                     });
                 }
                 _ => {}
@@ -894,6 +1115,49 @@ impl Rule for MutexLockInLoopRule {
         Severity::Warning
     }
 
+    fn category(&self) -> Category {
+        Category::Allocation
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["locks", "loops"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale:
+                "Acquiring a lock inside a loop pays the lock/unlock cost on every iteration \
+                and invites contention. Acquire it once before the loop when possible.",
+            bad_example: Some(
+                r#"for item in items {
+    let mut guard = data.lock().unwrap();
+    guard.push(item);
+}"#,
+            ),
+            good_example: Some(
+                r#"let mut guard = data.lock().unwrap();
+for item in items {
+    guard.push(item);
+}"#,
+            ),
+            impact: Some("Removes per-iteration lock overhead and contention."),
+            related: &["lock-across-await"],
+            suppression: Some(
+                "Suppress when the lock must be released between iterations so \
+                other threads can make progress.",
+            ),
+            autofix: false,
+        }
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
         let mut visitor = MutexLockVisitor {
             ctx,
@@ -1106,6 +1370,7 @@ impl<'ast> Visit<'ast> for MutexLockVisitor<'_> {
                         "Acquire the lock before the loop to reduce lock contention".to_string(),
                     ),
                     fix: None,
+                    target: None,
                 });
             }
         }
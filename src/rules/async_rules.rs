@@ -1,9 +1,17 @@
-use super::resolve::{is_std_root, ImportOracle};
+use super::resolve::{is_std_root, matches_configured_path, ImportOracle};
 use super::visitor::VisitorState;
-use super::{Diagnostic, Fix, Replacement, Rule, Severity, MAX_FIX_TEXT_SIZE};
-use crate::engine::AnalysisContext;
+use super::{
+    builtin_doc_url, Category, Confidence, Diagnostic, Explanation, Fix, Replacement, Rule,
+    Severity, MAX_FIX_TEXT_SIZE,
+};
+use crate::config::RuleOptions;
+use crate::engine::{AnalysisContext, Callee, CrateGraph, FileSummary, Symbol, SymbolKind};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
 use syn::visit::Visit;
-use syn::{Expr, ExprCall, ExprMethodCall, ExprPath, ImplItemFn, ItemFn, Member};
+use syn::{Expr, ExprCall, ExprMethodCall, ExprPath, ImplItemFn, ItemFn, ItemImpl, Member};
 
 // ============================================================================
 // Unbounded Channel Detection
@@ -50,12 +58,56 @@ impl Rule for UnboundedChannelRule {
         Severity::Warning
     }
 
+    fn category(&self) -> Category {
+        Category::Async
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["backpressure", "memory"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale: "Unbounded channels can grow without limit, exhausting memory if producers \
+                outpace consumers. A bounded channel applies backpressure instead.",
+            bad_example: Some(
+                r#"let (tx, rx) = std::sync::mpsc::channel(); // unbounded
+let (tx, rx) = tokio::sync::mpsc::unbounded_channel();"#,
+            ),
+            good_example: Some(
+                r#"let (tx, rx) = std::sync::mpsc::sync_channel(100); // bounded
+let (tx, rx) = tokio::sync::mpsc::channel(100);"#,
+            ),
+            impact: Some("Prevents out-of-memory crashes under load."),
+            related: &["unbounded-spawn"],
+            suppression: Some(
+                "Suppress when the producer is bounded by construction, e.g. a \
+                fixed number of messages.",
+            ),
+            autofix: true,
+        }
+    }
+
+    fn validate_options(&self, options: &RuleOptions) -> Result<(), String> {
+        options.parse::<UnboundedChannelOptions>().map(|_| ())
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
+        let options: UnboundedChannelOptions = ctx.rule_options(self.id());
         let mut visitor = UnboundedChannelVisitor {
             ctx,
             diagnostics: Vec::new(),
             state: VisitorState::new(),
-            imports: ImportOracle::from_file(ctx.ast),
+            imports: ImportOracle::from_context(ctx),
+            capacity: options.suggested_capacity,
         };
         visitor.visit_file(ctx.ast);
         visitor.diagnostics
@@ -67,6 +119,7 @@ struct UnboundedChannelVisitor<'a> {
     diagnostics: Vec<Diagnostic>,
     state: VisitorState,
     imports: ImportOracle,
+    capacity: NonZeroUsize,
 }
 
 /// Unbounded channel patterns and their bounded alternatives
@@ -108,21 +161,37 @@ const STD_MPSC_CHANNEL_PATTERNS: &[&str] = &[
 ];
 
 /// Channel replacement patterns for auto-fix
-/// Format: (match_suffix, replacement_fn, replacement_call)
-const CHANNEL_FIXES: &[(&str, &str, &str)] = &[
-    // std::sync::mpsc patterns - replace `channel()` with `sync_channel(32)`
-    ("mpsc::channel", "sync_channel", "sync_channel(32)"),
-    // tokio patterns - replace `unbounded_channel()` with `channel(32)`
-    ("unbounded_channel", "channel", "channel(32)"),
-    // crossbeam patterns - replace `unbounded()` with `bounded(32)`
-    ("crossbeam_channel::unbounded", "bounded", "bounded(32)"),
-    ("crossbeam::channel::unbounded", "bounded", "bounded(32)"),
+/// Format: (match_suffix, bounded_fn); the fix calls `bounded_fn(capacity)`
+const CHANNEL_FIXES: &[(&str, &str)] = &[
+    // std::sync::mpsc patterns - replace `channel()` with `sync_channel(N)`
+    ("mpsc::channel", "sync_channel"),
+    // tokio patterns - replace `unbounded_channel()` with `channel(N)`
+    ("unbounded_channel", "channel"),
+    // crossbeam patterns - replace `unbounded()` with `bounded(N)`
+    ("crossbeam_channel::unbounded", "bounded"),
+    ("crossbeam::channel::unbounded", "bounded"),
     // flume patterns
-    ("flume::unbounded", "bounded", "bounded(32)"),
+    ("flume::unbounded", "bounded"),
     // async-channel patterns
-    ("async_channel::unbounded", "bounded", "bounded(32)"),
+    ("async_channel::unbounded", "bounded"),
 ];
 
+/// Options from `[rules.unbounded-channel]`.
+#[derive(Debug, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct UnboundedChannelOptions {
+    /// Capacity the auto-fix gives the bounded channel.
+    suggested_capacity: NonZeroUsize,
+}
+
+impl Default for UnboundedChannelOptions {
+    fn default() -> Self {
+        Self {
+            suggested_capacity: NonZeroUsize::new(32).expect("nonzero"),
+        }
+    }
+}
+
 impl UnboundedChannelVisitor<'_> {
     fn report(
         &mut self,
@@ -151,6 +220,7 @@ impl UnboundedChannelVisitor<'_> {
                 alternative
             )),
             fix,
+            target: None,
         });
     }
 
@@ -159,7 +229,7 @@ impl UnboundedChannelVisitor<'_> {
         use syn::spanned::Spanned;
 
         // Find matching fix pattern
-        for &(match_suffix, _replacement_fn, replacement_call) in CHANNEL_FIXES {
+        for &(match_suffix, bounded_fn) in CHANNEL_FIXES {
             if path_str.ends_with(match_suffix) {
                 // Skip tokio's bounded channel (already bounded)
                 if path_str.contains("tokio") && !path_str.contains("unbounded") {
@@ -188,7 +258,7 @@ impl UnboundedChannelVisitor<'_> {
                     };
 
                     let prefix = &original_path[..fn_name_start];
-                    let new_path = format!("{}{}", prefix, replacement_call);
+                    let new_path = format!("{}{}({})", prefix, bounded_fn, self.capacity);
 
                     // Get the full call span (including parens)
                     let call_span = node.span();
@@ -347,11 +417,64 @@ impl Rule for UnboundedSpawnRule {
         Severity::Warning
     }
 
+    fn category(&self) -> Category {
+        Category::Async
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["concurrency", "backpressure"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale:
+                "Spawning tasks in a loop without a limit can exhaust memory and overwhelm the \
+                runtime with too many concurrent tasks. Bound concurrency with a semaphore, \
+                `JoinSet` or `buffer_unordered`.",
+            bad_example: Some(
+                r#"for url in urls {
+    tokio::spawn(fetch(url)); // one task per URL, no limit
+}"#,
+            ),
+            good_example: Some(
+                r#"use futures::stream::StreamExt;
+
+futures::stream::iter(urls)
+    .map(fetch)
+    .buffer_unordered(10) // at most 10 concurrent
+    .collect::<Vec<_>>()
+    .await;"#,
+            ),
+            impact: Some(
+                "Keeps memory use and runtime load bounded when the input is \
+                large.",
+            ),
+            related: &["unbounded-channel"],
+            suppression: Some("Suppress when the loop has a small, fixed number of iterations."),
+            autofix: false,
+        }
+    }
+
+    fn validate_options(&self, options: &RuleOptions) -> Result<(), String> {
+        options.parse::<UnboundedSpawnOptions>().map(|_| ())
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
+        let options: UnboundedSpawnOptions = ctx.rule_options(self.id());
         let mut visitor = UnboundedSpawnVisitor {
             ctx,
             diagnostics: Vec::new(),
             state: VisitorState::new(),
+            imports: ImportOracle::from_context(ctx),
+            extra_spawn: &options.extra_spawn,
         };
         visitor.visit_file(ctx.ast);
         visitor.diagnostics
@@ -362,6 +485,16 @@ struct UnboundedSpawnVisitor<'a> {
     ctx: &'a AnalysisContext<'a>,
     diagnostics: Vec<Diagnostic>,
     state: VisitorState,
+    imports: ImportOracle,
+    extra_spawn: &'a [String],
+}
+
+/// Options from `[rules.unbounded-spawn]`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct UnboundedSpawnOptions {
+    /// Paths of in-house spawn functions, e.g. `crate::rt::spawn_task`.
+    extra_spawn: Vec<String>,
 }
 
 /// Task spawn functions that should be bounded when used in loops
@@ -380,32 +513,45 @@ impl UnboundedSpawnVisitor<'_> {
                     SPAWN_PREFIXES.iter().any(|p| path_str.contains(p)) || path_str == spawn_fn; // bare `spawn` after `use`
 
                 if is_runtime_spawn {
-                    let line = span.start().line;
-                    let column = span.start().column;
-
-                    self.diagnostics.push(Diagnostic {
-                        rule_id: "unbounded-spawn",
-                        severity: Severity::Warning,
-                        // cargo-perf-ignore: format-in-loop
-                        message: format!(
-                            "Task `{}` in loop without concurrency limit can exhaust resources",
-                            spawn_fn
-                        ),
-                        file_path: self.ctx.file_path.to_path_buf(),
-                        line,
-                        column,
-                        end_line: None,
-                        end_column: None,
-                        suggestion: Some(
-                            "Use a Semaphore, buffer_unordered(), or JoinSet with limits"
-                                .to_string(),
-                        ),
-                        fix: None,
-                    });
+                    self.report(spawn_fn, span);
                     return;
                 }
             }
         }
+
+        // In-house spawn wrappers from `extra-spawn`
+        if !self.extra_spawn.is_empty() {
+            let canon = self.imports.canonicalize(path_str);
+            if self
+                .extra_spawn
+                .iter()
+                .any(|extra| matches_configured_path(&canon, extra))
+            {
+                let name = path_str.rsplit("::").next().unwrap_or(path_str);
+                self.report(name, span);
+            }
+        }
+    }
+
+    fn report(&mut self, spawn_fn: &str, span: proc_macro2::Span) {
+        self.diagnostics.push(Diagnostic {
+            rule_id: "unbounded-spawn",
+            severity: Severity::Warning,
+            message: format!(
+                "Task `{}` in loop without concurrency limit can exhaust resources",
+                spawn_fn
+            ),
+            file_path: self.ctx.file_path.to_path_buf(),
+            line: span.start().line,
+            column: span.start().column,
+            end_line: None,
+            end_column: None,
+            suggestion: Some(
+                "Use a Semaphore, buffer_unordered(), or JoinSet with limits".to_string(),
+            ),
+            fix: None,
+            target: None,
+        });
     }
 }
 
@@ -494,6 +640,7 @@ impl<'ast> Visit<'ast> for UnboundedSpawnVisitor<'_> {
                         "Use a Semaphore, buffer_unordered(), or JoinSet with limits".to_string(),
                     ),
                     fix: None,
+                    target: None,
                 });
             }
         }
@@ -582,9 +729,18 @@ impl UnboundedSpawnVisitor<'_> {
 // Blocking Call Detection
 // ============================================================================
 
-/// Detects blocking calls inside async functions
+/// Detects blocking calls inside async functions.
+///
+/// Besides calls written directly in an async body, calls to synchronous
+/// functions and methods of the same crate are followed (up to eight frames)
+/// through the crate graph, so `async fn handler() { load_config() }` is
+/// reported when `load_config` reaches `std::fs::read_to_string`. The
+/// diagnostic names the call chain.
 pub struct AsyncBlockInAsyncRule;
 
+/// Maximum number of crate-local sync functions followed from an async body.
+const MAX_CALL_CHAIN_DEPTH: usize = 8;
+
 impl Rule for AsyncBlockInAsyncRule {
     fn id(&self) -> &'static str {
         "async-block-in-async"
@@ -602,13 +758,78 @@ impl Rule for AsyncBlockInAsyncRule {
         Severity::Error
     }
 
+    fn category(&self) -> Category {
+        Category::Async
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["blocking", "runtime"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale:
+                "Blocking calls in async functions block the entire async runtime thread, so \
+                every other task scheduled on that thread stalls until the call returns. Sync \
+                helpers in the same crate are followed, so a blocking call a few frames down \
+                is reported at the async call site with the call chain.",
+            bad_example: Some(
+                r#"async fn fetch_data() {
+    let data = std::fs::read_to_string("file.txt"); // blocks the runtime thread
+    std::thread::sleep(Duration::from_secs(1)); // blocks the runtime thread
+}"#,
+            ),
+            good_example: Some(
+                r#"async fn fetch_data() {
+    let data = tokio::fs::read_to_string("file.txt").await;
+    tokio::time::sleep(Duration::from_secs(1)).await;
+}"#,
+            ),
+            impact: Some("Can reduce async throughput by 10-100x depending on workload."),
+            related: &["lock-across-await"],
+            suppression: Some(
+                "Calls wrapped in `spawn_blocking` or `block_in_place` are not \
+                reported. Suppress only when the call is known to be fast (e.g. \
+                reading a small file at startup).",
+            ),
+            autofix: true,
+        }
+    }
+
+    fn validate_options(&self, options: &RuleOptions) -> Result<(), String> {
+        options.parse::<AsyncBlockingOptions>().map(|_| ())
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
+        // Without a crate-wide graph (single-file analysis), follow calls into
+        // functions defined in this file only.
+        let file_graph;
+        let graph = match ctx.crate_graph() {
+            Some(graph) => graph,
+            None => {
+                file_graph = CrateGraph::build(&[FileSummary::from_ast(ctx.file_path, ctx.ast)]);
+                &file_graph
+            }
+        };
+        let options: AsyncBlockingOptions = ctx.rule_options(self.id());
         let mut visitor = AsyncBlockingVisitor {
             ctx,
             diagnostics: Vec::new(),
             in_async_fn: false,
             state: VisitorState::new(),
-            imports: ImportOracle::from_file(ctx.ast),
+            imports: ImportOracle::from_context(ctx),
+            graph,
+            impl_type: None,
+            chains: HashMap::new(),
+            extra_blocking: &options.extra_blocking,
         };
         visitor.visit_file(ctx.ast);
         visitor.diagnostics
@@ -621,6 +842,35 @@ struct AsyncBlockingVisitor<'a> {
     in_async_fn: bool,
     state: VisitorState,
     imports: ImportOracle,
+    graph: &'a CrateGraph,
+    /// Self type of the enclosing impl block, for `self.method()` calls.
+    impl_type: Option<String>,
+    /// Shortest blocking chain (if any) below each crate-local function,
+    /// keyed by its path and the number of frames the chain may span.
+    chains: HashMap<(String, usize), Option<BlockingChain>>,
+    /// In-house blocking functions from `extra-blocking`.
+    extra_blocking: &'a [String],
+}
+
+/// Options from `[rules.async-block-in-async]`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct AsyncBlockingOptions {
+    /// Paths of functions that block, e.g. `crate::legacy::sync_fetch`.
+    extra_blocking: Vec<String>,
+}
+
+/// A path from a crate-local sync function down to a blocking call.
+#[derive(Clone)]
+struct BlockingChain {
+    /// Functions called in turn, starting with the one called from async code.
+    frames: Vec<String>,
+    func_name: String,
+    /// Async replacement, if one is known.
+    alternative: Option<&'static str>,
+    /// Location of the blocking call itself.
+    file: PathBuf,
+    line: usize,
 }
 
 /// If `path` contains a segment equal to `type_leaf`, return the leading
@@ -774,21 +1024,193 @@ impl AsyncBlockingVisitor<'_> {
             return;
         }
 
-        let mut best: Option<(&str, &str)> = None;
+        if let Some((func_name, alternative)) = Self::match_blocking_path(&canon) {
+            let fix = call_node.and_then(|node| self.generate_blocking_fix(node, alternative));
+            self.emit_blocking(func_name, Some(alternative), span, fix);
+        } else if let Some(func_name) = self.match_extra_blocking(&canon) {
+            self.emit_blocking(func_name, None, span, None);
+        }
+    }
+
+    /// Name of the function if `canon` is one of the configured
+    /// `extra-blocking` paths.
+    fn match_extra_blocking<'p>(&self, canon: &'p str) -> Option<&'p str> {
+        self.extra_blocking
+            .iter()
+            .any(|extra| matches_configured_path(canon, extra))
+            .then(|| canon.rsplit("::").next().unwrap_or(canon))
+    }
+
+    /// Longest `BLOCKING_CALLS` entry whose `leaf::func` ends `canon`.
+    fn match_blocking_path(canon: &str) -> Option<(&'static str, &'static str)> {
+        let mut best: Option<(&'static str, &'static str)> = None;
         let mut best_len = 0;
         for (module_path, func_name, alternative) in BLOCKING_CALLS {
             let leaf = Self::module_leaf(module_path);
             let needle = format!("{leaf}::{func_name}");
-            if Self::path_ends_with_boundary(&canon, &needle) && needle.len() > best_len {
+            if Self::path_ends_with_boundary(canon, &needle) && needle.len() > best_len {
                 best = Some((func_name, alternative));
                 best_len = needle.len();
             }
         }
+        best
+    }
+
+    /// Match a call site recorded in the crate graph against `BLOCKING_CALLS`
+    /// and `extra-blocking`. Paths are already canonical, so crate-local paths
+    /// only match configured ones and method calls need a std-rooted receiver,
+    /// as for direct calls.
+    fn match_blocking_callee(&self, callee: &Callee) -> Option<(String, Option<&'static str>)> {
+        match callee {
+            Callee::Path(path) => {
+                if let Some(func_name) = self.match_extra_blocking(path) {
+                    return Some((func_name.to_string(), None));
+                }
+                if path.starts_with("crate::")
+                    || path.starts_with("tokio::")
+                    || path.starts_with("async_std::")
+                {
+                    return None;
+                }
+                Self::match_blocking_path(path)
+                    .map(|(func_name, alternative)| (func_name.to_string(), Some(alternative)))
+            }
+            Callee::Method {
+                name,
+                receiver: Some(receiver),
+            } => {
+                let segments: Vec<&str> = receiver.split("::").collect();
+                BLOCKING_CALLS
+                    .iter()
+                    .filter(|(_, func_name, _)| func_name == name)
+                    .find(|(module_path, _, _)| {
+                        let leaf = Self::module_leaf(module_path);
+                        segments
+                            .iter()
+                            .position(|s| *s == leaf)
+                            .is_some_and(|pos| is_std_root(&segments[..=pos].join("::")))
+                    })
+                    .map(|(_, func_name, alternative)| (func_name.to_string(), Some(*alternative)))
+            }
+            Callee::Method { receiver: None, .. } => None,
+        }
+    }
 
-        if let Some((func_name, alternative)) = best {
-            let fix = call_node.and_then(|node| self.generate_blocking_fix(node, alternative));
-            self.emit_blocking(func_name, alternative, span, fix);
+    /// Follow a call written in async code (`load_config`, `Self::load`,
+    /// `Type::load`) into the crate and report it if it reaches a blocking call.
+    fn check_local_callee(&mut self, written: &str, span: proc_macro2::Span) {
+        let graph = self.graph;
+        let Some(symbol) = graph.symbol(self.ctx.file_path, written) else {
+            return;
+        };
+        if let Some(func_name) = self.match_extra_blocking(&symbol.path) {
+            self.emit_blocking(func_name, None, span, None);
+            return;
+        }
+        if let Some(chain) = self.blocking_chain(symbol, MAX_CALL_CHAIN_DEPTH) {
+            self.emit_blocking_chain(&chain, span);
+        }
+    }
+
+    /// Search below a crate-local sync function for the shortest chain of at
+    /// most `depth` frames that reaches a blocking call, preferring earlier
+    /// calls among chains of the same length.
+    ///
+    /// The answer depends only on the function and `depth`, so it is memoized
+    /// for both, which bounds the work by the number of functions times the
+    /// depth limit. Recursion needs no cycle check: every step lowers
+    /// `depth`, and a shortest chain never visits a function twice.
+    fn blocking_chain(&mut self, symbol: &Symbol, depth: usize) -> Option<BlockingChain> {
+        if depth == 0
+            || symbol.is_async
+            || !matches!(symbol.kind, SymbolKind::Fn | SymbolKind::Method)
+        {
+            return None;
+        }
+        let key = (symbol.path.clone(), depth);
+        if let Some(cached) = self.chains.get(&key) {
+            return cached.clone();
         }
+
+        let graph = self.graph;
+        let frame = symbol
+            .path
+            .strip_prefix("crate::")
+            .unwrap_or(&symbol.path)
+            .to_string();
+        let calls = graph.calls(symbol);
+
+        let mut found = calls.iter().find_map(|call| {
+            let (func_name, alternative) = self.match_blocking_callee(&call.callee)?;
+            Some(BlockingChain {
+                frames: vec![frame.clone()],
+                func_name,
+                alternative,
+                file: symbol.file.clone(),
+                line: call.line,
+            })
+        });
+        if found.is_none() {
+            for call in calls {
+                let Callee::Path(path) = &call.callee else {
+                    continue;
+                };
+                let Some(callee) = graph.lookup(&symbol.file, path) else {
+                    continue;
+                };
+                let Some(mut chain) = self.blocking_chain(callee, depth - 1) else {
+                    continue;
+                };
+                if found
+                    .as_ref()
+                    .is_some_and(|best| best.frames.len() <= chain.frames.len() + 1)
+                {
+                    continue;
+                }
+                chain.frames.insert(0, frame.clone());
+                found = Some(chain);
+            }
+        }
+
+        self.chains.insert(key, found.clone());
+        found
+    }
+
+    fn emit_blocking_chain(&mut self, chain: &BlockingChain, span: proc_macro2::Span) {
+        let entry = chain.frames.first().map(String::as_str).unwrap_or_default();
+        let (instead, suggestion) = match chain.alternative {
+            Some(alternative) => (
+                format!("Use `{}.await` instead.", alternative),
+                format!(
+                    "Make `{}` async and use `{}.await`, or call it via `tokio::task::spawn_blocking`",
+                    entry, alternative
+                ),
+            ),
+            None => (
+                "Run it with `tokio::task::spawn_blocking` instead.".to_string(),
+                format!("Call `{}` via `tokio::task::spawn_blocking`", entry),
+            ),
+        };
+        self.diagnostics.push(Diagnostic {
+            rule_id: "async-block-in-async",
+            severity: Severity::Error,
+            message: format!(
+                "Blocking call `{}` inside async function via `{}` at {}:{}. {}",
+                chain.func_name,
+                chain.frames.join("` -> `"),
+                chain.file.display(),
+                chain.line,
+                instead
+            ),
+            file_path: self.ctx.file_path.to_path_buf(),
+            line: span.start().line,
+            column: span.start().column,
+            end_line: None,
+            end_column: None,
+            suggestion: Some(suggestion),
+            fix: None,
+            target: None,
+        });
     }
 
     /// Method-form blocking call, e.g. `cmd.output()`. A bare method name is far
@@ -837,31 +1259,48 @@ impl AsyncBlockingVisitor<'_> {
         }
 
         if let Some((func_name, alternative)) = best {
-            self.emit_blocking(func_name, alternative, span, None);
+            self.emit_blocking(func_name, Some(alternative), span, None);
         }
     }
 
+    /// Report a blocking call. `alternative` is the async replacement, if
+    /// one is known; configured `extra-blocking` functions have none.
     fn emit_blocking(
         &mut self,
         func_name: &str,
-        alternative: &str,
+        alternative: Option<&str>,
         span: proc_macro2::Span,
         fix: Option<Fix>,
     ) {
+        let (message, suggestion) = match alternative {
+            Some(alternative) => (
+                format!(
+                    "Blocking call `{}` inside async function. Use `{}.await` instead.",
+                    func_name, alternative
+                ),
+                format!("Replace with `{}.await`", alternative),
+            ),
+            None => (
+                format!(
+                    "Blocking call `{}` inside async function. Run it with `tokio::task::spawn_blocking` instead.",
+                    func_name
+                ),
+                "Move the call into `tokio::task::spawn_blocking`, or use an async alternative"
+                    .to_string(),
+            ),
+        };
         self.diagnostics.push(Diagnostic {
             rule_id: "async-block-in-async",
             severity: Severity::Error,
-            message: format!(
-                "Blocking call `{}` inside async function. Use `{}.await` instead.",
-                func_name, alternative
-            ),
+            message,
             file_path: self.ctx.file_path.to_path_buf(),
             line: span.start().line,
             column: span.start().column,
             end_line: None,
             end_column: None,
-            suggestion: Some(format!("Replace with `{}.await`", alternative)),
+            suggestion: Some(suggestion),
             fix,
+            target: None,
         });
     }
 
@@ -903,6 +1342,16 @@ impl AsyncBlockingVisitor<'_> {
 }
 
 impl<'ast> Visit<'ast> for AsyncBlockingVisitor<'_> {
+    fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
+        let impl_type = match &*node.self_ty {
+            syn::Type::Path(p) => p.path.segments.last().map(|s| s.ident.to_string()),
+            _ => None,
+        };
+        let outer = std::mem::replace(&mut self.impl_type, impl_type);
+        syn::visit::visit_item_impl(self, node);
+        self.impl_type = outer;
+    }
+
     fn visit_item_fn(&mut self, node: &'ast ItemFn) {
         if self.state.should_bail() {
             return;
@@ -982,7 +1431,15 @@ impl<'ast> Visit<'ast> for AsyncBlockingVisitor<'_> {
                     .map(|s| s.ident.span())
                     .unwrap_or_else(proc_macro2::Span::call_site);
                 // Pass the full call node for fix generation (to include args and add .await)
+                let before = self.diagnostics.len();
                 self.check_blocking_path_call(&path_str, display_span, Some(node));
+                if self.diagnostics.len() == before {
+                    let written = match (path_str.strip_prefix("Self::"), &self.impl_type) {
+                        (Some(rest), Some(ty)) => format!("{ty}::{rest}"),
+                        _ => path_str,
+                    };
+                    self.check_local_callee(&written, display_span);
+                }
             }
         }
         syn::visit::visit_expr_call(self, node);
@@ -994,6 +1451,13 @@ impl<'ast> Visit<'ast> for AsyncBlockingVisitor<'_> {
             // Method calls only fire when the receiver chain corroborates the
             // std type; a bare method name is too ambiguous to flag.
             self.check_blocking_method_call(&method_name, &node.receiver, node.method.span());
+
+            // `self.helper()` is the one receiver whose type is known.
+            let on_self = matches!(&*node.receiver, Expr::Path(p) if p.path.is_ident("self"));
+            if let (true, Some(ty)) = (on_self, &self.impl_type) {
+                let written = format!("{ty}::{method_name}");
+                self.check_local_callee(&written, node.method.span());
+            }
         }
         syn::visit::visit_expr_method_call(self, node);
     }
@@ -1780,4 +2244,244 @@ mod tests {
         "#;
         assert!(check_blocking_code(source).is_empty());
     }
+
+    // ========================================================================
+    // Interprocedural: blocking calls behind crate-local sync helpers
+    // ========================================================================
+
+    #[test]
+    fn test_blocking_two_frames_down_reports_chain() {
+        let source = r#"
+            fn load_config() -> String {
+                read_raw("config.toml")
+            }
+            fn read_raw(path: &str) -> String {
+                std::fs::read_to_string(path).unwrap()
+            }
+            async fn handler() {
+                let _ = load_config();
+            }
+        "#;
+        let diags = check_blocking_code(source);
+        assert_eq!(diags.len(), 1, "{:?}", diags);
+        assert_eq!(diags[0].line, 9, "reported at the call in the async fn");
+        assert!(
+            diags[0].message.contains("`load_config` -> `read_raw`"),
+            "message must show the call chain: {}",
+            diags[0].message
+        );
+        assert!(diags[0].message.contains("test.rs:6"));
+        assert!(diags[0].fix.is_none(), "no mechanical fix across frames");
+    }
+
+    #[test]
+    fn test_blocking_behind_self_method_flagged() {
+        let source = r#"
+            use std::process::Command;
+            struct Git;
+            impl Git {
+                fn head(&self) -> Vec<u8> {
+                    Command::new("git").arg("rev-parse").output().unwrap().stdout
+                }
+                async fn refresh(&self) {
+                    let _ = self.head();
+                }
+            }
+        "#;
+        let diags = check_blocking_code(source);
+        assert_eq!(diags.len(), 1, "{:?}", diags);
+        assert!(diags[0].message.contains("`Git::head`"));
+    }
+
+    #[test]
+    fn test_helper_offloading_via_closure_not_flagged() {
+        // The helper only builds a closure for `spawn_blocking`; the blocking
+        // call inside it does not run on the async worker.
+        let source = r#"
+            fn offload() {
+                tokio::task::spawn_blocking(|| std::fs::read_to_string("a"));
+            }
+            async fn handler() {
+                offload();
+            }
+        "#;
+        assert!(check_blocking_code(source).is_empty());
+    }
+
+    #[test]
+    fn test_async_helper_and_recursion_not_followed() {
+        let source = r#"
+            async fn fetch() {
+                std::thread::sleep(std::time::Duration::from_secs(1));
+            }
+            fn ping(n: u32) { if n > 0 { pong(n - 1) } }
+            fn pong(n: u32) { ping(n) }
+            async fn handler() {
+                fetch().await;
+                ping(3);
+            }
+        "#;
+        // Only the direct call inside `fetch` itself fires.
+        let diags = check_blocking_code(source);
+        assert_eq!(diags.len(), 1, "{:?}", diags);
+        assert_eq!(diags[0].line, 3);
+    }
+
+    #[test]
+    fn test_helper_past_depth_limit_still_found_when_shallower() {
+        // From `deep`, `slow` lies past the depth limit; from `shallow` it
+        // does not. The cut-short search from `deep` must not be remembered.
+        let source = r#"
+            fn f1() { f2() }
+            fn f2() { f3() }
+            fn f3() { f4() }
+            fn f4() { f5() }
+            fn f5() { f6() }
+            fn f6() { f7() }
+            fn f7() { f8() }
+            fn f8() { slow() }
+            fn slow() { std::thread::sleep(std::time::Duration::from_secs(1)) }
+            async fn deep() {
+                f1();
+            }
+            async fn shallow() {
+                f7();
+            }
+        "#;
+        let diags = check_blocking_code(source);
+        assert_eq!(diags.len(), 1, "{:?}", diags);
+        assert_eq!(diags[0].line, 15);
+        assert!(diags[0].message.contains("`f7` -> `f8` -> `slow`"));
+    }
+
+    #[test]
+    fn test_wide_recursive_call_graph_searched_once_per_depth() {
+        // Every helper calls itself and every helper of the next level, so
+        // following each path separately would take width^depth steps.
+        let width = 12;
+        let levels = MAX_CALL_CHAIN_DEPTH + 2;
+        let mut source = String::new();
+        for level in 0..levels {
+            for i in 0..width {
+                let mut body = format!("l{level}_{i}();");
+                if level + 1 < levels {
+                    for j in 0..width {
+                        body.push_str(&format!(" l{}_{j}();", level + 1));
+                    }
+                } else if i == width - 1 {
+                    body.push_str(" std::thread::sleep(std::time::Duration::from_secs(1));");
+                }
+                source.push_str(&format!("fn l{level}_{i}() {{ {body} }}\n"));
+            }
+        }
+        source.push_str("async fn deep() {\n    l0_0();\n}\n");
+        source.push_str("async fn shallow() {\n    l3_0();\n}\n");
+
+        let diags = check_blocking_code(&source);
+        assert_eq!(diags.len(), 1, "{:?}", diags);
+        assert!(
+            diags[0].message.contains(&format!(
+                "`l3_0` -> `l4_0` -> `l5_0` -> `l6_0` -> `l7_0` -> `l8_0` -> `l9_{}`",
+                width - 1
+            )),
+            "shortest chain, earliest calls first: {}",
+            diags[0].message
+        );
+    }
+
+    // ========================================================================
+    // [rules.<id>] options
+    // ========================================================================
+
+    fn check_with_options(rule: &dyn Rule, options: &str, source: &str) -> Vec<Diagnostic> {
+        let config: Config =
+            toml::from_str(&format!("[rules.{}]\n{}", rule.id(), options)).expect("valid options");
+        rule.validate_options(&config.rule_options[rule.id()])
+            .expect("options accepted");
+        let ast = syn::parse_file(source).expect("Failed to parse test code");
+        let ctx = AnalysisContext::new(Path::new("test.rs"), source, &ast, &config);
+        rule.check(&ctx)
+    }
+
+    #[test]
+    fn test_extra_blocking_functions() {
+        let source = r#"
+            mod legacy {
+                pub fn sync_fetch() -> String { String::new() }
+            }
+            fn wrapper() -> String { legacy::sync_fetch() }
+            async fn direct() {
+                legacy_db::fetch_blocking(1);
+                legacy::sync_fetch();
+            }
+            async fn indirect() {
+                wrapper();
+            }
+        "#;
+        assert!(check_blocking_code(source).is_empty());
+
+        let diags = check_with_options(
+            &AsyncBlockInAsyncRule,
+            r#"extra-blocking = ["crate::legacy::sync_fetch", "legacy_db::fetch_blocking"]"#,
+            source,
+        );
+        let lines: Vec<usize> = diags.iter().map(|d| d.line).collect();
+        assert_eq!(lines, vec![7, 8, 11], "{:?}", diags);
+        assert!(diags[0].message.contains("`fetch_blocking`"));
+        assert!(diags[0].message.contains("spawn_blocking"));
+        assert!(diags[0].fix.is_none());
+        assert!(
+            diags[2].message.contains("via `wrapper`"),
+            "{}",
+            diags[2].message
+        );
+    }
+
+    #[test]
+    fn test_channel_fix_uses_suggested_capacity() {
+        let source = r#"
+            fn f() {
+                let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<u8>();
+            }
+        "#;
+        let diags = check_with_options(&UnboundedChannelRule, "suggested-capacity = 256", source);
+        let fix = diags[0].fix.as_ref().expect("fix");
+        assert!(fix.replacements[0].new_text.ends_with("channel(256)"));
+    }
+
+    #[test]
+    fn test_extra_spawn_functions() {
+        let source = r#"
+            use crate::rt::spawn_task;
+            async fn f(ids: Vec<u32>) {
+                for id in ids {
+                    spawn_task(async move { id });
+                }
+            }
+        "#;
+        assert!(check_spawn_code(source).is_empty());
+        let diags = check_with_options(
+            &UnboundedSpawnRule,
+            r#"extra-spawn = ["crate::rt::spawn_task"]"#,
+            source,
+        );
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0].message.contains("`spawn_task`"));
+    }
+
+    #[test]
+    fn test_invalid_options_are_rejected() {
+        let config: Config =
+            toml::from_str("[rules.unbounded-channel]\nsuggested-capacity = 0\n").unwrap();
+        assert!(UnboundedChannelRule
+            .validate_options(&config.rule_options["unbounded-channel"])
+            .is_err());
+
+        let config: Config =
+            toml::from_str("[rules.unbounded-spawn]\nextra-spwan = [\"x\"]\n").unwrap();
+        let err = UnboundedSpawnRule
+            .validate_options(&config.rule_options["unbounded-spawn"])
+            .unwrap_err();
+        assert!(err.contains("extra-spwan"), "{}", err);
+    }
 }
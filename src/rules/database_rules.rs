@@ -2,9 +2,12 @@
 //!
 //! Detects N+1 query patterns and other database anti-patterns in Diesel, SQLx, and SeaORM.
 
+use super::resolve::{matches_configured_path, ImportOracle};
 use super::visitor::VisitorState;
-use super::{Diagnostic, Rule, Severity};
+use super::{builtin_doc_url, Category, Confidence, Diagnostic, Explanation, Rule, Severity};
+use crate::config::{Orm, RuleOptions};
 use crate::engine::AnalysisContext;
+use serde::Deserialize;
 use syn::punctuated::Punctuated;
 use syn::visit::Visit;
 use syn::{Expr, ExprAwait, ExprCall, ExprMethodCall, ExprPath, Token};
@@ -19,6 +22,10 @@ use syn::{Expr, ExprAwait, ExprCall, ExprMethodCall, ExprPath, Token};
 /// - **Diesel**: `.load()`, `.first()`, `.get_result()`, `.execute()` methods
 /// - **SeaORM**: `.find()`, `.one()`, `.all()`, Entity operations
 ///
+/// `[database] orm` in `cargo-perf.toml` limits detection to the named ORMs.
+/// Their ambiguous query methods (`.first(conn)`, `.one(db)`) are then
+/// flagged whenever they get a single executor-like argument.
+///
 /// # Example
 /// ```rust,ignore
 /// // Bad: N+1 query
@@ -52,11 +59,61 @@ impl Rule for NPlusOneQueryRule {
         Severity::Error
     }
 
+    fn category(&self) -> Category {
+        Category::Database
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["database", "latency"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale: "Executing database queries inside loops causes N+1 query problems: for N \
+                items, N+1 round trips are made instead of one or two batch queries.",
+            bad_example: Some(
+                r#"for user_id in user_ids {
+    let user = sqlx::query!("SELECT * FROM users WHERE id = ?", user_id)
+        .fetch_one(&pool)
+        .await?;
+}"#,
+            ),
+            good_example: Some(
+                r#"let users = sqlx::query!("SELECT * FROM users WHERE id = ANY($1)", &user_ids)
+    .fetch_all(&pool)
+    .await?;"#,
+            ),
+            impact: Some("100 items = 101 queries instead of 1; often 50-100x slower."),
+            related: &["unbounded-spawn"],
+            suppression: Some(
+                "Set `[database] orm` in cargo-perf.toml so only that ORM's \
+                query methods are checked; this also catches its ambiguous \
+                methods such as Diesel's `.first(conn)`.",
+            ),
+            autofix: false,
+        }
+    }
+
+    fn validate_options(&self, options: &RuleOptions) -> Result<(), String> {
+        options.parse::<NPlusOneOptions>().map(|_| ())
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
+        let options: NPlusOneOptions = ctx.rule_options(self.id());
         let mut visitor = NPlusOneVisitor {
             ctx,
             diagnostics: Vec::new(),
             state: VisitorState::new(),
+            imports: ImportOracle::from_context(ctx),
+            options: &options,
         };
         visitor.visit_file(ctx.ast);
         // A single query statement (e.g. `query(..).bind(..).fetch_one(..)`) can
@@ -72,6 +129,18 @@ struct NPlusOneVisitor<'a> {
     ctx: &'a AnalysisContext<'a>,
     diagnostics: Vec<Diagnostic>,
     state: VisitorState,
+    imports: ImportOracle,
+    options: &'a NPlusOneOptions,
+}
+
+/// Options from `[rules.n-plus-one-query]`, for in-house data access layers.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct NPlusOneOptions {
+    /// Method names that always run a query, e.g. `fetch_by_id`.
+    extra_query_methods: Vec<String>,
+    /// Paths of functions that run a query, e.g. `crate::db::load_user`.
+    extra_query_functions: Vec<String>,
 }
 
 /// SQLx function calls that indicate a query
@@ -108,11 +177,88 @@ const SEAORM_UNAMBIGUOUS_METHODS: &[&str] = &[
 ];
 
 /// SeaORM method calls that need receiver validation
-const SEAORM_AMBIGUOUS_METHODS: &[&str] = &["one", "all", "find"];
+const SEAORM_AMBIGUOUS_METHODS: &[&str] = &["one", "all"];
+
+/// SeaORM query-building methods; they take no executor, so only a
+/// corroborated receiver or connection argument marks them as queries
+const SEAORM_BUILDER_METHODS: &[&str] = &["find"];
 
 /// Entity operations that need receiver validation
 const AMBIGUOUS_OPERATIONS: &[&str] = &["insert", "update", "delete", "save", "execute"];
 
+/// SQLx method calls that need receiver validation
+const SQLX_AMBIGUOUS_METHODS: &[&str] = &["execute"];
+
+/// A table of methods belonging to one ORM.
+struct OrmMethods {
+    orm: Orm,
+    methods: &'static [&'static str],
+    /// The methods run a query. When the project configures `orm`, a call
+    /// with a single executor-shaped argument is corroboration enough.
+    query: bool,
+    hint: &'static str,
+}
+
+const SQLX_HINT: &str = "Consider using WHERE ... IN or ANY() for batch fetching.";
+const DIESEL_HINT: &str = "Consider using .filter(column.eq_any(&ids)) for batch operations.";
+const SEAORM_HINT: &str =
+    "Consider using Entity::find().filter(Column::Id.is_in(ids)) for batch fetching.";
+
+/// Method tables in lookup order; the first table of an ORM in use that
+/// lists the method decides the hint.
+const ORM_METHODS: &[OrmMethods] = &[
+    OrmMethods {
+        orm: Orm::Sqlx,
+        methods: SQLX_FETCH_METHODS,
+        query: true,
+        hint: SQLX_HINT,
+    },
+    OrmMethods {
+        orm: Orm::Diesel,
+        methods: DIESEL_UNAMBIGUOUS_METHODS,
+        query: true,
+        hint: DIESEL_HINT,
+    },
+    OrmMethods {
+        orm: Orm::Diesel,
+        methods: DIESEL_AMBIGUOUS_METHODS,
+        query: true,
+        hint: DIESEL_HINT,
+    },
+    OrmMethods {
+        orm: Orm::SeaOrm,
+        methods: SEAORM_UNAMBIGUOUS_METHODS,
+        query: true,
+        hint: SEAORM_HINT,
+    },
+    OrmMethods {
+        orm: Orm::SeaOrm,
+        methods: SEAORM_AMBIGUOUS_METHODS,
+        query: true,
+        hint: SEAORM_HINT,
+    },
+    OrmMethods {
+        orm: Orm::SeaOrm,
+        methods: SEAORM_BUILDER_METHODS,
+        query: false,
+        hint: SEAORM_HINT,
+    },
+    // `insert`/`save` are just as often collection methods taking one
+    // argument, so these always need the usual corroboration.
+    OrmMethods {
+        orm: Orm::SeaOrm,
+        methods: AMBIGUOUS_OPERATIONS,
+        query: false,
+        hint: "Consider using Entity::insert_many() or batch operations.",
+    },
+    OrmMethods {
+        orm: Orm::Sqlx,
+        methods: SQLX_AMBIGUOUS_METHODS,
+        query: true,
+        hint: SQLX_HINT,
+    },
+];
+
 /// Method names distinctive enough to an ORM that an ORM import in the file is
 /// sufficient corroboration. Deliberately excludes ubiquitous names like `load`
 /// (atomics), `first`/`find`/`one`/`all` (iterators), and `insert` (collections).
@@ -157,6 +303,7 @@ impl NPlusOneVisitor<'_> {
                     .to_string(),
             ),
             fix: None,
+            target: None,
         });
     }
 
@@ -179,6 +326,22 @@ impl NPlusOneVisitor<'_> {
         }
     }
 
+    fn check_extra_query_function(&mut self, path_str: &str, span: proc_macro2::Span) {
+        if self.options.extra_query_functions.is_empty() {
+            return;
+        }
+        let canon = self.imports.canonicalize(path_str);
+        if self
+            .options
+            .extra_query_functions
+            .iter()
+            .any(|extra| matches_configured_path(&canon, extra))
+        {
+            let name = path_str.rsplit("::").next().unwrap_or(path_str);
+            self.report_diagnostic(span, "Batch the lookups into one query.", name);
+        }
+    }
+
     fn check_diesel_call(&mut self, path_str: &str, span: proc_macro2::Span) {
         // Check for diesel::insert_into, diesel::update, diesel::delete
         for &op in DIESEL_OPERATIONS {
@@ -205,57 +368,81 @@ impl NPlusOneVisitor<'_> {
         receiver: &Expr,
         args: &Punctuated<Expr, Token![,]>,
     ) {
+        // Configured in-house query methods need no corroboration
+        if self
+            .options
+            .extra_query_methods
+            .iter()
+            .any(|extra| extra == method_name)
+        {
+            self.report_diagnostic(span, "Batch the lookups into one query.", method_name);
+            return;
+        }
+
+        let database = &self.ctx.config.database;
+        let Some(table) = ORM_METHODS
+            .iter()
+            .find(|table| database.uses(table.orm) && table.methods.contains(&method_name))
+        else {
+            return;
+        };
+
         // Every N+1 detection requires corroboration that this really is a
         // database call. A bare method name is far too ambiguous: `load` is an
         // atomic read, `first`/`find`/`one`/`all` are iterator methods, `insert`
         // is a HashMap method. We corroborate via:
         //   * a receiver that looks like a query-builder chain, or
         //   * a database connection / pool passed as an argument, or
-        //   * for rare ORM-specific method names, an ORM import in the file.
+        //   * for rare ORM-specific method names, an ORM import in the file, or
+        //   * when the project names its ORM in `[database] orm`, that ORM's
+        //     query method called with a single executor-shaped argument.
         let strong = Self::looks_like_db_operation(receiver, 0, self.orm_imported())
             || Self::has_db_connection_arg(args);
-        let corroborated =
-            strong || (ORM_SPECIFIC_METHODS.contains(&method_name) && self.orm_imported());
-        if !corroborated {
-            return;
-        }
-
-        if SQLX_FETCH_METHODS.contains(&method_name) {
-            self.report_diagnostic(
-                span,
-                "Consider using WHERE ... IN or ANY() for batch fetching.",
-                method_name,
-            );
-        } else if DIESEL_UNAMBIGUOUS_METHODS.contains(&method_name)
-            || DIESEL_AMBIGUOUS_METHODS.contains(&method_name)
-        {
-            self.report_diagnostic(
-                span,
-                "Consider using .filter(column.eq_any(&ids)) for batch operations.",
-                method_name,
-            );
-        } else if SEAORM_UNAMBIGUOUS_METHODS.contains(&method_name)
-            || SEAORM_AMBIGUOUS_METHODS.contains(&method_name)
-        {
-            self.report_diagnostic(
-                span,
-                "Consider using Entity::find().filter(Column::Id.is_in(ids)) for batch fetching.",
-                method_name,
-            );
-        } else if AMBIGUOUS_OPERATIONS.contains(&method_name) {
-            self.report_diagnostic(
-                span,
-                "Consider using Entity::insert_many() or batch operations.",
-                method_name,
-            );
+        let corroborated = strong
+            || (ORM_SPECIFIC_METHODS.contains(&method_name) && self.orm_imported())
+            || (table.query && !database.orm.is_empty() && Self::has_executor_arg(args));
+        if corroborated {
+            self.report_diagnostic(span, table.hint, method_name);
         }
     }
 
     /// Whether the file imports a known ORM crate. Used only as a weak
     /// corroboration signal for rare, ORM-specific method names.
     fn orm_imported(&self) -> bool {
+        let database = &self.ctx.config.database;
         let src = self.ctx.source;
-        src.contains("use diesel") || src.contains("use sqlx") || src.contains("use sea_orm")
+        (database.uses(Orm::Diesel) && src.contains("use diesel"))
+            || (database.uses(Orm::Sqlx) && src.contains("use sqlx"))
+            || (database.uses(Orm::SeaOrm) && src.contains("use sea_orm"))
+    }
+
+    /// Check if the call's only argument could be a connection or pool.
+    ///
+    /// Diesel's `.first(conn)` and SeaORM's `.one(db)`/`.all(db)` take exactly
+    /// one executor, whatever it is named; `.first()` on a slice takes none and
+    /// `Iterator::all`/`find` take a closure. Literals and multi-segment paths
+    /// (`Ordering::Relaxed`) are constants, not executors.
+    fn has_executor_arg(args: &Punctuated<Expr, Token![,]>) -> bool {
+        if args.len() != 1 {
+            return false;
+        }
+        // `&mut *conn` reborrows a pooled connection; a bare `*id` is a value.
+        let arg = match &args[0] {
+            Expr::Reference(ref_expr) => match &*ref_expr.expr {
+                Expr::Unary(syn::ExprUnary {
+                    op: syn::UnOp::Deref(_),
+                    expr,
+                    ..
+                }) => &**expr,
+                inner => inner,
+            },
+            other => other,
+        };
+        match arg {
+            Expr::Path(path) => path.qself.is_none() && path.path.segments.len() == 1,
+            Expr::Field(_) | Expr::MethodCall(_) | Expr::Call(_) | Expr::Try(_) => true,
+            _ => false,
+        }
     }
 
     /// Check if any argument looks like a database connection.
@@ -449,23 +636,26 @@ impl<'ast> Visit<'ast> for NPlusOneVisitor<'_> {
                     .collect::<Vec<_>>()
                     .join("::");
 
+                let span = path
+                    .segments
+                    .last()
+                    .map(|s| s.ident.span())
+                    .unwrap_or_else(proc_macro2::Span::call_site);
+
+                let database = &self.ctx.config.database;
+
                 // Check for SQLx calls
-                self.check_sqlx_call(
-                    &path_str,
-                    path.segments
-                        .last()
-                        .map(|s| s.ident.span())
-                        .unwrap_or_else(proc_macro2::Span::call_site),
-                );
+                if database.uses(Orm::Sqlx) {
+                    self.check_sqlx_call(&path_str, span);
+                }
 
                 // Check for Diesel calls
-                self.check_diesel_call(
-                    &path_str,
-                    path.segments
-                        .last()
-                        .map(|s| s.ident.span())
-                        .unwrap_or_else(proc_macro2::Span::call_site),
-                );
+                if database.uses(Orm::Diesel) {
+                    self.check_diesel_call(&path_str, span);
+                }
+
+                // Check for configured in-house query functions
+                self.check_extra_query_function(&path_str, span);
             }
         }
         syn::visit::visit_expr_call(self, node);
@@ -493,12 +683,19 @@ mod tests {
     use std::path::Path;
 
     fn check_code(source: &str) -> Vec<Diagnostic> {
+        check_with_config(source, &Config::default())
+    }
+
+    fn check_with_config(source: &str, config: &Config) -> Vec<Diagnostic> {
         let ast = syn::parse_file(source).expect("Failed to parse test code");
-        let config = Config::default();
-        let ctx = AnalysisContext::new(Path::new("test.rs"), source, &ast, &config);
+        let ctx = AnalysisContext::new(Path::new("test.rs"), source, &ast, config);
         NPlusOneQueryRule.check(&ctx)
     }
 
+    fn orm_config(orm: &str) -> Config {
+        toml::from_str(&format!("[database]\norm = {}\n", orm)).unwrap()
+    }
+
     // SQLx tests
     #[test]
     fn test_detects_sqlx_query_in_loop() {
@@ -1020,4 +1217,75 @@ mod tests {
         assert_eq!(rule.name(), "N+1 Query Detection");
         assert!(!rule.description().is_empty());
     }
+
+    #[test]
+    fn test_extra_query_methods_and_functions() {
+        let source = r#"
+            async fn f(repo: &Repo, ids: &[u32]) {
+                for id in ids {
+                    let user = repo.fetch_by_id(*id).await;
+                    let orders = crate::dal::load_orders(*id);
+                }
+            }
+        "#;
+        assert!(check_code(source).is_empty());
+
+        let config: Config = toml::from_str(
+            r#"
+[rules.n-plus-one-query]
+extra-query-methods = ["fetch_by_id"]
+extra-query-functions = ["dal::load_orders"]
+"#,
+        )
+        .unwrap();
+        let diagnostics = check_with_config(source, &config);
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert!(diagnostics[0].message.contains("`fetch_by_id`"));
+        assert!(diagnostics[1].message.contains("`load_orders`"));
+    }
+
+    #[test]
+    fn test_configured_orm_flags_its_ambiguous_methods() {
+        let source = r#"
+            fn bad(pg: &mut PgConnection, db: &DatabaseConnection, ids: &[i32]) {
+                for id in ids {
+                    let user = users.find(*id).first::<User>(&mut *pg);
+                    let post = posts.filter(post_id.eq(*id)).first(pg);
+                    let rows = Post::find_by_author(*id).all(self.db());
+                }
+            }
+        "#;
+        assert!(check_code(source).is_empty());
+
+        let lines = |config: &Config| -> Vec<usize> {
+            check_with_config(source, config)
+                .iter()
+                .map(|d| d.line)
+                .collect()
+        };
+        assert_eq!(lines(&orm_config("\"diesel\"")), vec![4, 5]);
+        assert_eq!(lines(&orm_config("\"sea-orm\"")), vec![6]);
+        assert_eq!(
+            lines(&orm_config("[\"diesel\", \"sea-orm\"]")),
+            vec![4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn test_configured_orm_ignores_other_orms_and_iterators() {
+        let source = r#"
+            async fn f(pool: &PgPool, items: &[Item], flag: &AtomicBool) {
+                for id in 0..10 {
+                    let _ = sqlx::query("SELECT 1").fetch_one(pool).await;
+                    let _ = items.first();
+                    let _ = items.iter().all(|item| item.ok);
+                    let _ = flag.load(Ordering::Relaxed);
+                    let _ = names.insert(name);
+                }
+            }
+        "#;
+        assert_eq!(check_code(source).len(), 1);
+        assert!(check_with_config(source, &orm_config("\"diesel\"")).is_empty());
+        assert_eq!(check_with_config(source, &orm_config("\"sqlx\"")).len(), 1);
+    }
 }
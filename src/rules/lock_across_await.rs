@@ -4,7 +4,7 @@
 //! in async code because the guard isn't released while waiting.
 
 use super::visitor::VisitorState;
-use super::{Diagnostic, Rule, Severity};
+use super::{builtin_doc_url, Category, Confidence, Diagnostic, Explanation, Rule, Severity};
 use crate::engine::AnalysisContext;
 use std::collections::HashMap;
 use syn::visit::Visit;
@@ -30,6 +30,52 @@ impl Rule for LockAcrossAwaitRule {
         Severity::Error
     }
 
+    fn category(&self) -> Category {
+        Category::Async
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["deadlock", "locks"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale:
+                "Holding a synchronous `MutexGuard` or `RwLock` guard across an `.await` point \
+                can deadlock: the task may be suspended while holding the lock, and any task \
+                that then tries to take it blocks the runtime thread. Async lock guards held \
+                across `.await` are reported as warnings, since they only serialize tasks.",
+            bad_example: Some(
+                r#"async fn update(mutex: &std::sync::Mutex<Data>) {
+    let mut guard = mutex.lock().unwrap();
+    do_async_work().await; // guard still held: deadlock risk
+    *guard = new_value;
+}"#,
+            ),
+            good_example: Some(
+                r#"async fn update(mutex: &std::sync::Mutex<Data>) {
+    {
+        let mut guard = mutex.lock().unwrap();
+        *guard = new_value;
+    } // guard dropped before the await
+    do_async_work().await;
+}"#,
+            ),
+            impact: Some("Can hang the whole service in production."),
+            related: &["async-block-in-async", "mutex-in-loop"],
+            suppression: None,
+            autofix: false,
+        }
+    }
+
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic> {
         let mut visitor = LockAcrossAwaitVisitor {
             ctx,
@@ -314,6 +360,7 @@ impl LockAcrossAwaitVisitor<'_> {
             end_column: None,
             suggestion: Some(suggestion),
             fix: None,
+            target: None,
         });
     }
 }
@@ -6,9 +6,13 @@ pub mod lock_across_await;
 pub mod memory_rules;
 pub mod registry;
 pub mod resolve;
+pub mod selection;
+pub mod suppression_rules;
 pub mod visitor;
 
+use crate::config::RuleOptions;
 use crate::engine::AnalysisContext;
+use crate::workspace::TargetInfo;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -81,16 +85,20 @@ pub struct Diagnostic {
     pub end_column: Option<usize>,
     pub suggestion: Option<String>,
     pub fix: Option<Fix>,
+    /// Package and target the file is compiled into, when analyzing a Cargo
+    /// workspace.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<TargetInfo>,
 }
 
 /// An auto-fix for a diagnostic
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Fix {
     pub description: String,
     pub replacements: Vec<Replacement>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Replacement {
     pub file_path: PathBuf,
     pub start_byte: usize,
@@ -98,7 +106,121 @@ pub struct Replacement {
     pub new_text: String,
 }
 
+/// The area of performance or correctness a rule covers.
+///
+/// Category names double as group names in rule selections (`--rules async`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Category {
+    Async,
+    Database,
+    Memory,
+    Iterator,
+    Allocation,
+    /// Checks of cargo-perf's own inline suppressions.
+    Suppression,
+    /// Rules that fit none of the above; the default for plugin rules.
+    Other,
+}
+
+impl Category {
+    /// Every category, in display order.
+    pub const ALL: &'static [Category] = &[
+        Category::Async,
+        Category::Database,
+        Category::Memory,
+        Category::Iterator,
+        Category::Allocation,
+        Category::Suppression,
+        Category::Other,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Category::Async => "async",
+            Category::Database => "database",
+            Category::Memory => "memory",
+            Category::Iterator => "iterator",
+            Category::Allocation => "allocation",
+            Category::Suppression => "suppression",
+            Category::Other => "other",
+        }
+    }
+
+    /// Look up a category by its name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|c| c.as_str() == name)
+    }
+}
+
+impl std::fmt::Display for Category {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How likely a diagnostic from a rule is a real problem.
+///
+/// `--strict` runs only [`Confidence::High`] rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    Low,
+    Medium,
+    High,
+}
+
+impl Confidence {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Confidence::Low => "low",
+            Confidence::Medium => "medium",
+            Confidence::High => "high",
+        }
+    }
+}
+
+impl std::fmt::Display for Confidence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Long-form documentation of a rule, rendered by `cargo perf explain`.
+///
+/// Code examples are plain Rust snippets without surrounding fences.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Explanation {
+    /// Why the pattern matters.
+    pub rationale: &'static str,
+    /// Code the rule reports.
+    pub bad_example: Option<&'static str>,
+    /// The recommended alternative.
+    pub good_example: Option<&'static str>,
+    /// Expected performance impact of fixing it.
+    pub impact: Option<&'static str>,
+    /// IDs of related rules.
+    pub related: &'static [&'static str],
+    /// When suppressing the rule is reasonable, beyond the generic syntax.
+    pub suppression: Option<&'static str>,
+    /// Whether `cargo perf fix` can rewrite some of the rule's findings.
+    pub autofix: bool,
+}
+
+/// Documentation URL of a built-in rule.
+pub(crate) fn builtin_doc_url(rule_id: &str) -> String {
+    format!(
+        "https://github.com/cschuman/cargo-perf/blob/main/docs/rules.md#{}",
+        rule_id
+    )
+}
+
 /// The Rule trait - implement this to add new checks
+///
+/// Only `id`, `name`, `description`, `default_severity` and `check` are
+/// required. The metadata methods have defaults so plugin rules keep working,
+/// but overriding them lets a rule take part in `--strict`, rule groups and
+/// the documentation shown by `explain`, SARIF and the LSP.
 pub trait Rule: Send + Sync {
     /// Unique identifier for this rule (e.g., "async-block-in-async")
     fn id(&self) -> &'static str;
@@ -112,6 +234,46 @@ pub trait Rule: Send + Sync {
     /// Default severity level
     fn default_severity(&self) -> Severity;
 
+    /// Category the rule belongs to
+    fn category(&self) -> Category {
+        Category::Other
+    }
+
+    /// How reliable the rule's diagnostics are
+    fn confidence(&self) -> Confidence {
+        Confidence::Medium
+    }
+
+    /// Free-form keywords, e.g. `["blocking", "tokio"]`
+    fn tags(&self) -> &'static [&'static str] {
+        &[]
+    }
+
+    /// Link to the rule's documentation
+    fn doc_url(&self) -> Option<String> {
+        None
+    }
+
+    /// Long-form explanation: rationale, examples, impact and related rules
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale: self.description(),
+            ..Explanation::default()
+        }
+    }
+
+    /// Check the `[rules.<id>]` options table when the config is loaded.
+    ///
+    /// Rules that take options parse them into their options type here; the
+    /// default rejects any option.
+    fn validate_options(&self, options: &RuleOptions) -> std::result::Result<(), String> {
+        if options.is_empty() {
+            Ok(())
+        } else {
+            Err(format!("`{}` takes no options", self.id()))
+        }
+    }
+
     /// Run the check and return diagnostics
     fn check(&self, ctx: &AnalysisContext) -> Vec<Diagnostic>;
 }
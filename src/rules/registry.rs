@@ -11,6 +11,7 @@ use super::database_rules::NPlusOneQueryRule;
 use super::iter_rules::CollectThenIterateRule;
 use super::lock_across_await::LockAcrossAwaitRule;
 use super::memory_rules::{CloneInLoopRule, RegexInLoopRule};
+use super::suppression_rules::UnusedSuppressionRule;
 use super::Rule;
 use std::collections::HashMap;
 use std::sync::LazyLock;
@@ -37,6 +38,8 @@ static RULES: LazyLock<Vec<Box<dyn Rule>>> = LazyLock::new(|| {
         Box::new(FormatInLoopRule),
         Box::new(StringConcatLoopRule),
         Box::new(MutexLockInLoopRule),
+        // Suppression rules
+        Box::new(UnusedSuppressionRule),
     ]
 });
 
@@ -107,6 +110,23 @@ mod tests {
         assert!(!has_rule("fake-rule"));
     }
 
+    #[test]
+    fn test_builtin_rules_have_metadata() {
+        use crate::rules::Category;
+
+        for rule in all_rules() {
+            assert_ne!(rule.category(), Category::Other, "{}", rule.id());
+            assert!(!rule.tags().is_empty(), "{}", rule.id());
+            assert!(rule.doc_url().unwrap().ends_with(rule.id()));
+            assert_ne!(
+                rule.explanation().rationale,
+                rule.description(),
+                "{}",
+                rule.id()
+            );
+        }
+    }
+
     #[test]
     fn test_rule_ids() {
         let ids: Vec<_> = rule_ids().collect();
@@ -124,5 +144,6 @@ mod tests {
         assert!(ids.contains(&"format-in-loop"));
         assert!(ids.contains(&"string-concat-loop"));
         assert!(ids.contains(&"mutex-in-loop"));
+        assert!(ids.contains(&"unused-suppression"));
     }
 }
@@ -9,12 +9,16 @@
 //! path. This oracle collects exactly that evidence, once per file, so rules can
 //! gate their matchers on it instead of matching bare names.
 //!
-//! It deliberately stops at *in-file* resolution. It does not follow `use` into
-//! other modules, resolve glob imports to concrete names, or infer receiver /
-//! return types — those need real name resolution. Names with no local evidence
-//! are reported as [`Origin::Unknown`] and the caller decides the precision /
-//! recall tradeoff.
-
+//! On its own it stops at *in-file* resolution. When the file is analyzed as
+//! part of a crate, [`ImportOracle::from_context`] additionally rewrites each
+//! `use` through the crate-wide [`CrateGraph`](crate::engine::CrateGraph), so an
+//! import of a crate-local re-export (`pub use std::fs::read_to_string as
+//! slurp;` in another module) canonicalizes to the std path it names. It never
+//! resolves glob imports to concrete names or infers receiver / return types —
+//! those need real name resolution. Names with no evidence are reported as
+//! [`Origin::Unknown`] and the caller decides the precision / recall tradeoff.
+
+use crate::engine::AnalysisContext;
 use std::collections::{HashMap, HashSet};
 use syn::{Fields, File, Item, ReturnType, Type, UseTree};
 
@@ -62,6 +66,16 @@ impl ImportOracle {
         oracle
     }
 
+    /// Build the oracle for the file being analyzed, following its imports
+    /// through the crate graph when one is attached to the context.
+    pub fn from_context(ctx: &AnalysisContext) -> Self {
+        let mut oracle = Self::from_file(ctx.ast);
+        if let Some(graph) = ctx.crate_graph() {
+            oracle.use_map.extend(graph.resolved_imports(ctx.file_path));
+        }
+        oracle
+    }
+
     fn record_item(&mut self, item: &Item) {
         match item {
             Item::Use(u) => self.record_use_tree(&u.tree, String::new()),
@@ -231,10 +245,44 @@ pub fn is_std_root(path: &str) -> bool {
     )
 }
 
+/// True if a canonical call path names a user-configured path (rule options
+/// such as `extra-blocking`). The configured path matches on a `::` boundary
+/// from the end, and a leading `crate::` on either side is ignored, so
+/// `crate::rt::spawn_task` matches a call to `rt::spawn_task` from the crate
+/// root and `legacy::fetch` matches `my_lib::legacy::fetch`.
+pub fn matches_configured_path(canon: &str, configured: &str) -> bool {
+    let canon = canon.strip_prefix("crate::").unwrap_or(canon);
+    let configured = configured.strip_prefix("crate::").unwrap_or(configured);
+    canon
+        .strip_suffix(configured)
+        .is_some_and(|prefix| prefix.is_empty() || prefix.ends_with("::"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn configured_paths_match_on_segment_boundaries() {
+        assert!(matches_configured_path(
+            "crate::legacy::fetch",
+            "crate::legacy::fetch"
+        ));
+        assert!(matches_configured_path(
+            "legacy::fetch",
+            "crate::legacy::fetch"
+        ));
+        assert!(matches_configured_path(
+            "my_lib::legacy::fetch",
+            "legacy::fetch"
+        ));
+        assert!(!matches_configured_path(
+            "my_legacy::fetch",
+            "legacy::fetch"
+        ));
+        assert!(!matches_configured_path("fetch", "legacy::fetch"));
+    }
+
     fn oracle(src: &str) -> ImportOracle {
         ImportOracle::from_file(&syn::parse_file(src).expect("parse"))
     }
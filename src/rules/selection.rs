@@ -0,0 +1,217 @@
+//! Rule selection - which rules a run executes.
+//!
+//! A selection is an include list and an exclude list of patterns. Each
+//! pattern is a rule ID (`regex-in-loop`), a [`Category`] name (`async`,
+//! `allocation`, ...), or a glob over rule IDs where `*` matches any run of
+//! characters and `?` a single one (`*-no-capacity`). A rule runs if the
+//! include list is empty or one of its patterns matches, no exclude pattern
+//! matches, and its [`Confidence`] meets the minimum (`--strict`).
+
+use super::{registry, Category, Confidence, Rule};
+use crate::error::{Error, Result};
+
+/// Include and exclude patterns over rule IDs.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleSelection {
+    include: Vec<String>,
+    exclude: Vec<String>,
+    min_confidence: Option<Confidence>,
+}
+
+impl RuleSelection {
+    /// A selection that runs every rule.
+    pub fn all() -> Self {
+        Self::default()
+    }
+
+    /// Add comma-separated include patterns (the `--rules` flag).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a pattern matches no known rule or group.
+    pub fn include(mut self, spec: &str) -> Result<Self> {
+        self.include.extend(parse_patterns(spec)?);
+        Ok(self)
+    }
+
+    /// Add comma-separated exclude patterns (the `--exclude-rules` flag).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Config`] if a pattern matches no known rule or group.
+    pub fn exclude(mut self, spec: &str) -> Result<Self> {
+        self.exclude.extend(parse_patterns(spec)?);
+        Ok(self)
+    }
+
+    /// Only run rules with at least this confidence.
+    pub fn with_min_confidence(mut self, confidence: Confidence) -> Self {
+        self.min_confidence = Some(confidence);
+        self
+    }
+
+    /// Whether `rule` is selected.
+    pub fn is_selected(&self, rule: &dyn Rule) -> bool {
+        let matches = |patterns: &[String]| patterns.iter().any(|p| pattern_matches(p, rule));
+        (self.include.is_empty() || matches(&self.include))
+            && !matches(&self.exclude)
+            && self
+                .min_confidence
+                .is_none_or(|min| rule.confidence() >= min)
+    }
+
+    /// The selected rules out of `rules`, in their original order.
+    pub fn select<'r>(&self, rules: impl IntoIterator<Item = &'r dyn Rule>) -> Vec<&'r dyn Rule> {
+        rules
+            .into_iter()
+            .filter(|rule| self.is_selected(*rule))
+            .collect()
+    }
+}
+
+/// Split a comma-separated list and check every entry names something.
+fn parse_patterns(spec: &str) -> Result<Vec<String>> {
+    spec.split(',')
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(|pattern| {
+            let known = Category::from_name(pattern).is_some()
+                || registry::all_rules()
+                    .iter()
+                    .any(|rule| pattern_matches(pattern, rule.as_ref()));
+            if known {
+                Ok(pattern.to_string())
+            } else {
+                let groups: Vec<&str> = Category::ALL.iter().map(|c| c.as_str()).collect();
+                Err(Error::config(format!(
+                    "`{}` matches no rule or group (groups: {}; see `cargo perf rules`)",
+                    pattern,
+                    groups.join(", ")
+                )))
+            }
+        })
+        .collect()
+}
+
+fn pattern_matches(pattern: &str, rule: &dyn Rule) -> bool {
+    if let Some(category) = Category::from_name(pattern) {
+        return rule.category() == category;
+    }
+    wildcard_match(pattern.as_bytes(), rule.id().as_bytes())
+}
+
+/// Match `text` against a pattern where `*` is any run and `?` any one byte.
+pub(crate) fn wildcard_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    // Position after the last `*` and the text position it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((star_p, star_t)) => {
+                    backtrack = Some((star_p, star_t + 1));
+                    p = star_p;
+                    t = star_t + 1;
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selected(selection: &RuleSelection) -> Vec<&'static str> {
+        registry::all_rules()
+            .iter()
+            .filter(|rule| selection.is_selected(rule.as_ref()))
+            .map(|rule| rule.id())
+            .collect()
+    }
+
+    fn is_selected(selection: &RuleSelection, id: &str) -> bool {
+        selection.is_selected(registry::get_rule(id).unwrap())
+    }
+
+    #[test]
+    fn test_all_selects_every_rule() {
+        assert_eq!(
+            selected(&RuleSelection::all()).len(),
+            registry::all_rules().len()
+        );
+    }
+
+    #[test]
+    fn test_include_ids_groups_and_globs() {
+        let selection = RuleSelection::all()
+            .include("regex-in-loop, database,*-no-capacity")
+            .unwrap();
+        assert_eq!(
+            selected(&selection),
+            vec![
+                "n-plus-one-query",
+                "regex-in-loop",
+                "vec-no-capacity",
+                "hashmap-no-capacity",
+                "string-no-capacity",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_exclude_wins_over_include() {
+        let selection = RuleSelection::all()
+            .include("async")
+            .unwrap()
+            .exclude("unbounded-*")
+            .unwrap();
+        assert_eq!(
+            selected(&selection),
+            vec!["async-block-in-async", "lock-across-await"]
+        );
+
+        let selection = RuleSelection::all().exclude("allocation").unwrap();
+        assert!(!is_selected(&selection, "format-in-loop"));
+        assert!(is_selected(&selection, "clone-in-hot-loop"));
+    }
+
+    #[test]
+    fn test_min_confidence() {
+        let strict = RuleSelection::all().with_min_confidence(Confidence::High);
+        assert_eq!(
+            selected(&strict),
+            vec![
+                "async-block-in-async",
+                "lock-across-await",
+                "unused-suppression"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_pattern_is_an_error() {
+        let err = RuleSelection::all().include("async,nope-*").unwrap_err();
+        assert!(err.to_string().contains("nope-*"));
+    }
+
+    #[test]
+    fn test_wildcard_match() {
+        assert!(wildcard_match(b"*-no-capacity", b"vec-no-capacity"));
+        assert!(wildcard_match(b"*loop*", b"clone-in-hot-loop"));
+        assert!(wildcard_match(b"vec-no-capacit?", b"vec-no-capacity"));
+        assert!(wildcard_match(b"*", b""));
+        assert!(!wildcard_match(b"*-no-capacity", b"format-in-loop"));
+        assert!(!wildcard_match(b"vec", b"vec-no-capacity"));
+    }
+}
@@ -0,0 +1,564 @@
+//! Rule for keeping inline suppressions honest.
+//!
+//! A suppression that no longer suppresses anything, or that names a rule
+//! that does not exist, hides nothing today but may hide a real finding
+//! later; an `#[expect]` whose finding no longer fires is reported the same
+//! way. With `require-reason`, suppressions must also say why. Whether a
+//! suppression was used is only known once every other rule has run, so the
+//! engine reports these after the rest of a file's checks.
+
+use super::registry;
+use super::{builtin_doc_url, Category, Confidence, Diagnostic, Explanation, Fix, Replacement};
+use super::{Rule, Severity};
+use crate::config::RuleOptions;
+use crate::engine::AnalysisContext;
+use crate::suppression::{MarkerProblem, Suppression, SuppressionExtractor, SuppressionKind};
+use crate::Config;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// ID of [`UnusedSuppressionRule`].
+pub const UNUSED_SUPPRESSION: &str = "unused-suppression";
+
+/// Detects suppressions that suppressed nothing or name unknown rules
+pub struct UnusedSuppressionRule;
+
+/// Options from `[rules.unused-suppression]`.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, rename_all = "kebab-case", deny_unknown_fields)]
+struct UnusedSuppressionOptions {
+    /// Report suppressions that give no reason.
+    require_reason: bool,
+}
+
+impl Rule for UnusedSuppressionRule {
+    fn id(&self) -> &'static str {
+        UNUSED_SUPPRESSION
+    }
+
+    fn name(&self) -> &'static str {
+        "Unused Suppression"
+    }
+
+    fn description(&self) -> &'static str {
+        "Detects cargo-perf suppressions that suppressed nothing or name unknown rules"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn category(&self) -> Category {
+        Category::Suppression
+    }
+
+    fn confidence(&self) -> Confidence {
+        Confidence::High
+    }
+
+    fn tags(&self) -> &'static [&'static str] {
+        &["suppression", "hygiene"]
+    }
+
+    fn doc_url(&self) -> Option<String> {
+        Some(builtin_doc_url(self.id()))
+    }
+
+    fn explanation(&self) -> Explanation {
+        Explanation {
+            rationale:
+                "A `// cargo-perf-ignore` comment or `#[allow(cargo_perf::...)]` attribute that \
+                suppressed no finding is left over from code that has since changed, and one \
+                naming an unknown rule, often a typo, never suppressed anything. Either way it \
+                would silently hide the next real finding in its scope. A suppression only counts \
+                as unused when the rules it names ran; one for every rule only when all built-in \
+                rules ran. An `#[expect(cargo_perf::...)]` is reported once the finding it \
+                expects no longer fires. With `require-reason = true`, suppressions without a \
+                reason (`-- reason: ...` in a comment, `reason = \"...\"` in an attribute) are \
+                reported too, so each one explains itself to reviewers. A \
+                `cargo-perf-ignore-start` without an end marker, an end marker without a block \
+                and a `cargo-perf-ignore-file` below the first item suppress nothing and are \
+                reported as well.",
+            bad_example: Some(
+                r#"fn first(items: &[String]) -> Option<String> {
+    // cargo-perf-ignore: clone-in-loop
+    items.first().cloned()
+}"#,
+            ),
+            good_example: Some(
+                r#"fn first(items: &[String]) -> Option<String> {
+    items.first().cloned()
+}"#,
+            ),
+            impact: None,
+            related: &[],
+            suppression: Some(
+                "Suppress with `// cargo-perf-ignore: unused-suppression` on the line before a \
+                suppression that must stay, such as one for code behind a feature flag.",
+            ),
+            autofix: true,
+        }
+    }
+
+    fn validate_options(&self, options: &RuleOptions) -> Result<(), String> {
+        options.parse::<UnusedSuppressionOptions>().map(|_| ())
+    }
+
+    /// Reports nothing on its own: the engine calls `check_suppressions`
+    /// with the results of the other rules.
+    fn check(&self, _ctx: &AnalysisContext) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
+
+/// Whether `config` makes suppressions without a reason a finding.
+pub(crate) fn requires_reason(config: &Config) -> bool {
+    config
+        .rule_options
+        .get(UNUSED_SUPPRESSION)
+        .and_then(|options| options.parse::<UnusedSuppressionOptions>().ok())
+        .is_some_and(|options| options.require_reason)
+}
+
+/// Report the file's suppressions that suppressed nothing, name unknown
+/// rules, or, if the options require one, give no reason, and its stray
+/// block and file markers. Reports that other suppressions silence are left
+/// out.
+///
+/// `used` holds the `(suppression, rule)` pairs from
+/// [`SuppressionExtractor::matching`] that suppressed a diagnostic, `ran` the
+/// rules that ran on the file, and `known` every rule ID that exists.
+pub(crate) fn check_suppressions(
+    ctx: &AnalysisContext,
+    suppressions: &SuppressionExtractor,
+    used: &HashSet<(usize, usize)>,
+    ran: &HashSet<&str>,
+    known: &HashSet<&str>,
+) -> Vec<Diagnostic> {
+    let require_reason = requires_reason(ctx.config);
+    let all_ran = registry::rule_ids().all(|id| ran.contains(id));
+    let mut diagnostics = Vec::new();
+
+    for (index, suppression) in suppressions.suppressions().iter().enumerate() {
+        let mut drop = Vec::new();
+        let mut problems = Vec::new();
+        let expect = suppression.kind == SuppressionKind::Expect;
+        for (rule, rule_id) in suppression.rules.iter().enumerate() {
+            let unused = !used.contains(&(index, rule));
+            if rule_id == UNUSED_SUPPRESSION {
+                // Whether this one is used depends on the findings below
+                continue;
+            } else if rule_id == "all" {
+                if unused && all_ran {
+                    problems.push(if expect {
+                        "expected a finding, but none fired".to_string()
+                    } else {
+                        "suppresses all rules but suppressed nothing".to_string()
+                    });
+                    drop.push(rule);
+                }
+            } else if !known.contains(rule_id.as_str()) {
+                problems.push(match closest_rule(rule_id, known) {
+                    Some(close) => {
+                        format!("names unknown rule `{rule_id}` (did you mean `{close}`?)")
+                    }
+                    None => format!("names unknown rule `{rule_id}`"),
+                });
+                drop.push(rule);
+            } else if unused && ran.contains(rule_id.as_str()) {
+                problems.push(if expect {
+                    format!("expected a `{rule_id}` finding, but none fired")
+                } else {
+                    format!("suppressed no `{rule_id}` finding")
+                });
+                drop.push(rule);
+            }
+        }
+        let removes = drop.len() == suppression.rules.len();
+        if require_reason && suppression.reason.is_none() && !removes {
+            problems.push("gives no reason".to_string());
+        }
+        // Other suppressions can silence the report, but not the one it is
+        // about, such as a trailing comment on its own line
+        if problems.is_empty()
+            || suppressions
+                .matching(UNUSED_SUPPRESSION, suppression.line)
+                .any(|(other, _)| other != index)
+        {
+            continue;
+        }
+
+        let fix = (!drop.is_empty()).then(|| Fix {
+            description: if removes {
+                "Remove unused suppression".to_string()
+            } else {
+                "Remove unused rule IDs".to_string()
+            },
+            replacements: suppression
+                .without_rules(ctx.source, &drop)
+                .into_iter()
+                .map(|(range, new_text)| Replacement {
+                    file_path: ctx.file_path.to_path_buf(),
+                    start_byte: range.start,
+                    end_byte: range.end,
+                    new_text,
+                })
+                .collect(),
+        });
+        let suggestion = if removes {
+            "Remove the suppression"
+        } else if !drop.is_empty() {
+            "Remove the unused rule IDs from the suppression"
+        } else if suppression.kind == SuppressionKind::Comment {
+            "Say why after the rule IDs: `-- reason: ...`"
+        } else {
+            "Say why in the attribute: `reason = \"...\"`"
+        };
+        diagnostics.push(Diagnostic {
+            rule_id: UNUSED_SUPPRESSION,
+            severity: Severity::Warning,
+            message: format!("{} {}", describe(suppression), problems.join("; ")),
+            file_path: ctx.file_path.to_path_buf(),
+            line: suppression.line,
+            column: suppression.column,
+            end_line: None,
+            end_column: None,
+            suggestion: Some(suggestion.to_string()),
+            fix,
+            target: None,
+        });
+    }
+
+    for marker in suppressions.stray_markers() {
+        if suppressions
+            .matching(UNUSED_SUPPRESSION, marker.line)
+            .next()
+            .is_some()
+        {
+            continue;
+        }
+        let (message, suggestion) = match marker.problem {
+            MarkerProblem::UnclosedStart => (
+                "Suppression block has no `cargo-perf-ignore-end` and suppresses nothing",
+                "Add `// cargo-perf-ignore-end` where the block ends",
+            ),
+            MarkerProblem::UnmatchedEnd => (
+                "`cargo-perf-ignore-end` closes no suppression block",
+                "Remove the end marker",
+            ),
+            MarkerProblem::FileAfterItems => (
+                "`cargo-perf-ignore-file` below the first item suppresses nothing",
+                "Move it above the first item of the file",
+            ),
+        };
+        // Removing a start or file marker would drop what its author meant to
+        // suppress; an end marker without a block can simply go
+        let fix = (marker.problem == MarkerProblem::UnmatchedEnd).then(|| {
+            let range = marker.removal(ctx.source);
+            Fix {
+                description: "Remove stray end marker".to_string(),
+                replacements: vec![Replacement {
+                    file_path: ctx.file_path.to_path_buf(),
+                    start_byte: range.start,
+                    end_byte: range.end,
+                    new_text: String::new(),
+                }],
+            }
+        });
+        diagnostics.push(Diagnostic {
+            rule_id: UNUSED_SUPPRESSION,
+            severity: Severity::Warning,
+            message: message.to_string(),
+            file_path: ctx.file_path.to_path_buf(),
+            line: marker.line,
+            column: marker.column,
+            end_line: None,
+            end_column: None,
+            suggestion: Some(suggestion.to_string()),
+            fix,
+            target: None,
+        });
+    }
+
+    diagnostics
+}
+
+fn describe(suppression: &Suppression) -> &'static str {
+    match suppression.kind {
+        SuppressionKind::Comment => "Suppression comment",
+        SuppressionKind::Attribute => "`#[allow]` suppression",
+        SuppressionKind::Expect => "`#[expect]` suppression",
+    }
+}
+
+/// The known rule ID closest to `rule_id`, if it is close enough to be a typo.
+fn closest_rule<'k>(rule_id: &str, known: &HashSet<&'k str>) -> Option<&'k str> {
+    let max_distance = (rule_id.len() / 3).max(2);
+    known
+        .iter()
+        .map(|&id| (edit_distance(rule_id, id), id))
+        .filter(|&(distance, _)| distance <= max_distance)
+        .min()
+        .map(|(_, id)| id)
+}
+
+/// Levenshtein distance between two ASCII-ish strings, by bytes.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.as_bytes();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, &ca) in a.as_bytes().iter().enumerate() {
+        let mut current = Vec::with_capacity(b.len() + 1);
+        current.push(i + 1);
+        for (j, &cb) in b.iter().enumerate() {
+            let substitute = previous[j] + usize::from(ca != cb);
+            current.push(substitute.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::engine::file_analyzer::check_with_rules;
+    use crate::engine::AnalysisContext;
+    use crate::rules::{registry, Diagnostic};
+    use crate::Config;
+    use std::path::Path;
+
+    fn check(source: &str) -> Vec<Diagnostic> {
+        check_with_config(source, Config::default())
+    }
+
+    fn check_with_config(source: &str, config: Config) -> Vec<Diagnostic> {
+        let ast = syn::parse_file(source).unwrap();
+        let ctx = AnalysisContext::new(Path::new("test.rs"), source, &ast, &config);
+        check_with_rules(&ctx, registry::all_rules().iter().map(|r| r.as_ref()))
+            .into_iter()
+            .filter(|d| d.rule_id == super::UNUSED_SUPPRESSION)
+            .collect()
+    }
+
+    fn fixed(source: &str, diag: &Diagnostic) -> String {
+        let replacement = &diag.fix.as_ref().unwrap().replacements[0];
+        let mut fixed = source.to_string();
+        fixed.replace_range(
+            replacement.start_byte..replacement.end_byte,
+            &replacement.new_text,
+        );
+        fixed
+    }
+
+    #[test]
+    fn test_used_suppression_is_not_reported() {
+        let source = r#"
+fn f(items: &[String]) {
+    for item in items {
+        // cargo-perf-ignore: clone-in-hot-loop
+        let copy = item.clone();
+        drop(copy);
+    }
+}
+"#;
+        assert!(check(source).is_empty());
+    }
+
+    #[test]
+    fn test_unused_comment_is_removed() {
+        let source = "fn f() {\n    // cargo-perf-ignore: clone-in-hot-loop\n    let x = 1;\n}\n";
+        let diags = check(source);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].line, 2);
+        assert!(diags[0]
+            .message
+            .contains("suppressed no `clone-in-hot-loop` finding"));
+        assert_eq!(fixed(source, &diags[0]), "fn f() {\n    let x = 1;\n}\n");
+    }
+
+    #[test]
+    fn test_unknown_rule_suggests_closest() {
+        let source = r#"
+fn f(items: &[String]) {
+    for item in items {
+        // cargo-perf-ignore: clone-in-loop, clone-in-hot-loop
+        let copy = item.clone();
+        drop(copy);
+    }
+}
+"#;
+        let diags = check(source);
+        assert_eq!(diags.len(), 1);
+        assert!(diags[0]
+            .message
+            .contains("unknown rule `clone-in-loop` (did you mean `clone-in-hot-loop`?)"));
+        assert!(fixed(source, &diags[0]).contains("// cargo-perf-ignore: clone-in-hot-loop\n"));
+    }
+
+    #[test]
+    fn test_unused_attribute_keeps_other_lints() {
+        let source = "#[allow(dead_code, cargo_perf::regex_in_loop)]\nfn f() {}\n";
+        let diags = check(source);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(fixed(source, &diags[0]), "#[allow(dead_code)]\nfn f() {}\n");
+
+        let source = "#[allow(cargo_perf::regex_in_loop)]\nfn f() {}\n";
+        assert_eq!(fixed(source, &check(source)[0]), "fn f() {}\n");
+    }
+
+    #[test]
+    fn test_rules_that_did_not_run_are_not_judged() {
+        let source = "fn f() {\n    // cargo-perf-ignore: clone-in-hot-loop\n    let x = 1;\n}\n";
+        let ast = syn::parse_file(source).unwrap();
+        let config = Config::default();
+        let ctx = AnalysisContext::new(Path::new("test.rs"), source, &ast, &config);
+        let rules = [registry::get_rule(super::UNUSED_SUPPRESSION).unwrap()];
+        assert!(check_with_rules(&ctx, rules.into_iter()).is_empty());
+
+        // Nor are suppressions of all rules while some rules did not run
+        let source = "fn f() {\n    // cargo-perf-ignore\n    let x = 1;\n}\n";
+        let ast = syn::parse_file(source).unwrap();
+        let ctx = AnalysisContext::new(Path::new("test.rs"), source, &ast, &config);
+        assert!(check_with_rules(&ctx, rules.into_iter()).is_empty());
+        assert_eq!(check(source).len(), 1);
+    }
+
+    #[test]
+    fn test_unused_trailing_and_block_comments() {
+        let source = "fn f() {\n    let x = 1; // cargo-perf-ignore\n}\n";
+        let diags = check(source);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(diags[0].line, 2);
+        assert_eq!(fixed(source, &diags[0]), "fn f() {\n    let x = 1;\n}\n");
+
+        let source = "fn f() {\n    // cargo-perf-ignore-start: regex-in-loop\n    let x = 1;\n    // cargo-perf-ignore-end\n}\n";
+        let diags = check(source);
+        assert_eq!(diags.len(), 1);
+        let replacements = &diags[0].fix.as_ref().unwrap().replacements;
+        assert_eq!(replacements.len(), 2);
+        let mut fixed = source.to_string();
+        for replacement in replacements.iter().rev() {
+            fixed.replace_range(
+                replacement.start_byte..replacement.end_byte,
+                &replacement.new_text,
+            );
+        }
+        assert_eq!(fixed, "fn f() {\n    let x = 1;\n}\n");
+
+        // A file-wide suppression of this rule silences the reports
+        let source = format!("// cargo-perf-ignore-file: unused-suppression\n{source}");
+        assert!(check(&source).is_empty());
+    }
+
+    #[test]
+    fn test_stray_block_and_file_markers() {
+        let source = r#"
+fn f(items: &[String]) {
+    // cargo-perf-ignore-end
+    for item in items {
+        // cargo-perf-ignore-start: clone-in-hot-loop
+        let copy = item.clone();
+        drop(copy);
+    }
+}
+// cargo-perf-ignore-file: clone-in-hot-loop
+"#;
+        let ast = syn::parse_file(source).unwrap();
+        let config = Config::default();
+        let ctx = AnalysisContext::new(Path::new("test.rs"), source, &ast, &config);
+        let all = check_with_rules(&ctx, registry::all_rules().iter().map(|r| r.as_ref()));
+        // Neither the unclosed block nor the late file comment suppresses
+        assert!(all.iter().any(|d| d.rule_id == "clone-in-hot-loop"));
+
+        let diags = check(source);
+        let lines: Vec<usize> = diags.iter().map(|d| d.line).collect();
+        assert_eq!(lines, [3, 5, 10], "{:?}", diags);
+        assert!(diags[0].message.contains("closes no suppression block"));
+        assert!(diags[1].message.contains("no `cargo-perf-ignore-end`"));
+        assert!(diags[2].message.contains("below the first item"));
+        assert!(fixed(source, &diags[0]).starts_with("\nfn f(items: &[String]) {\n    for item"));
+        assert!(diags[1].fix.is_none() && diags[2].fix.is_none());
+    }
+
+    #[test]
+    fn test_unfulfilled_expectation() {
+        let source = r#"
+#[expect(cargo_perf::clone_in_hot_loop)]
+fn f(items: &[String]) {
+    for item in items {
+        let copy = item.clone();
+        drop(copy);
+    }
+}
+"#;
+        assert!(check(source).is_empty());
+
+        let source = "#[expect(cargo_perf::clone_in_hot_loop, reason = \"cold\")]\nfn f() {}\n";
+        let diags = check(source);
+        assert_eq!(diags.len(), 1);
+        assert_eq!(
+            diags[0].message,
+            "`#[expect]` suppression expected a `clone-in-hot-loop` finding, but none fired"
+        );
+        assert_eq!(fixed(source, &diags[0]), "fn f() {}\n");
+    }
+
+    #[test]
+    fn test_require_reason() {
+        let config: Config =
+            toml::from_str("[rules.unused-suppression]\nrequire-reason = true\n").unwrap();
+        let rule = registry::get_rule(super::UNUSED_SUPPRESSION).unwrap();
+        rule.validate_options(&config.rule_options[super::UNUSED_SUPPRESSION])
+            .unwrap();
+
+        let source = r#"
+fn f(items: &[String]) {
+    for item in items {
+        // cargo-perf-ignore: clone-in-hot-loop -- reason: small vec, bounded to 4
+        let a = item.clone();
+        // cargo-perf-ignore: clone-in-hot-loop
+        let b = item.clone();
+        drop((a, b));
+    }
+}
+
+#[allow(cargo_perf::clone_in_hot_loop, reason = "runs once")]
+fn g(items: &[String]) {
+    for item in items {
+        drop(item.clone());
+    }
+}
+
+#[allow(cargo_perf::clone_in_hot_loop)]
+fn h(items: &[String]) {
+    for item in items {
+        drop(item.clone());
+    }
+}
+"#;
+        let diags = check_with_config(source, config);
+        let lines: Vec<_> = diags.iter().map(|d| d.line).collect();
+        assert_eq!(lines, [6, 19]);
+        assert_eq!(diags[0].message, "Suppression comment gives no reason");
+        assert!(diags[0].fix.is_none());
+        assert_eq!(diags[1].message, "`#[allow]` suppression gives no reason");
+
+        // Reasons are optional by default
+        assert!(check(source).is_empty());
+
+        let invalid: Config =
+            toml::from_str("[rules.unused-suppression]\nrequire-reasons = true\n").unwrap();
+        assert!(rule
+            .validate_options(&invalid.rule_options[super::UNUSED_SUPPRESSION])
+            .is_err());
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(
+            super::edit_distance("clone-in-loop", "clone-in-hot-loop"),
+            4
+        );
+        assert_eq!(super::edit_distance("", "abc"), 3);
+        assert_eq!(super::edit_distance("same", "same"), 0);
+    }
+}
@@ -20,7 +20,8 @@
 //!
 //! ## Comment-based Suppression (Line-level)
 //!
-//! Suppresses diagnostics for the next line only (fine-grained control):
+//! Suppresses diagnostics for the next line only (fine-grained control), or
+//! for its own line when it follows code:
 //!
 //! ```rust,ignore
 //! fn process_items(items: &[Item]) {
@@ -28,172 +29,621 @@
 //!         // cargo-perf-ignore: clone-in-hot-loop
 //!         let copy = item.clone();  // No warning (only this line)
 //!         let other = item.clone(); // Warning! (not suppressed)
+//!         let last = item.clone(); // cargo-perf-ignore: clone-in-hot-loop
 //!     }
 //! }
 //! ```
 //!
+//! ## Block and File Comments
+//!
+//! Code that cannot take attributes, such as generated code or macro-heavy
+//! blocks, can be suppressed between markers, or in the whole file:
+//!
+//! ```rust,ignore
+//! // cargo-perf-ignore-file: vec-no-capacity
+//!
+//! // cargo-perf-ignore-start: clone-in-hot-loop, format-in-loop
+//! generated_handlers! { /* ... */ }
+//! // cargo-perf-ignore-end
+//! ```
+//!
+//! An end marker closes the innermost open block, and file comments must
+//! come before the first item, with the file's other leading comments. A
+//! block without an end marker, an end marker without a block, and a file
+//! comment below the first item suppress nothing; `unused-suppression`
+//! reports them.
+//!
+//! ## Reasons and Expectations
+//!
+//! Either form can say why the finding is acceptable, after `--` in a
+//! comment or in a `reason` in an attribute:
+//!
+//! ```rust,ignore
+//! // cargo-perf-ignore: clone-in-hot-loop -- reason: small vec, bounded to 4
+//! let copy = item.clone();
+//!
+//! #[allow(cargo_perf::clone_in_hot_loop, reason = "runs once at startup")]
+//! fn load() { /* ... */ }
+//! ```
+//!
+//! `#[expect(cargo_perf::...)]` suppresses like `#[allow]`, but is reported
+//! once the expected diagnostic no longer fires.
+//!
 //! ## When to Use Which
 //!
 //! - **Attributes**: When you've reviewed an entire function/type and determined all
 //!   diagnostics are acceptable. Good for legacy code or intentional patterns.
 //! - **Comments**: When only specific lines need suppression. Provides documentation
 //!   for why that particular usage is acceptable.
-
-use std::collections::HashSet;
+//!
+//! Suppressions that suppress nothing, name rules that do not exist, or,
+//! when the rule's `require-reason` option is set, give no reason, are
+//! reported by the `unused-suppression` rule.
+
+use crate::engine::LineIndex;
+use proc_macro2::LineColumn;
+use std::collections::HashMap;
+use std::ops::{Range, RangeInclusive};
+use syn::spanned::Spanned;
 use syn::visit::Visit;
-use syn::{Attribute, File, ItemFn, ItemImpl, ItemMod, ItemStruct};
+use syn::{Attribute, File, ImplItemFn, ItemFn, ItemImpl, ItemMod, ItemStruct, TraitItemFn};
+
+/// The comment marker for comment-based suppressions.
+const IGNORE_MARKER: &str = "cargo-perf-ignore";
+
+/// How a suppression is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuppressionKind {
+    /// A `// cargo-perf-ignore` comment
+    Comment,
+    /// `cargo_perf::` paths in an `#[allow(...)]` attribute
+    Attribute,
+    /// `cargo_perf::` paths in an `#[expect(...)]` attribute
+    Expect,
+}
+
+/// A suppression written in the source.
+#[derive(Debug, Clone)]
+pub struct Suppression {
+    pub kind: SuppressionKind,
+    /// Line the comment or attribute starts on (1-indexed)
+    pub line: usize,
+    /// Column the comment or attribute starts at (1-indexed)
+    pub column: usize,
+    /// Rule IDs it names, with `_` in attribute paths read as `-`; `all`
+    /// stands for every rule
+    pub rules: Vec<String>,
+    /// Lines it applies to, or `None` for the whole file
+    pub lines: Option<RangeInclusive<usize>>,
+    /// Why the findings are acceptable, from `-- reason: ...` in a comment
+    /// or `reason = "..."` in an attribute
+    pub reason: Option<String>,
+    /// Byte range of the comment or attribute
+    span: Range<usize>,
+    /// Byte range of the `// cargo-perf-ignore-end` comment closing a block
+    end_span: Option<Range<usize>>,
+    /// Byte ranges of the comma-separated entries, which in an attribute may
+    /// include other lints, with the index in `rules` of those naming a rule.
+    /// A reason is not an entry.
+    entries: Vec<(Range<usize>, Option<usize>)>,
+}
+
+impl Suppression {
+    /// Byte range of the comment or attribute
+    pub fn span(&self) -> Range<usize> {
+        self.span.clone()
+    }
+
+    /// Source text of the suppression without the rules at `drop` (indices
+    /// into `rules`), as byte ranges and their replacements. Removing every
+    /// entry removes the comment or attribute, and the end marker of a block,
+    /// each with its line if nothing else is on it.
+    pub fn without_rules(&self, source: &str, drop: &[usize]) -> Vec<(Range<usize>, String)> {
+        let kept: Vec<&str> = self
+            .entries
+            .iter()
+            .filter(|(_, rule)| rule.is_none_or(|rule| !drop.contains(&rule)))
+            .map(|(range, _)| &source[range.clone()])
+            .collect();
+        if let (false, Some((first, _)), Some((last, _))) =
+            (kept.is_empty(), self.entries.first(), self.entries.last())
+        {
+            return vec![(first.start..last.end, kept.join(", "))];
+        }
+
+        std::iter::once(&self.span)
+            .chain(&self.end_span)
+            .map(|span| (removal(source, span), String::new()))
+            .collect()
+    }
+}
+
+/// Byte range that removes `span` from `source`, with its line when it stands
+/// alone, or else with the whitespace before it.
+fn removal(source: &str, span: &Range<usize>) -> Range<usize> {
+    let line_start = source[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = source[span.end..]
+        .find('\n')
+        .map_or(source.len(), |i| span.end + i + 1);
+    if source[line_start..span.start].trim().is_empty()
+        && source[span.end..line_end].trim().is_empty()
+    {
+        return line_start..line_end;
+    }
+    source[..span.start].trim_end().len()..span.end
+}
+
+/// A block or file marker that suppresses nothing because of where it is.
+#[derive(Debug, Clone)]
+pub struct StrayMarker {
+    pub problem: MarkerProblem,
+    /// Line the comment starts on (1-indexed)
+    pub line: usize,
+    /// Column the comment starts at (1-indexed)
+    pub column: usize,
+    /// Byte range of the comment
+    span: Range<usize>,
+}
+
+impl StrayMarker {
+    /// Byte range that removes the comment, with its line if nothing else is
+    /// on it.
+    pub fn removal(&self, source: &str) -> Range<usize> {
+        removal(source, &self.span)
+    }
+}
+
+/// Why a [`StrayMarker`] suppresses nothing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarkerProblem {
+    /// `cargo-perf-ignore-start` without a matching end marker
+    UnclosedStart,
+    /// `cargo-perf-ignore-end` without an open block
+    UnmatchedEnd,
+    /// `cargo-perf-ignore-file` below the first item
+    FileAfterItems,
+}
+
+/// Where a `// cargo-perf-ignore` comment applies, from its marker.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CommentScope {
+    /// `cargo-perf-ignore`: the next line, or its own line after code
+    Line,
+    /// `cargo-perf-ignore-start`: up to the matching end marker
+    Start,
+    /// `cargo-perf-ignore-end`
+    End,
+    /// `cargo-perf-ignore-file`: the whole file
+    File,
+}
 
 /// Extracts all cargo_perf suppressions from a file.
 pub struct SuppressionExtractor {
-    /// Suppressions by line number: (line, set of suppressed rule IDs)
-    /// An empty set means all rules are suppressed
-    pub line_suppressions: std::collections::HashMap<usize, HashSet<String>>,
-    /// Global suppressions that apply to the entire file
-    pub file_suppressions: HashSet<String>,
+    suppressions: Vec<Suppression>,
+    /// Indices of the suppressions that apply to each line
+    by_line: HashMap<usize, Vec<usize>>,
+    /// Indices of the suppressions that apply to the entire file
+    file_wide: Vec<usize>,
+    stray: Vec<StrayMarker>,
+    line_index: LineIndex,
 }
 
 impl SuppressionExtractor {
     /// Extract suppressions from source code and AST.
     pub fn new(source: &str, ast: &File) -> Self {
         let mut extractor = Self {
-            line_suppressions: std::collections::HashMap::new(),
-            file_suppressions: HashSet::new(),
+            suppressions: Vec::new(),
+            by_line: HashMap::new(),
+            file_wide: Vec::new(),
+            stray: Vec::new(),
+            line_index: LineIndex::new(source),
         };
 
         // Extract comment-based suppressions from source
-        extractor.extract_comment_suppressions(source);
-
-        // Extract attribute-based suppressions from AST
-        extractor.visit_file(ast);
+        let first_item = ast.items.first().map(|item| item.span().start().line);
+        extractor.extract_comment_suppressions(source, first_item);
 
         // Check for file-level attributes
-        for attr in &ast.attrs {
-            extractor.process_attribute(attr, None);
+        extractor.add_attribute_suppressions(source, &ast.attrs, None);
+
+        // Extract attribute-based suppressions from AST
+        ExtractVisitor {
+            extractor: &mut extractor,
+            source,
         }
+        .visit_file(ast);
 
         extractor
     }
 
+    /// Every suppression in the file, comments first.
+    pub fn suppressions(&self) -> &[Suppression] {
+        &self.suppressions
+    }
+
+    /// Block and file markers that suppress nothing because of where they are.
+    pub fn stray_markers(&self) -> &[StrayMarker] {
+        &self.stray
+    }
+
     /// Check if a diagnostic at the given line should be suppressed.
     pub fn is_suppressed(&self, rule_id: &str, line: usize) -> bool {
-        // Check file-level suppressions
-        if self.file_suppressions.contains("all") || self.file_suppressions.contains(rule_id) {
-            return true;
-        }
-
-        // Check line-level suppressions
-        if let Some(suppressions) = self.line_suppressions.get(&line) {
-            if suppressions.is_empty()
-                || suppressions.contains("all")
-                || suppressions.contains(rule_id)
-            {
-                return true;
-            }
-        }
+        self.matching(rule_id, line).next().is_some()
+    }
 
-        false
+    /// The suppressions that suppress `rule_id` at `line`, as pairs of the
+    /// suppression's index and the index of the matching entry in its `rules`.
+    pub fn matching<'s>(
+        &'s self,
+        rule_id: &'s str,
+        line: usize,
+    ) -> impl Iterator<Item = (usize, usize)> + 's {
+        let on_line = self.by_line.get(&line).map_or(&[][..], Vec::as_slice);
+        self.file_wide
+            .iter()
+            .chain(on_line)
+            .filter_map(move |&index| {
+                let rules = &self.suppressions[index].rules;
+                let rule = rules.iter().position(|r| r == "all" || r == rule_id)?;
+                Some((index, rule))
+            })
     }
 
-    /// Extract `// cargo-perf-ignore: rule_id` comments.
-    fn extract_comment_suppressions(&mut self, source: &str) {
-        for (line_num, line) in source.lines().enumerate() {
-            let line_num = line_num + 1; // 1-indexed
+    /// Extract `// cargo-perf-ignore: rule_id -- reason: ...` comments, and
+    /// their `-start`/`-end` and `-file` forms. `-file` comments count only
+    /// above `first_item`, the line the file's first item starts on.
+    fn extract_comment_suppressions(&mut self, source: &str, first_item: Option<usize>) {
+        // Blocks whose end marker has not been seen yet
+        let mut open: Vec<Suppression> = Vec::new();
+        for comment in line_comments(source) {
+            // A plain `//` comment whose text starts with the marker; doc
+            // comments only mention it
+            let text = &source[comment.start + 2..comment.end];
+            if text.starts_with(['/', '!']) {
+                continue;
+            }
+            let Some(rest) = text.trim_start().strip_prefix(IGNORE_MARKER) else {
+                continue;
+            };
+            let (scope, rest) = [
+                ("-start", CommentScope::Start),
+                ("-end", CommentScope::End),
+                ("-file", CommentScope::File),
+            ]
+            .into_iter()
+            .find_map(|(suffix, scope)| Some((scope, rest.strip_prefix(suffix)?)))
+            .unwrap_or((CommentScope::Line, rest));
+            if rest.starts_with(|c: char| c.is_alphanumeric() || c == '-' || c == '_') {
+                continue;
+            }
 
-            // Check for cargo-perf-ignore comment
-            if let Some(idx) = line.find("cargo-perf-ignore") {
-                let rest = &line[idx + "cargo-perf-ignore".len()..];
-                let rest = rest.trim_start_matches(':').trim();
+            let (line, _) = self.line_index.line_col(comment.start);
+            let line_start = self.line_index.line_start(line).unwrap_or(0);
+            let column = source[line_start..comment.start].chars().count() + 1;
+            let span =
+                comment.start..comment.start + source[comment.start..comment.end].trim_end().len();
+            let stray = |problem| StrayMarker {
+                problem,
+                line,
+                column,
+                span: span.clone(),
+            };
+            if scope == CommentScope::End {
+                match open.pop() {
+                    Some(mut block) => {
+                        block.lines = block.lines.map(|lines| *lines.start()..=line);
+                        block.end_span = Some(span);
+                        self.push(block);
+                    }
+                    None => self.stray.push(stray(MarkerProblem::UnmatchedEnd)),
+                }
+                continue;
+            }
+            if scope == CommentScope::File && first_item.is_some_and(|first| line >= first) {
+                self.stray.push(stray(MarkerProblem::FileAfterItems));
+                continue;
+            }
 
-                let suppressions = self
-                    .line_suppressions
-                    .entry(line_num + 1) // Suppress the *next* line
-                    .or_default();
+            let list = rest.strip_prefix(':').unwrap_or(rest);
+            let list_start = comment.end - list.len();
+            let mut rules = Vec::new();
+            let mut entries = Vec::new();
+            for (range, rule) in parse_rule_list(list) {
+                entries.push((
+                    list_start + range.start..list_start + range.end,
+                    Some(rules.len()),
+                ));
+                rules.push(rule.to_string());
+            }
+            if rules.is_empty() {
+                rules.push("all".to_string());
+            }
+            let listed = entries
+                .last()
+                .map_or(0, |(range, _)| range.end - list_start);
+            let reason = list[listed..]
+                .trim_start()
+                .strip_prefix("--")
+                .map(|text| {
+                    let text = text.trim_start();
+                    text.strip_prefix("reason:").unwrap_or(text).trim()
+                })
+                .filter(|reason| !reason.is_empty())
+                .map(str::to_string);
+
+            let after_code = !source[line_start..comment.start].trim().is_empty();
+            let suppression = Suppression {
+                kind: SuppressionKind::Comment,
+                line,
+                column,
+                rules,
+                lines: match scope {
+                    CommentScope::Line if after_code => Some(line..=line),
+                    // Suppress the *next* line
+                    CommentScope::Line => Some(line + 1..=line + 1),
+                    // Up to the end marker, once it is found
+                    CommentScope::Start | CommentScope::End => Some(line..=line),
+                    CommentScope::File => None,
+                },
+                reason,
+                span,
+                end_span: None,
+                entries,
+            };
+            match scope {
+                CommentScope::Start => open.push(suppression),
+                _ => self.push(suppression),
+            }
+        }
 
-                if rest.is_empty() || rest == "all" {
-                    suppressions.insert("all".to_string());
-                } else {
-                    // Parse comma-separated rule IDs
-                    for rule in rest.split(',') {
-                        let rule = rule.trim();
-                        if !rule.is_empty() {
-                            suppressions.insert(rule.to_string());
+        // Unclosed blocks suppress nothing rather than the rest of the file
+        for block in open {
+            self.stray.push(StrayMarker {
+                problem: MarkerProblem::UnclosedStart,
+                line: block.line,
+                column: block.column,
+                span: block.span,
+            });
+        }
+        self.stray.sort_by_key(|marker| marker.line);
+    }
+
+    /// Add the suppressions in `#[allow(cargo_perf::...)]` and
+    /// `#[expect(cargo_perf::...)]` attributes, for `lines` or the whole file.
+    fn add_attribute_suppressions(
+        &mut self,
+        source: &str,
+        attrs: &[Attribute],
+        lines: Option<RangeInclusive<usize>>,
+    ) {
+        for attr in attrs {
+            let kind = if attr.path().is_ident("allow") {
+                SuppressionKind::Attribute
+            } else if attr.path().is_ident("expect") {
+                SuppressionKind::Expect
+            } else {
+                continue;
+            };
+            let Ok(nested) = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated,
+            ) else {
+                continue;
+            };
+
+            let mut rules = Vec::new();
+            let mut entries = Vec::new();
+            let mut reason = None;
+            for meta in nested {
+                let path = match meta {
+                    syn::Meta::Path(path) => path,
+                    syn::Meta::NameValue(value) if value.path.is_ident("reason") => {
+                        if let syn::Expr::Lit(syn::ExprLit {
+                            lit: syn::Lit::Str(text),
+                            ..
+                        }) = value.value
+                        {
+                            reason = Some(text.value()).filter(|r| !r.trim().is_empty());
                         }
+                        continue;
                     }
+                    _ => continue,
+                };
+                let segments: Vec<_> = path.segments.iter().map(|s| s.ident.to_string()).collect();
+                let rule =
+                    (segments.first().map(|s| s.as_str()) == Some("cargo_perf")).then(|| {
+                        let rule_id = segments.get(1).map(|s| s.as_str()).unwrap_or("all");
+                        rules.push(rule_id.replace('_', "-"));
+                        rules.len() - 1
+                    });
+                if let Some(range) = self.byte_range(source, path.span()) {
+                    entries.push((range, rule));
                 }
             }
+            if rules.is_empty() {
+                continue;
+            }
+
+            let start = attr.pound_token.span.start();
+            self.push(Suppression {
+                kind,
+                line: start.line,
+                column: start.column + 1,
+                rules,
+                lines: lines.clone(),
+                reason,
+                span: self.byte_range(source, attr.span()).unwrap_or_default(),
+                end_span: None,
+                entries,
+            });
         }
     }
 
-    /// Process an attribute to extract suppressions.
-    fn process_attribute(&mut self, attr: &Attribute, span_line: Option<usize>) {
-        // Check for #[allow(cargo_perf::...)]
-        if attr.path().is_ident("allow") {
-            if let Ok(nested) = attr.parse_args_with(
-                syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
-            ) {
-                for path in nested {
-                    let segments: Vec<_> =
-                        path.segments.iter().map(|s| s.ident.to_string()).collect();
-
-                    if segments.first().map(|s| s.as_str()) == Some("cargo_perf") {
-                        let rule_id = segments.get(1).map(|s| s.as_str()).unwrap_or("all");
-
-                        if let Some(line) = span_line {
-                            self.line_suppressions
-                                .entry(line)
-                                .or_default()
-                                .insert(rule_id.replace('_', "-"));
-                        } else {
-                            self.file_suppressions.insert(rule_id.replace('_', "-"));
-                        }
-                    }
+    fn push(&mut self, suppression: Suppression) {
+        let index = self.suppressions.len();
+        match &suppression.lines {
+            Some(lines) => {
+                for line in lines.clone() {
+                    self.by_line.entry(line).or_default().push(index);
                 }
             }
+            None => self.file_wide.push(index),
         }
+        self.suppressions.push(suppression);
     }
 
-    /// Add suppressions for a range of lines based on attributes.
-    fn add_item_suppressions(&mut self, attrs: &[Attribute], start_line: usize, end_line: usize) {
-        let mut rules_to_suppress = HashSet::new();
+    /// Byte range of a span in `source`.
+    fn byte_range(&self, source: &str, span: proc_macro2::Span) -> Option<Range<usize>> {
+        Some(self.byte_offset(source, span.start())?..self.byte_offset(source, span.end())?)
+    }
 
-        for attr in attrs {
-            if attr.path().is_ident("allow") {
-                if let Ok(nested) = attr.parse_args_with(
-                    syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated,
-                ) {
-                    for path in nested {
-                        let segments: Vec<_> =
-                            path.segments.iter().map(|s| s.ident.to_string()).collect();
-
-                        if segments.first().map(|s| s.as_str()) == Some("cargo_perf") {
-                            let rule_id = segments.get(1).map(|s| s.as_str()).unwrap_or("all");
-                            rules_to_suppress.insert(rule_id.replace('_', "-"));
-                        }
+    /// Byte offset of a position, whose column counts characters.
+    fn byte_offset(&self, source: &str, position: LineColumn) -> Option<usize> {
+        let line_start = self.line_index.line_start(position.line)?;
+        let rest = source.get(line_start..)?;
+        Some(
+            line_start
+                + rest
+                    .char_indices()
+                    .nth(position.column)
+                    .map_or(rest.len(), |(i, _)| i),
+        )
+    }
+}
+
+/// Byte ranges of the `//` comments in `source`, up to the end of their
+/// line, skipping string and character literals and block comments.
+fn line_comments(source: &str) -> Vec<Range<usize>> {
+    let bytes = source.as_bytes();
+    let is_ident = |b: u8| b.is_ascii_alphanumeric() || b == b'_';
+    let mut comments = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let rest = &source[i..];
+        if rest.starts_with("//") {
+            let line = rest.split('\n').next().unwrap_or(rest);
+            comments.push(i..i + line.trim_end_matches('\r').len());
+            i += line.len();
+        } else if rest.starts_with("/*") {
+            // Block comments nest
+            let mut depth = 0;
+            while i < bytes.len() {
+                if source[i..].starts_with("/*") {
+                    depth += 1;
+                    i += 2;
+                } else if source[i..].starts_with("*/") {
+                    depth -= 1;
+                    i += 2;
+                    if depth == 0 {
+                        break;
                     }
+                } else {
+                    i += 1;
                 }
             }
+        } else if bytes[i] == b'"' {
+            i += 1;
+            while i < bytes.len() && bytes[i] != b'"' {
+                i += if bytes[i] == b'\\' { 2 } else { 1 };
+            }
+            i += 1;
+        } else if bytes[i] == b'r'
+            && (i == 0
+                || !is_ident(bytes[i - 1])
+                || (matches!(bytes[i - 1], b'b' | b'c') && (i == 1 || !is_ident(bytes[i - 2]))))
+            && rest[1..].trim_start_matches('#').starts_with('"')
+        {
+            // Raw strings end at a quote followed by as many `#` as they began with
+            let hashes = rest[1..].len() - rest[1..].trim_start_matches('#').len();
+            let closing = format!("\"{}", "#".repeat(hashes));
+            let body = i + hashes + 2;
+            i = source[body..]
+                .find(&closing)
+                .map_or(source.len(), |n| body + n + closing.len());
+        } else if bytes[i] == b'\'' {
+            // A character literal, or else a lifetime or label
+            let mut chars = rest[1..].chars();
+            match chars.next() {
+                Some('\\') => {
+                    // Skip the escaped character, which may be a quote
+                    let escaped = chars.next().map_or(0, char::len_utf8);
+                    let body = i + 2 + escaped;
+                    i = source[body..]
+                        .find('\'')
+                        .map_or(source.len(), |n| body + n + 1);
+                }
+                Some(c) if chars.next() == Some('\'') => i += c.len_utf8() + 2,
+                _ => i += 1,
+            }
+        } else {
+            i += rest.chars().next().map_or(1, char::len_utf8);
         }
+    }
+    comments
+}
 
-        // Apply suppressions to all lines in the item's range
-        if !rules_to_suppress.is_empty() {
-            for line in start_line..=end_line {
-                self.line_suppressions
-                    .entry(line)
-                    .or_default()
-                    .extend(rules_to_suppress.iter().cloned());
-            }
+/// Parse a comma-separated list of rule IDs at the start of `text`, stopping
+/// at anything else, into the IDs and their byte ranges in `text`.
+pub(crate) fn parse_rule_list(text: &str) -> Vec<(Range<usize>, &str)> {
+    let is_id_char = |c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_';
+    let mut rules = Vec::new();
+    let mut pos = 0;
+    loop {
+        let start = pos + (text[pos..].len() - text[pos..].trim_start().len());
+        // IDs start with a letter or digit, so `--` starts the reason
+        if !text[start..].starts_with(|c: char| c.is_ascii_alphanumeric()) {
+            break;
+        }
+        let len = text[start..]
+            .find(|c: char| !is_id_char(c))
+            .unwrap_or(text.len() - start);
+        rules.push((start..start + len, &text[start..start + len]));
+        let after = start + len;
+        let next = after + (text[after..].len() - text[after..].trim_start().len());
+        match text[next..].strip_prefix(',') {
+            Some(_) => pos = next + 1,
+            None => break,
         }
     }
+    rules
+}
+
+/// Walks items to find the line ranges their attributes suppress.
+struct ExtractVisitor<'e, 's> {
+    extractor: &'e mut SuppressionExtractor,
+    source: &'s str,
+}
+
+impl ExtractVisitor<'_, '_> {
+    fn add_item(&mut self, attrs: &[Attribute], start_line: usize, end_line: usize) {
+        self.extractor
+            .add_attribute_suppressions(self.source, attrs, Some(start_line..=end_line));
+    }
 }
 
-impl<'ast> Visit<'ast> for SuppressionExtractor {
+impl<'ast> Visit<'ast> for ExtractVisitor<'_, '_> {
     fn visit_item_fn(&mut self, node: &'ast ItemFn) {
         let start = node.sig.fn_token.span.start().line;
         let end = node.block.brace_token.span.close().start().line;
-        self.add_item_suppressions(&node.attrs, start, end);
+        self.add_item(&node.attrs, start, end);
         syn::visit::visit_item_fn(self, node);
     }
 
+    fn visit_impl_item_fn(&mut self, node: &'ast ImplItemFn) {
+        let start = node.sig.fn_token.span.start().line;
+        let end = node.block.brace_token.span.close().start().line;
+        self.add_item(&node.attrs, start, end);
+        syn::visit::visit_impl_item_fn(self, node);
+    }
+
+    fn visit_trait_item_fn(&mut self, node: &'ast TraitItemFn) {
+        if let Some(block) = &node.default {
+            let start = node.sig.fn_token.span.start().line;
+            let end = block.brace_token.span.close().start().line;
+            self.add_item(&node.attrs, start, end);
+        }
+        syn::visit::visit_trait_item_fn(self, node);
+    }
+
     fn visit_item_struct(&mut self, node: &'ast ItemStruct) {
         let start = node.struct_token.span.start().line;
         // Get accurate end line based on struct variant
@@ -205,14 +655,14 @@ impl<'ast> Visit<'ast> for SuppressionExtractor {
                 .map(|t| t.span.start().line)
                 .unwrap_or(start),
         };
-        self.add_item_suppressions(&node.attrs, start, end);
+        self.add_item(&node.attrs, start, end);
         syn::visit::visit_item_struct(self, node);
     }
 
     fn visit_item_impl(&mut self, node: &'ast ItemImpl) {
         let start = node.impl_token.span.start().line;
         let end = node.brace_token.span.close().start().line;
-        self.add_item_suppressions(&node.attrs, start, end);
+        self.add_item(&node.attrs, start, end);
         syn::visit::visit_item_impl(self, node);
     }
 
@@ -220,7 +670,7 @@ impl<'ast> Visit<'ast> for SuppressionExtractor {
         if let Some((brace, _)) = &node.content {
             let start = node.mod_token.span.start().line;
             let end = brace.span.close().start().line;
-            self.add_item_suppressions(&node.attrs, start, end);
+            self.add_item(&node.attrs, start, end);
         }
         syn::visit::visit_item_mod(self, node);
     }
@@ -275,6 +725,246 @@ fn test() {
         assert!(extractor.is_suppressed("clone-in-hot-loop", 4));
     }
 
+    #[test]
+    fn test_attribute_suppression_method() {
+        let source = r#"
+impl Foo {
+    #[allow(cargo_perf::clone_in_hot_loop)]
+    fn test(&self) {
+        let x = data.clone();
+    }
+
+    fn other(&self) {
+        let x = data.clone();
+    }
+}
+"#;
+        let ast = syn::parse_file(source).unwrap();
+        let extractor = SuppressionExtractor::new(source, &ast);
+
+        assert!(extractor.is_suppressed("clone-in-hot-loop", 5));
+        assert!(!extractor.is_suppressed("clone-in-hot-loop", 9));
+    }
+
+    #[test]
+    fn test_comment_rule_list_ends_at_other_text() {
+        let source = r#"
+fn test() {
+    // cargo-perf-ignore: clone-in-hot-loop, regex-in-loop  (bounded to 4 items)
+    let x = data.clone();
+    /// cargo-perf-ignore: vec-no-capacity
+    let y = Vec::new();
+}
+"#;
+        let ast = syn::parse_file(source).unwrap();
+        let extractor = SuppressionExtractor::new(source, &ast);
+
+        let suppressions = extractor.suppressions();
+        assert_eq!(suppressions.len(), 1);
+        assert_eq!(
+            suppressions[0].rules,
+            ["clone-in-hot-loop", "regex-in-loop"]
+        );
+        assert_eq!(
+            extractor.matching("regex-in-loop", 4).collect::<Vec<_>>(),
+            [(0, 1)]
+        );
+        // Doc comments only mention the marker
+        assert!(!extractor.is_suppressed("vec-no-capacity", 6));
+
+        let mut edited = source.to_string();
+        for (range, text) in suppressions[0].without_rules(source, &[0]) {
+            edited.replace_range(range, &text);
+        }
+        assert!(edited.contains("// cargo-perf-ignore: regex-in-loop  (bounded"));
+    }
+
+    #[test]
+    fn test_reasons() {
+        let source = r#"
+#[allow(dead_code, cargo_perf::regex_in_loop, reason = "compiled once per call")]
+fn test() {
+    // cargo-perf-ignore: clone-in-hot-loop -- reason: small vec, bounded to 4
+    let x = data.clone();
+    // cargo-perf-ignore -- reason: generated code
+    let y = data.clone();
+    // cargo-perf-ignore: vec-no-capacity --
+    let z = Vec::new();
+}
+"#;
+        let ast = syn::parse_file(source).unwrap();
+        let extractor = SuppressionExtractor::new(source, &ast);
+
+        let reasons: Vec<_> = extractor
+            .suppressions()
+            .iter()
+            .map(|s| (s.rules.join(","), s.reason.as_deref()))
+            .collect();
+        assert_eq!(
+            reasons,
+            [
+                (
+                    "clone-in-hot-loop".to_string(),
+                    Some("small vec, bounded to 4")
+                ),
+                ("all".to_string(), Some("generated code")),
+                ("vec-no-capacity".to_string(), None),
+                ("regex-in-loop".to_string(), Some("compiled once per call")),
+            ]
+        );
+
+        // Removing a rule keeps the other lints and the reason
+        let attribute = &extractor.suppressions()[3];
+        let mut edited = source.to_string();
+        for (range, text) in attribute.without_rules(source, &[0]) {
+            edited.replace_range(range, &text);
+        }
+        assert!(edited.contains("#[allow(dead_code, reason = \"compiled once per call\")]"));
+    }
+
+    #[test]
+    fn test_expect_attribute() {
+        let source = r#"
+#[expect(cargo_perf::clone_in_hot_loop)]
+fn test() {
+    let x = data.clone();
+}
+"#;
+        let ast = syn::parse_file(source).unwrap();
+        let extractor = SuppressionExtractor::new(source, &ast);
+
+        assert_eq!(extractor.suppressions()[0].kind, SuppressionKind::Expect);
+        assert!(extractor.is_suppressed("clone-in-hot-loop", 4));
+        assert!(!extractor.is_suppressed("vec-no-capacity", 4));
+    }
+
+    #[test]
+    fn test_trailing_comment_suppresses_own_line() {
+        let source = r#"
+fn test() {
+    let x = data.clone(); // cargo-perf-ignore: clone-in-hot-loop
+    let y = data.clone();
+}
+"#;
+        let ast = syn::parse_file(source).unwrap();
+        let extractor = SuppressionExtractor::new(source, &ast);
+
+        assert!(extractor.is_suppressed("clone-in-hot-loop", 3));
+        assert!(!extractor.is_suppressed("clone-in-hot-loop", 4));
+    }
+
+    #[test]
+    fn test_block_comments() {
+        let source = r#"// cargo-perf-ignore-file: vec-no-capacity
+fn test() {
+    // cargo-perf-ignore-start: clone-in-hot-loop -- reason: generated
+    let a = data.clone();
+    // cargo-perf-ignore-start
+    let b = data.clone();
+    // cargo-perf-ignore-end
+    let c = data.clone();
+    // cargo-perf-ignore-end
+    let d = data.clone();
+    // cargo-perf-ignore-start: regex-in-loop
+    let e = data.clone();
+}
+"#;
+        let ast = syn::parse_file(source).unwrap();
+        let extractor = SuppressionExtractor::new(source, &ast);
+
+        let scopes: Vec<_> = extractor
+            .suppressions()
+            .iter()
+            .map(|s| (s.rules.join(","), s.lines.clone()))
+            .collect();
+        assert_eq!(
+            scopes,
+            [
+                ("vec-no-capacity".to_string(), None),
+                ("all".to_string(), Some(5..=7)),
+                ("clone-in-hot-loop".to_string(), Some(3..=9)),
+            ]
+        );
+        // The unclosed block suppresses nothing
+        let stray: Vec<_> = extractor
+            .stray_markers()
+            .iter()
+            .map(|m| (m.problem, m.line))
+            .collect();
+        assert_eq!(stray, [(MarkerProblem::UnclosedStart, 11)]);
+        assert_eq!(
+            extractor.suppressions()[2].reason.as_deref(),
+            Some("generated")
+        );
+        assert!(extractor.is_suppressed("vec-no-capacity", 12));
+        assert!(extractor.is_suppressed("clone-in-hot-loop", 8));
+        assert!(!extractor.is_suppressed("clone-in-hot-loop", 10));
+        assert!(!extractor.is_suppressed("regex-in-loop", 12));
+
+        // Removing a block removes both of its markers
+        let mut edited = source.to_string();
+        let mut removals = extractor.suppressions()[2].without_rules(source, &[0]);
+        removals.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, text) in removals {
+            edited.replace_range(range, &text);
+        }
+        assert!(!edited.contains("reason: generated"));
+        assert_eq!(edited.matches("cargo-perf-ignore-end").count(), 1);
+        assert!(edited.contains("    let c = data.clone();\n    let d"));
+    }
+
+    #[test]
+    fn test_stray_markers() {
+        let source = r#"//! Crate docs
+// cargo-perf-ignore-file: vec-no-capacity
+#![allow(dead_code)]
+
+/// Item docs
+// cargo-perf-ignore-file: clone-in-hot-loop
+fn test() {
+    // cargo-perf-ignore-end
+    let a = data.clone();
+}
+"#;
+        let ast = syn::parse_file(source).unwrap();
+        let extractor = SuppressionExtractor::new(source, &ast);
+
+        // Leading comments count, even after inner attributes and docs
+        assert_eq!(extractor.suppressions().len(), 1);
+        assert!(extractor.is_suppressed("vec-no-capacity", 9));
+        assert!(!extractor.is_suppressed("clone-in-hot-loop", 9));
+        let stray: Vec<_> = extractor
+            .stray_markers()
+            .iter()
+            .map(|m| (m.problem, m.line, m.column))
+            .collect();
+        assert_eq!(
+            stray,
+            [
+                (MarkerProblem::FileAfterItems, 6, 1),
+                (MarkerProblem::UnmatchedEnd, 8, 5),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_markers_in_literals_are_not_suppressions() {
+        let source = r##"
+fn test() {
+    let a = "// cargo-perf-ignore: clone-in-hot-loop";
+    let b = r#"// cargo-perf-ignore"#;
+    let c = '\''; /* // cargo-perf-ignore */
+    let d = data.clone();
+}
+"##;
+        let ast = syn::parse_file(source).unwrap();
+        let extractor = SuppressionExtractor::new(source, &ast);
+
+        assert!(extractor.suppressions().is_empty());
+        assert!(!extractor.is_suppressed("clone-in-hot-loop", 4));
+        assert!(!extractor.is_suppressed("clone-in-hot-loop", 7));
+    }
+
     #[test]
     fn test_no_suppression() {
         let source = r#"
@@ -0,0 +1,566 @@
+//! Cargo workspace model: packages and their build targets.
+//!
+//! Plain directory discovery cannot tell compiled code from fixtures, generated
+//! files or dead modules. When the analyzed path is inside a Cargo project, the
+//! packages and targets (lib, bins, tests, benches, examples, build scripts) are
+//! read from `cargo metadata --no-deps --offline`, or, if cargo is unavailable
+//! or fails, from the manifests directly using Cargo's target auto-discovery
+//! conventions. The engine then analyzes only files reachable from a target
+//! root through `mod` declarations and tags each diagnostic with the package
+//! and target it belongs to.
+
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// The kind of a Cargo build target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TargetKind {
+    Lib,
+    Bin,
+    Test,
+    Bench,
+    Example,
+    /// A build script (`build.rs`).
+    #[serde(alias = "build")]
+    CustomBuild,
+}
+
+impl TargetKind {
+    /// Map a `cargo metadata` target kind (`lib`, `rlib`, `proc-macro`, `bin`,
+    /// ...) to a [`TargetKind`].
+    fn from_cargo_kind(kind: &str) -> Option<Self> {
+        match kind {
+            "lib" | "rlib" | "dylib" | "cdylib" | "staticlib" | "proc-macro" => Some(Self::Lib),
+            "bin" => Some(Self::Bin),
+            "test" => Some(Self::Test),
+            "bench" => Some(Self::Bench),
+            "example" => Some(Self::Example),
+            "custom-build" => Some(Self::CustomBuild),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Self::Lib => "lib",
+            Self::Bin => "bin",
+            Self::Test => "test",
+            Self::Bench => "bench",
+            Self::Example => "example",
+            Self::CustomBuild => "custom-build",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for TargetKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "lib" => Ok(Self::Lib),
+            "bin" => Ok(Self::Bin),
+            "test" => Ok(Self::Test),
+            "bench" => Ok(Self::Bench),
+            "example" => Ok(Self::Example),
+            "custom-build" | "build" => Ok(Self::CustomBuild),
+            _ => Err(format!("Unknown target kind: {}", s)),
+        }
+    }
+}
+
+/// The package and target a diagnostic's file is compiled into.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TargetInfo {
+    pub package: String,
+    pub kind: TargetKind,
+    /// Target name, e.g. the binary name for a `bin` target.
+    pub name: String,
+}
+
+/// A build target of a package.
+#[derive(Debug, Clone)]
+pub struct Target {
+    pub name: String,
+    pub kind: TargetKind,
+    /// Crate root file of the target.
+    pub src_path: PathBuf,
+}
+
+/// A workspace member package.
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    pub manifest_path: PathBuf,
+    pub targets: Vec<Target>,
+}
+
+impl Package {
+    /// Directory containing the package manifest.
+    pub fn root(&self) -> &Path {
+        self.manifest_path.parent().unwrap_or(Path::new(""))
+    }
+
+    /// Diagnostic tag for one of this package's targets.
+    pub fn target_info(&self, target: &Target) -> TargetInfo {
+        TargetInfo {
+            package: self.name.clone(),
+            kind: target.kind,
+            name: target.name.clone(),
+        }
+    }
+}
+
+/// Which workspace packages to analyze.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum PackageSelection {
+    /// Packages at or below the analyzed path, or the package containing it.
+    #[default]
+    Default,
+    /// Every workspace member (`--workspace`).
+    Workspace,
+    /// Named packages (`-p <name>`).
+    Packages(Vec<String>),
+}
+
+/// A Cargo workspace and its member packages.
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub members: Vec<Package>,
+}
+
+#[derive(Deserialize)]
+struct Metadata {
+    packages: Vec<MetadataPackage>,
+    workspace_members: Vec<String>,
+    workspace_root: PathBuf,
+}
+
+#[derive(Deserialize)]
+struct MetadataPackage {
+    id: String,
+    name: String,
+    manifest_path: PathBuf,
+    targets: Vec<MetadataTarget>,
+}
+
+#[derive(Deserialize)]
+struct MetadataTarget {
+    name: String,
+    kind: Vec<String>,
+    src_path: PathBuf,
+}
+
+impl Workspace {
+    /// Load the workspace containing `path`, or `None` if `path` is not inside
+    /// a Cargo project.
+    pub fn load(path: &Path) -> Option<Self> {
+        let path = path.canonicalize().ok()?;
+        let manifest = path
+            .ancestors()
+            .map(|dir| dir.join("Cargo.toml"))
+            .find(|manifest| manifest.is_file())?;
+        Self::from_cargo_metadata(&manifest).or_else(|| Self::from_manifest(&manifest))
+    }
+
+    /// Run `cargo metadata` for `manifest` without touching the network.
+    fn from_cargo_metadata(manifest: &Path) -> Option<Self> {
+        let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+        let output = Command::new(cargo)
+            .args([
+                "metadata",
+                "--format-version",
+                "1",
+                "--no-deps",
+                "--offline",
+            ])
+            .arg("--manifest-path")
+            .arg(manifest)
+            .output()
+            .ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::from_metadata_json(&String::from_utf8_lossy(&output.stdout)).ok()
+    }
+
+    /// Parse the JSON output of `cargo metadata --format-version 1`.
+    pub fn from_metadata_json(json: &str) -> Result<Self> {
+        let metadata: Metadata = serde_json::from_str(json)
+            .map_err(|e| Error::config(format!("Invalid cargo metadata: {}", e)))?;
+        let members = metadata
+            .packages
+            .into_iter()
+            .filter(|p| metadata.workspace_members.contains(&p.id))
+            .map(|p| Package {
+                name: p.name,
+                manifest_path: canonical(p.manifest_path),
+                targets: p
+                    .targets
+                    .into_iter()
+                    .filter_map(|t| {
+                        let kind = t.kind.iter().find_map(|k| TargetKind::from_cargo_kind(k))?;
+                        Some(Target {
+                            name: t.name,
+                            kind,
+                            src_path: canonical(t.src_path),
+                        })
+                    })
+                    .collect(),
+            })
+            .collect();
+        Ok(Self {
+            root: canonical(metadata.workspace_root),
+            members,
+        })
+    }
+
+    /// Build the workspace from manifests alone, following Cargo's target
+    /// auto-discovery. Used when `cargo metadata` is unavailable.
+    fn from_manifest(manifest: &Path) -> Option<Self> {
+        // The workspace root is the nearest ancestor manifest with `[workspace]`.
+        let root_manifest = manifest
+            .ancestors()
+            .skip(1)
+            .map(|dir| dir.join("Cargo.toml"))
+            .filter(|m| m.is_file())
+            .find(|m| read_manifest(m).is_some_and(|t| t.get("workspace").is_some()))
+            .unwrap_or_else(|| manifest.to_path_buf());
+        let root_toml = read_manifest(&root_manifest)?;
+        let root = root_manifest.parent()?.to_path_buf();
+
+        let mut manifests = Vec::new();
+        if root_toml.get("package").is_some() {
+            manifests.push(root_manifest.clone());
+        }
+        let members = root_toml
+            .get("workspace")
+            .and_then(|w| w.get("members"))
+            .and_then(|m| m.as_array())
+            .into_iter()
+            .flatten()
+            .filter_map(|m| m.as_str());
+        for member in members {
+            for dir in expand_member_glob(&root, member) {
+                let member_manifest = dir.join("Cargo.toml");
+                if member_manifest.is_file() && !manifests.contains(&member_manifest) {
+                    manifests.push(member_manifest);
+                }
+            }
+        }
+
+        let members = manifests
+            .iter()
+            .filter_map(|m| package_from_manifest(m))
+            .collect();
+        Some(Self {
+            root: canonical(root),
+            members,
+        })
+    }
+
+    /// Select packages to analyze for `path`.
+    pub fn select(&self, selection: &PackageSelection, path: &Path) -> Result<Vec<&Package>> {
+        match selection {
+            PackageSelection::Workspace => Ok(self.members.iter().collect()),
+            PackageSelection::Packages(names) => names
+                .iter()
+                .map(|name| {
+                    self.members
+                        .iter()
+                        .find(|p| &p.name == name)
+                        .ok_or_else(|| {
+                            let known: Vec<&str> =
+                                self.members.iter().map(|p| p.name.as_str()).collect();
+                            Error::config(format!(
+                                "package `{}` not found in workspace (members: {})",
+                                name,
+                                known.join(", ")
+                            ))
+                        })
+                })
+                .collect(),
+            PackageSelection::Default => {
+                let path = path.canonicalize().map_err(|e| Error::io(path, e))?;
+                // Packages inside the path; failing that, the innermost
+                // package containing it.
+                let inside: Vec<&Package> = self
+                    .members
+                    .iter()
+                    .filter(|p| p.root().starts_with(&path))
+                    .collect();
+                if !inside.is_empty() {
+                    return Ok(inside);
+                }
+                Ok(self
+                    .members
+                    .iter()
+                    .filter(|p| path.starts_with(p.root()))
+                    .max_by_key(|p| p.root().components().count())
+                    .into_iter()
+                    .collect())
+            }
+        }
+    }
+}
+
+fn canonical(path: PathBuf) -> PathBuf {
+    path.canonicalize().unwrap_or(path)
+}
+
+fn read_manifest(path: &Path) -> Option<toml::Table> {
+    std::fs::read_to_string(path).ok()?.parse().ok()
+}
+
+/// Expand a `[workspace] members` entry; only a trailing `*` segment
+/// (`crates/*`) is supported as a glob.
+fn expand_member_glob(root: &Path, member: &str) -> Vec<PathBuf> {
+    match member.strip_suffix("/*") {
+        Some(parent) => std::fs::read_dir(root.join(parent))
+            .map(|entries| {
+                let mut dirs: Vec<PathBuf> = entries
+                    .filter_map(|e| e.ok())
+                    .map(|e| e.path())
+                    .filter(|p| p.is_dir())
+                    .collect();
+                dirs.sort();
+                dirs
+            })
+            .unwrap_or_default(),
+        None => vec![root.join(member)],
+    }
+}
+
+/// Read a package manifest and discover its targets.
+fn package_from_manifest(manifest: &Path) -> Option<Package> {
+    let toml = read_manifest(manifest)?;
+    let package = toml.get("package")?;
+    let name = package.get("name")?.as_str()?.to_string();
+    let dir = manifest.parent()?;
+    let mut targets = Vec::new();
+
+    let mut add = |kind: TargetKind, name: String, src_path: PathBuf| {
+        if src_path.is_file() && !targets.iter().any(|t: &Target| t.src_path == src_path) {
+            targets.push(Target {
+                name,
+                kind,
+                src_path: canonical(src_path),
+            });
+        }
+    };
+
+    // Explicitly declared targets first, so their names win.
+    let lib_path = toml
+        .get("lib")
+        .and_then(|l| l.get("path"))
+        .and_then(|p| p.as_str())
+        .map(|p| dir.join(p))
+        .unwrap_or_else(|| dir.join("src/lib.rs"));
+    add(TargetKind::Lib, name.replace('-', "_"), lib_path);
+    for (section, kind, default_dir) in [
+        ("bin", TargetKind::Bin, "src/bin"),
+        ("test", TargetKind::Test, "tests"),
+        ("bench", TargetKind::Bench, "benches"),
+        ("example", TargetKind::Example, "examples"),
+    ] {
+        let declared = toml.get(section).and_then(|v| v.as_array());
+        for target in declared.into_iter().flatten() {
+            let Some(target_name) = target.get("name").and_then(|n| n.as_str()) else {
+                continue;
+            };
+            let src_path = match target.get("path").and_then(|p| p.as_str()) {
+                Some(p) => dir.join(p),
+                None => dir.join(default_dir).join(format!("{target_name}.rs")),
+            };
+            add(kind, target_name.to_string(), src_path);
+        }
+    }
+
+    // Auto-discovered targets.
+    add(TargetKind::Bin, name.clone(), dir.join("src/main.rs"));
+    for (kind, default_dir) in [
+        (TargetKind::Bin, "src/bin"),
+        (TargetKind::Test, "tests"),
+        (TargetKind::Bench, "benches"),
+        (TargetKind::Example, "examples"),
+    ] {
+        let Ok(entries) = std::fs::read_dir(dir.join(default_dir)) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+        paths.sort();
+        for path in paths {
+            let (target_name, src_path) = if path.is_dir() {
+                (path.file_name(), path.join("main.rs"))
+            } else if path.extension().is_some_and(|e| e == "rs") {
+                (path.file_stem(), path.clone())
+            } else {
+                continue;
+            };
+            if let Some(target_name) = target_name.and_then(|n| n.to_str()) {
+                add(kind, target_name.to_string(), src_path);
+            }
+        }
+    }
+
+    let build = match package.get("build") {
+        Some(toml::Value::String(p)) => Some(dir.join(p)),
+        Some(toml::Value::Boolean(false)) => None,
+        _ => Some(dir.join("build.rs")),
+    };
+    if let Some(build) = build {
+        add(
+            TargetKind::CustomBuild,
+            "build-script-build".to_string(),
+            build,
+        );
+    }
+
+    Some(Package {
+        name,
+        manifest_path: canonical(manifest.to_path_buf()),
+        targets,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn write(root: &Path, rel: &str, content: &str) {
+        let path = root.join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    }
+
+    fn workspace_fixture() -> TempDir {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        write(
+            root,
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"my-core\"\n",
+        );
+        write(root, "crates/core/src/lib.rs", "mod db;");
+        write(root, "crates/core/src/db.rs", "");
+        write(root, "crates/core/tests/it.rs", "");
+        write(root, "crates/core/build.rs", "fn main() {}");
+        write(
+            root,
+            "crates/cli/Cargo.toml",
+            "[package]\nname = \"cli\"\n\n[[bin]]\nname = \"tool\"\npath = \"src/tool.rs\"\n",
+        );
+        write(root, "crates/cli/src/tool.rs", "fn main() {}");
+        write(root, "crates/cli/src/bin/extra.rs", "fn main() {}");
+        tmp
+    }
+
+    fn kinds(package: &Package) -> Vec<(TargetKind, &str)> {
+        let mut kinds: Vec<_> = package
+            .targets
+            .iter()
+            .map(|t| (t.kind, t.name.as_str()))
+            .collect();
+        kinds.sort_by_key(|(k, n)| (k.to_string(), n.to_string()));
+        kinds
+    }
+
+    #[test]
+    fn test_from_manifest_discovers_members_and_targets() {
+        let tmp = workspace_fixture();
+        let ws = Workspace::from_manifest(&tmp.path().join("Cargo.toml")).unwrap();
+        let names: Vec<&str> = ws.members.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["cli", "my-core"]);
+
+        assert_eq!(
+            kinds(&ws.members[1]),
+            vec![
+                (TargetKind::CustomBuild, "build-script-build"),
+                (TargetKind::Lib, "my_core"),
+                (TargetKind::Test, "it"),
+            ]
+        );
+        assert_eq!(
+            kinds(&ws.members[0]),
+            vec![(TargetKind::Bin, "extra"), (TargetKind::Bin, "tool")]
+        );
+    }
+
+    #[test]
+    fn test_from_member_manifest_finds_workspace_root() {
+        let tmp = workspace_fixture();
+        let ws = Workspace::from_manifest(&tmp.path().join("crates/core/Cargo.toml")).unwrap();
+        assert_eq!(ws.members.len(), 2);
+        assert_eq!(ws.root, tmp.path().canonicalize().unwrap());
+    }
+
+    #[test]
+    fn test_select_packages() {
+        let tmp = workspace_fixture();
+        let ws = Workspace::from_manifest(&tmp.path().join("Cargo.toml")).unwrap();
+        let root = tmp.path();
+
+        let all = ws.select(&PackageSelection::Default, root).unwrap();
+        assert_eq!(all.len(), 2);
+
+        let inner = ws
+            .select(&PackageSelection::Default, &root.join("crates/core/src"))
+            .unwrap();
+        assert_eq!(inner.len(), 1);
+        assert_eq!(inner[0].name, "my-core");
+
+        let named = ws
+            .select(
+                &PackageSelection::Packages(vec!["cli".to_string()]),
+                &root.join("crates/core"),
+            )
+            .unwrap();
+        assert_eq!(named[0].name, "cli");
+
+        let err = ws
+            .select(&PackageSelection::Packages(vec!["nope".to_string()]), root)
+            .unwrap_err();
+        assert!(err.to_string().contains("nope"));
+    }
+
+    #[test]
+    fn test_from_metadata_json() {
+        let json = r#"{
+            "packages": [
+                {"id": "a 0.1.0", "name": "a", "manifest_path": "/ws/a/Cargo.toml",
+                 "targets": [
+                    {"name": "a", "kind": ["lib"], "src_path": "/ws/a/src/lib.rs"},
+                    {"name": "derive", "kind": ["proc-macro"], "src_path": "/ws/a/src/derive.rs"},
+                    {"name": "it", "kind": ["test"], "src_path": "/ws/a/tests/it.rs"}
+                 ]},
+                {"id": "dep 1.0.0", "name": "dep", "manifest_path": "/reg/dep/Cargo.toml",
+                 "targets": []}
+            ],
+            "workspace_members": ["a 0.1.0"],
+            "workspace_root": "/ws"
+        }"#;
+        let ws = Workspace::from_metadata_json(json).unwrap();
+        assert_eq!(ws.members.len(), 1);
+        assert_eq!(
+            kinds(&ws.members[0]),
+            vec![
+                (TargetKind::Lib, "a"),
+                (TargetKind::Lib, "derive"),
+                (TargetKind::Test, "it")
+            ]
+        );
+    }
+}
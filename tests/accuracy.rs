@@ -22,7 +22,7 @@
 //! honest.
 
 use cargo_perf::engine::parser::parse_file;
-use cargo_perf::engine::AnalysisContext;
+use cargo_perf::engine::{check_with_rules, AnalysisContext};
 use cargo_perf::rules::registry;
 use cargo_perf::Config;
 use std::collections::{BTreeMap, BTreeSet};
@@ -73,21 +73,20 @@ fn expected_findings(source: &str) -> BTreeSet<Finding> {
     set
 }
 
-/// Run every registered rule over `source`, returning the `(rule_id, line)` of
-/// each diagnostic. Panics if a fixture fails to parse — fixtures must be valid
-/// Rust so that any finding is attributable to a rule, not to a parse gap.
+/// Run every registered rule over `source` as the engine does, with inline
+/// suppressions applied, returning the `(rule_id, line)` of each diagnostic.
+/// Panics if a fixture fails to parse — fixtures must be valid Rust so that any
+/// finding is attributable to a rule, not to a parse gap.
 fn actual_findings(path: &Path, source: &str) -> BTreeSet<Finding> {
     let ast = parse_file(source)
         .unwrap_or_else(|e| panic!("fixture {} must be valid Rust: {e}", path.display()));
     let config = Config::default();
     let ctx = AnalysisContext::new(path, source, &ast, &config);
-    let mut set = BTreeSet::new();
-    for rule in registry::all_rules() {
-        for diag in rule.check(&ctx) {
-            set.insert((diag.rule_id.to_string(), diag.line));
-        }
-    }
-    set
+    let rules = registry::all_rules().iter().map(|rule| rule.as_ref());
+    check_with_rules(&ctx, rules)
+        .into_iter()
+        .map(|diag| (diag.rule_id.to_string(), diag.line))
+        .collect()
 }
 
 /// Parse `// perf-guard: <rule-id>[, ...]` markers: the rule ids this fixture is
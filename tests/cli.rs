@@ -64,6 +64,44 @@ fn test_explain_unknown_rule() {
         .stderr(predicate::str::contains("Unknown rule"));
 }
 
+#[test]
+fn test_explain_json() {
+    let output = cargo_perf()
+        .args(["explain", "regex-in-loop", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let doc: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(doc["id"], "regex-in-loop");
+    assert_eq!(doc["category"], "memory");
+    assert!(doc["good_example"].as_str().unwrap().contains("LazyLock"));
+}
+
+#[test]
+fn test_explain_all_markdown_matches_docs() {
+    let output = cargo_perf()
+        .args(["explain", "--all", "--format", "markdown"])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let reference = String::from_utf8(output.stdout).unwrap();
+    let docs =
+        std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/docs/rules.md")).unwrap();
+    assert_eq!(
+        reference, docs,
+        "docs/rules.md is stale; regenerate it with `cargo perf explain --all --format markdown`"
+    );
+}
+
+#[test]
+fn test_explain_requires_rule_or_all() {
+    cargo_perf().arg("explain").assert().failure();
+    cargo_perf()
+        .args(["explain", "regex-in-loop", "--format", "sarif"])
+        .assert()
+        .failure();
+}
+
 #[test]
 fn test_init_creates_config() {
     let temp = TempDir::new().unwrap();
@@ -299,6 +337,212 @@ async fn bad() {
     assert!(baseline_content.contains("async-block-in-async"));
 }
 
+#[test]
+fn test_baseline_survives_reformatting() {
+    let temp = TempDir::new().unwrap();
+    let code = temp.path().join("code.rs");
+    fs::write(
+        &code,
+        "async fn bad() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n",
+    )
+    .unwrap();
+    cargo_perf()
+        .arg("baseline")
+        .arg(temp.path())
+        .assert()
+        .success();
+
+    // A comment above and the call split across lines
+    fs::write(
+        &code,
+        r#"// Blocks on purpose
+async fn bad() {
+    let unrelated = 1;
+    std::thread::sleep(
+        std::time::Duration::from_secs(1),
+    );
+}
+"#,
+    )
+    .unwrap();
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .arg("--baseline")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("No performance issues found"));
+}
+
+#[test]
+fn test_baseline_migrate() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("code.rs"),
+        "async fn f() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join(".cargo-perf-baseline"),
+        r#"{"version": 1, "entries": [{"fingerprint":
+            {"rule_id": "async-block-in-async", "file_path": "gone.rs", "code_hash": 1},
+            "description": "stale"}]}"#,
+    )
+    .unwrap();
+
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .arg("--baseline")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("cargo perf baseline --migrate"));
+
+    // Updating or pruning would have to convert it, losing entries
+    for flag in ["--update", "--prune"] {
+        cargo_perf()
+            .arg("baseline")
+            .arg(temp.path())
+            .arg(flag)
+            .assert()
+            .failure()
+            .stderr(predicate::str::contains("cargo perf baseline --migrate"));
+    }
+    // A run without the entry's rule cannot tell whether it was fixed
+    cargo_perf()
+        .arg("baseline")
+        .arg(temp.path())
+        .arg("--migrate")
+        .arg("--rules")
+        .arg("vec-no-capacity")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 baseline entries"));
+
+    cargo_perf()
+        .arg("baseline")
+        .arg(temp.path())
+        .arg("--migrate")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Dropped: 1 entries"));
+    let migrated = fs::read_to_string(temp.path().join(".cargo-perf-baseline")).unwrap();
+    assert!(migrated.contains("\"version\": 2"));
+}
+
+#[test]
+fn test_baseline_stale_entries() {
+    let temp = TempDir::new().unwrap();
+    let code = temp.path().join("code.rs");
+    let blocking = |secs: u32| {
+        format!(
+            "async fn f{secs}() {{\n    std::thread::sleep(std::time::Duration::from_secs({secs}));\n}}\n"
+        )
+    };
+    fs::write(&code, blocking(1) + &blocking(2)).unwrap();
+    cargo_perf()
+        .arg("baseline")
+        .arg(temp.path())
+        .assert()
+        .success();
+
+    // Fix one of the two baselined issues
+    fs::write(&code, blocking(1)).unwrap();
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .arg("--baseline")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Stale: 1 baseline entry"));
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .arg("--baseline")
+        .arg("--ratchet")
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("1 stale entry"));
+
+    // Entries of rules that did not run are not stale
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .arg("--baseline")
+        .arg("--ratchet")
+        .arg("--rules")
+        .arg("memory")
+        .assert()
+        .success();
+
+    cargo_perf()
+        .arg("baseline")
+        .arg(temp.path())
+        .arg("--prune")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Removed: 1 stale entries"));
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .arg("--baseline")
+        .arg("--ratchet")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("Stale").not());
+}
+
+#[test]
+fn test_baseline_owner_and_expiry() {
+    let temp = TempDir::new().unwrap();
+    let code = temp.path().join("code.rs");
+    fs::write(
+        &code,
+        "async fn f() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n",
+    )
+    .unwrap();
+    cargo_perf()
+        .arg("baseline")
+        .arg(temp.path())
+        .arg("--owner")
+        .arg("storage-team")
+        .arg("--ticket")
+        .arg("PERF-42")
+        .arg("--expires")
+        .arg("2000-01-31")
+        .assert()
+        .success();
+
+    // The entry has expired, so its finding is reported again
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .arg("--baseline")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("async-block-in-async"))
+        .stderr(predicate::str::contains("Expired: 1 baseline entry"));
+
+    cargo_perf()
+        .arg("baseline")
+        .arg(temp.path())
+        .arg("--report")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "storage-team (1 entry, 1 expired)",
+        ))
+        .stdout(predicate::str::contains("ticket PERF-42"));
+
+    cargo_perf()
+        .arg("baseline")
+        .arg(temp.path())
+        .arg("--expires")
+        .arg("soon")
+        .assert()
+        .failure();
+}
+
 #[test]
 fn test_check_with_baseline_flag() {
     let temp = TempDir::new().unwrap();
@@ -343,6 +587,78 @@ async fn slow() {
     assert_eq!(code, after);
 }
 
+#[test]
+fn test_fix_removes_unused_suppressions() {
+    let temp = TempDir::new().unwrap();
+    let code = "fn first(names: &[String]) -> Option<String> {\n    // cargo-perf-ignore: clone-in-loop\n    names.first().cloned()\n}\n";
+    fs::write(temp.path().join("lib.rs"), code).unwrap();
+
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unused-suppression"))
+        .stdout(predicate::str::contains(
+            "did you mean `clone-in-hot-loop`?",
+        ));
+
+    cargo_perf().arg("fix").arg(temp.path()).assert().success();
+    let fixed = fs::read_to_string(temp.path().join("lib.rs")).unwrap();
+    assert_eq!(
+        fixed,
+        "fn first(names: &[String]) -> Option<String> {\n    names.first().cloned()\n}\n"
+    );
+}
+
+#[test]
+fn test_require_suppression_reasons() {
+    let temp = TempDir::new().unwrap();
+    let code = r#"pub fn copy_all(names: &[String]) -> Vec<String> {
+    let mut copies = Vec::with_capacity(names.len());
+    for name in names {
+        // cargo-perf-ignore: clone-in-hot-loop
+        copies.push(name.clone());
+    }
+    copies
+}
+"#;
+    fs::write(temp.path().join("lib.rs"), code).unwrap();
+
+    cargo_perf()
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unused-suppression").not());
+
+    fs::write(
+        temp.path().join("cargo-perf.toml"),
+        "[rules.unused-suppression]\nrequire-reason = true\n",
+    )
+    .unwrap();
+    cargo_perf()
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "Suppression comment gives no reason",
+        ));
+
+    fs::write(
+        temp.path().join("lib.rs"),
+        code.replace("hot-loop\n", "hot-loop -- reason: few names\n"),
+    )
+    .unwrap();
+    cargo_perf()
+        .current_dir(temp.path())
+        .arg("check")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("unused-suppression").not());
+}
+
 #[test]
 fn test_default_command_is_check() {
     let temp = TempDir::new().unwrap();
@@ -368,3 +684,366 @@ async fn bad() {
 // Note: The "cargo perf" invocation handling is tested via actual cargo
 // invocation, not by passing "perf" as first arg to the binary directly.
 // The re-parsing logic in main.rs handles args from cargo's invocation path.
+
+#[test]
+fn test_check_reuses_cache_for_unchanged_files() {
+    let temp = TempDir::new().unwrap();
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "[package]\nname = \"demo\"\n",
+    )
+    .unwrap();
+    fs::create_dir(temp.path().join("src")).unwrap();
+    fs::write(
+        temp.path().join("src/lib.rs"),
+        "async fn bad() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n",
+    )
+    .unwrap();
+
+    let run = || {
+        cargo_perf()
+            .env_remove("CARGO_TARGET_DIR")
+            .arg("check")
+            .arg(temp.path())
+            .arg("--timing")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains("async-block-in-async"))
+    };
+
+    run().stderr(predicate::str::contains("(0 from cache)"));
+    assert!(temp
+        .path()
+        .join("target/cargo-perf/analysis-cache.json")
+        .is_file());
+    run().stderr(predicate::str::contains("(1 from cache)"));
+
+    cargo_perf()
+        .env_remove("CARGO_TARGET_DIR")
+        .arg("check")
+        .arg(temp.path())
+        .arg("--timing")
+        .arg("--no-cache")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("(0 from cache)"));
+}
+
+/// A two-package workspace whose blocking calls sit in a library module, a
+/// binary, a file no target reaches, and a test fixture.
+fn workspace_with_dead_files() -> TempDir {
+    const BLOCKING: &str =
+        "pub async fn bad() {\n    std::thread::sleep(std::time::Duration::from_secs(1));\n}\n";
+    let temp = TempDir::new().unwrap();
+    let write = |rel: &str, content: &str| {
+        let path = temp.path().join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+    write("Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n");
+    write(
+        "crates/core/Cargo.toml",
+        "[package]\nname = \"core-lib\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write("crates/core/src/lib.rs", "pub mod db;\n");
+    write("crates/core/src/db.rs", BLOCKING);
+    write("crates/core/src/dead.rs", BLOCKING);
+    write("crates/core/tests/fixtures/input.rs", BLOCKING);
+    write(
+        "crates/app/Cargo.toml",
+        "[package]\nname = \"app\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write(
+        "crates/app/src/main.rs",
+        &format!("fn main() {{}}\n{BLOCKING}"),
+    );
+    temp
+}
+
+#[test]
+fn test_check_workspace_skips_files_outside_targets() {
+    let temp = workspace_with_dead_files();
+
+    cargo_perf()
+        .env_remove("CARGO_TARGET_DIR")
+        .arg("--format")
+        .arg("json")
+        .arg("--no-cache")
+        .arg("--path")
+        .arg(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("db.rs"))
+        .stdout(predicate::str::contains(r#""package": "core-lib""#))
+        .stdout(predicate::str::contains(r#""kind": "lib""#))
+        .stdout(predicate::str::contains(r#""package": "app""#))
+        .stdout(predicate::str::contains(r#""kind": "bin""#))
+        .stdout(predicate::str::contains("dead.rs").not())
+        .stdout(predicate::str::contains("fixtures").not());
+}
+
+#[test]
+fn test_check_package_selection() {
+    let temp = workspace_with_dead_files();
+
+    cargo_perf()
+        .env_remove("CARGO_TARGET_DIR")
+        .arg("--format")
+        .arg("json")
+        .arg("--no-cache")
+        .arg("-p")
+        .arg("app")
+        .arg("--path")
+        .arg(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""package": "app""#))
+        .stdout(predicate::str::contains("core-lib").not());
+
+    // Inside a member, only that package is analyzed by default
+    cargo_perf()
+        .env_remove("CARGO_TARGET_DIR")
+        .arg("--format")
+        .arg("json")
+        .arg("--no-cache")
+        .arg("--path")
+        .arg(temp.path().join("crates/core"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""package": "core-lib""#))
+        .stdout(predicate::str::contains(r#""package": "app""#).not());
+
+    cargo_perf()
+        .arg("--no-cache")
+        .arg("-p")
+        .arg("missing")
+        .arg("--path")
+        .arg(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("package `missing` not found"));
+}
+
+fn write_blocking_and_format_in_loop(dir: &std::path::Path) {
+    fs::write(
+        dir.join("mixed.rs"),
+        r#"
+async fn bad() {
+    std::thread::sleep(std::time::Duration::from_secs(1));
+}
+
+fn labels(items: &[u32]) -> Vec<String> {
+    let mut out = Vec::with_capacity(items.len());
+    for i in items {
+        out.push(format!("item-{}", i));
+    }
+    out
+}
+"#,
+    )
+    .unwrap();
+}
+
+#[test]
+fn test_check_rules_selection() {
+    let temp = TempDir::new().unwrap();
+    write_blocking_and_format_in_loop(temp.path());
+
+    cargo_perf()
+        .arg("--rules")
+        .arg("allocation")
+        .arg("--path")
+        .arg(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("format-in-loop"))
+        .stdout(predicate::str::contains("async-block-in-async").not());
+
+    cargo_perf()
+        .arg("check")
+        .arg(temp.path())
+        .arg("--exclude-rules")
+        .arg("*-in-loop")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("async-block-in-async"))
+        .stdout(predicate::str::contains("format-in-loop").not());
+
+    cargo_perf()
+        .arg("--rules")
+        .arg("no-such-rule")
+        .arg("--path")
+        .arg(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("no-such-rule"));
+}
+
+#[test]
+fn test_check_output_format_from_config() {
+    let temp = TempDir::new().unwrap();
+    write_blocking_and_format_in_loop(temp.path());
+    fs::write(
+        temp.path().join("cargo-perf.toml"),
+        "[output]\nformat = \"sarif\"\ncolor = \"never\"\n",
+    )
+    .unwrap();
+
+    cargo_perf()
+        .current_dir(temp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("sarif-schema-2.1.0.json"));
+
+    // The command line overrides the config file
+    cargo_perf()
+        .current_dir(temp.path())
+        .arg("--format")
+        .arg("json")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(r#""rule_id": "format-in-loop""#))
+        .stdout(predicate::str::contains("sarif-schema").not());
+
+    fs::write(
+        temp.path().join("cargo-perf.toml"),
+        "[output]\nformat = \"xml\"\n",
+    )
+    .unwrap();
+    cargo_perf()
+        .current_dir(temp.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("output.format"));
+}
+
+#[test]
+fn test_check_overrides_by_path_and_target() {
+    const CLONE_IN_LOOP: &str =
+        "pub fn f(data: &[String]) {\n    for s in data {\n        let _ = s.clone();\n    }\n}\n";
+    let temp = TempDir::new().unwrap();
+    let write = |rel: &str, content: &str| {
+        let path = temp.path().join(rel);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+    };
+    write(
+        "Cargo.toml",
+        "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+    );
+    write("src/lib.rs", &format!("pub mod hot_path;\n{CLONE_IN_LOOP}"));
+    write("src/hot_path/mod.rs", CLONE_IN_LOOP);
+    write("tests/it.rs", CLONE_IN_LOOP);
+    write(
+        "cargo-perf.toml",
+        r#"
+[[overrides]]
+targets = ["test", "bench"]
+rules = { clone-in-hot-loop = "allow" }
+
+[[overrides]]
+paths = ["src/hot_path"]
+rules = { clone-in-hot-loop = "deny" }
+"#,
+    );
+
+    let output = cargo_perf()
+        .env_remove("CARGO_TARGET_DIR")
+        .args(["--format", "json", "--no-cache", "--path"])
+        .arg(temp.path())
+        .output()
+        .unwrap();
+    let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let mut found: Vec<(String, String)> = diagnostics
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| {
+            let path = std::path::Path::new(d["file_path"].as_str().unwrap());
+            let relative = path.strip_prefix(temp.path()).unwrap();
+            (
+                relative.to_string_lossy().replace('\\', "/"),
+                d["severity"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    found.sort();
+    assert_eq!(
+        found,
+        vec![
+            ("src/hot_path/mod.rs".to_string(), "error".to_string()),
+            ("src/lib.rs".to_string(), "warning".to_string()),
+        ]
+    );
+}
+
+fn git(dir: &std::path::Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?}", args);
+}
+
+#[test]
+fn test_check_changed_since_and_staged() {
+    const OLD: &str = "pub fn old(data: &[String]) {\n    for s in data {\n        let _ = s.clone();\n    }\n}\n";
+    const NEW: &str = "\npub fn new(data: &[String]) {\n    for s in data {\n        let _ = s.clone();\n    }\n}\n";
+    let temp = TempDir::new().unwrap();
+    fs::write(temp.path().join("lib.rs"), OLD).unwrap();
+    git(temp.path(), &["init", "-q"]);
+    git(temp.path(), &["add", "."]);
+    git(temp.path(), &["commit", "-q", "-m", "init"]);
+    fs::write(temp.path().join("lib.rs"), format!("{OLD}{NEW}")).unwrap();
+    fs::write(temp.path().join("untracked.rs"), OLD).unwrap();
+
+    let lines = |args: &[&str]| -> Vec<(String, u64)> {
+        let output = cargo_perf()
+            .env_remove("CARGO_TARGET_DIR")
+            .args(["--format", "json", "--no-cache", "check"])
+            .arg(temp.path())
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{:?}", output);
+        let diagnostics: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+        let mut found: Vec<(String, u64)> = diagnostics
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|d| {
+                let path = std::path::Path::new(d["file_path"].as_str().unwrap());
+                let name = path.file_name().unwrap().to_string_lossy().to_string();
+                (name, d["line"].as_u64().unwrap())
+            })
+            .collect();
+        found.sort();
+        found
+    };
+
+    assert_eq!(lines(&[]).len(), 3);
+    assert_eq!(
+        lines(&["--changed-since", "HEAD"]),
+        vec![("lib.rs".to_string(), 9), ("untracked.rs".to_string(), 3)]
+    );
+    assert!(lines(&["--staged"]).is_empty());
+    git(temp.path(), &["add", "lib.rs"]);
+    assert_eq!(lines(&["--staged"]), vec![("lib.rs".to_string(), 9)]);
+
+    cargo_perf()
+        .args(["--fail-on", "warning", "check"])
+        .arg(temp.path())
+        .args(["--changed-since", "HEAD", "--no-cache"])
+        .assert()
+        .failure();
+    cargo_perf()
+        .args(["check"])
+        .arg(temp.path())
+        .args(["--changed-since", "no-such-rev", "--no-cache"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("git:"));
+}
@@ -0,0 +1,11 @@
+// perf-guard: async-block-in-async
+// Negative: the sync helper hands its blocking read to `spawn_blocking` inside a
+// closure, so calling the helper from async code never blocks the worker.
+// Following the helper must not descend into the offloaded closure body.
+fn spawn_load() -> tokio::task::JoinHandle<String> {
+    tokio::task::spawn_blocking(|| std::fs::read_to_string("config.toml").unwrap_or_default())
+}
+
+async fn handler() -> String {
+    spawn_load().await.unwrap_or_default()
+}
@@ -0,0 +1,40 @@
+// perf-guard: unused-suppression, clone-in-hot-loop
+// Negative: each suppression and expectation silences a real finding, so
+// none of them is unused and the suppressed clones are not reported either.
+fn copy_all(names: &[String]) -> Vec<String> {
+    let mut copies = Vec::with_capacity(names.len());
+    for name in names {
+        // cargo-perf-ignore: clone-in-hot-loop
+        copies.push(name.clone());
+    }
+    copies
+}
+
+#[allow(cargo_perf::clone_in_hot_loop)]
+fn copy_each(names: &[String]) {
+    for name in names {
+        consume(name.clone());
+    }
+}
+
+#[expect(cargo_perf::clone_in_hot_loop, reason = "names are short")]
+fn copy_each_expected(names: &[String]) {
+    for name in names {
+        consume(name.clone());
+    }
+}
+
+fn copy_generated(names: &[String]) -> Vec<String> {
+    let mut copies = Vec::with_capacity(names.len() * 2);
+    // cargo-perf-ignore-start: clone-in-hot-loop
+    for name in names {
+        copies.push(name.clone());
+    }
+    // cargo-perf-ignore-end
+    for name in names {
+        copies.push(name.clone()); // cargo-perf-ignore: clone-in-hot-loop
+    }
+    copies
+}
+
+fn consume(_: String) {}
@@ -0,0 +1,14 @@
+// Positive: the async handler never names `std::fs` itself, but the sync helper
+// it calls reaches `std::fs::read_to_string` two frames down. The runtime worker
+// still blocks, so the call in the async fn is reported with the chain.
+fn load_config() -> String {
+    read_raw("config.toml")
+}
+
+fn read_raw(path: &str) -> String {
+    std::fs::read_to_string(path).unwrap_or_default()
+}
+
+async fn handler() -> usize {
+    load_config().len() // perf-expect: async-block-in-async
+}
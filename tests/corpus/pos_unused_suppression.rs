@@ -0,0 +1,26 @@
+// Positive: the loop the first comment once silenced was refactored away, and
+// the second comment misspells `clone-in-hot-loop`, so neither suppresses
+// anything and the clone it was meant for is still reported. The `#[expect]`
+// outlived its loop the same way, as did the trailing comment.
+fn first_name(names: &[String]) -> Option<String> {
+    // cargo-perf-ignore: clone-in-hot-loop // perf-expect: unused-suppression
+    names.first().cloned()
+}
+
+fn copy_all(names: &[String]) -> Vec<String> {
+    let mut copies = Vec::with_capacity(names.len());
+    for name in names {
+        // cargo-perf-ignore: clone-in-loop // perf-expect: unused-suppression
+        copies.push(name.clone()); // perf-expect: clone-in-hot-loop
+    }
+    copies
+}
+
+#[expect(cargo_perf::clone_in_hot_loop)] // perf-expect: unused-suppression
+fn last_name(names: &[String]) -> Option<String> {
+    names.last().cloned()
+}
+
+fn count(names: &[String]) -> usize {
+    names.len() // cargo-perf-ignore: clone-in-hot-loop // perf-expect: unused-suppression
+}
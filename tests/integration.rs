@@ -331,3 +331,42 @@ fn test_skips_non_rust_files() {
         diagnostics
     );
 }
+
+/// Test that imports are resolved through re-exports in other modules
+#[test]
+fn test_cross_file_reexport_resolution() {
+    let temp_dir = tempfile::tempdir().expect("Create temp dir");
+    let src_dir = temp_dir.path().join("project").join("src");
+    std::fs::create_dir_all(&src_dir).expect("Create src dir");
+
+    std::fs::write(src_dir.join("lib.rs"), "mod util;\nmod api;\n").expect("Write lib.rs");
+    std::fs::write(
+        src_dir.join("util.rs"),
+        "pub use std::fs::read_to_string as slurp;\n",
+    )
+    .expect("Write util.rs");
+    std::fs::write(
+        src_dir.join("api.rs"),
+        r#"
+use crate::util::slurp;
+
+async fn load_config() -> String {
+    slurp("config.toml").unwrap()
+}
+"#,
+    )
+    .expect("Write api.rs");
+
+    let config = Config::default();
+    let diagnostics = analyze(&src_dir, &config).expect("Analysis should succeed");
+
+    assert!(
+        diagnostics
+            .iter()
+            .any(|d| d.rule_id == "async-block-in-async"
+                && d.file_path.ends_with("api.rs")
+                && d.line == 5),
+        "Should resolve `slurp` to std::fs::read_to_string: {:?}",
+        diagnostics
+    );
+}